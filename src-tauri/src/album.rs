@@ -13,20 +13,28 @@ use serde::{Deserialize, Serialize};
 use tauri::{async_runtime, AppHandle, Wry};
 
 use crate::{
+    artifacts::{
+        album_meta_dir, album_thumb_dir, artifacts_missing, detect_read_only, ensure_writable,
+        relocate_album_artifacts, root_id,
+    },
     constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS},
-    duplicates::compute_hash_for_path,
+    duplicates::{compute_hash_for_path, migrate_album_ignores, migrate_moved_media_ignores},
+    library::{record_opened, settings_for},
     metadata::{
         get_file_metadata_cached, get_metadata_with_favorite, read_album_meta,
         transfer_media_metadata_batch_caller_holds_lock,
         transfer_media_metadata_entry_caller_holds_lock, DetachedAlbum, DetachedMediaEntry,
     },
     preload::{
-        artifacts_missing, drop_preload_for_path, enqueue_preload, preload_dir, set_active_root,
+        drop_preload_for_path, enqueue_preload, focus_album, preload_dir, register_root,
         start_preloader_worker, CURRENT_PRELOAD_CANCEL, PRELOADED, PRELOAD_QUEUE,
     },
     settings::read_settings,
-    thumb::{ensure_thumb, ensure_thumb_with_settings},
-    util::{has_extension, STORE_WRITE_LOCK},
+    thumb::{
+        ensure_thumb, ensure_thumb_with_settings, media_preview_path, media_thumb_path,
+        media_view_path, rename_thumb_artifacts,
+    },
+    util::{has_extension, move_file, STORE_WRITE_LOCK},
 };
 
 #[derive(Clone)]
@@ -99,7 +107,7 @@ fn is_album_dir(path: &Path) -> bool {
             .unwrap_or(false)
 }
 
-fn normalized_relative_path(path: &Path) -> String {
+pub(crate) fn normalized_relative_path(path: &Path) -> String {
     path.to_string_lossy().replace('\\', "/")
 }
 
@@ -167,14 +175,7 @@ fn media_files_for_album(path: &Path) -> Result<Vec<PathBuf>, String> {
         .map_err(|e| e.to_string())?
         .filter_map(|e| e.ok())
         .map(|e| e.path())
-        .filter(|p| {
-            if let Some(ext) = p.extension().and_then(|s| s.to_str()) {
-                if ext.eq_ignore_ascii_case("heic") {
-                    return false;
-                }
-            }
-            has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS)
-        })
+        .filter(|p| has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS))
         .collect::<Vec<_>>();
 
     Ok(files)
@@ -228,6 +229,12 @@ pub struct FavoriteDetachedMediaEntry {
     pub album_name: String,
     pub album_id: String,
     pub favorite: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -240,33 +247,31 @@ pub struct IncomingFile {
     pub data: Option<Vec<u8>>,
 }
 
-#[tauri::command]
-pub fn get_albums_detached(
-    app: AppHandle<Wry>,
-    root_dir: String,
-) -> Result<Vec<DetachedAlbum>, String> {
-    let root = PathBuf::from(&root_dir);
+fn list_root_albums(root: &Path) -> Result<Vec<DetachedAlbum>, String> {
     if !root.is_dir() {
         return Err(format!("{} is not a directory", root.display()));
     }
-    set_active_root(&root);
+    register_root(root);
+    record_opened(root);
+    detect_read_only(root);
+    let root_id = root_id(root);
 
     let mut albums = Vec::new();
-    let entries = walk_album_entries(&root)?;
+    let entries = walk_album_entries(root)?;
     let media_by_entry: Vec<Vec<PathBuf>> = entries
         .iter()
         .map(|entry| media_files_for_album(&entry.path))
         .collect::<Result<_, _>>()?;
 
     for (idx, entry) in entries.iter().enumerate() {
-        let thumb_dir = entry.path.join(".room237-thumb");
-        fs::create_dir_all(&thumb_dir).map_err(|e| e.to_string())?;
+        let thumb_dir = album_thumb_dir(&entry.path);
+        if let Err(e) = fs::create_dir_all(&thumb_dir) {
+            log::warn!("failed to create {}: {}", thumb_dir.display(), e);
+        }
 
         let thumb_files: Vec<_> = fs::read_dir(&thumb_dir)
-            .map_err(|e| e.to_string())?
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .collect();
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
 
         let media_files = &media_by_entry[idx];
 
@@ -274,11 +279,10 @@ pub fn get_albums_detached(
         let thumb = if !thumb_files.is_empty() {
             Some(thumb_files[0].to_string_lossy().into_owned())
         } else if let Some(first) = media_files.first() {
-            Some(
-                ensure_thumb(first, &thumb_dir)?
-                    .to_string_lossy()
-                    .into_owned(),
-            )
+            ensure_thumb(first, &thumb_dir)
+                .map_err(|e| log::warn!("album thumb failed {}: {}", first.display(), e))
+                .ok()
+                .map(|t| t.to_string_lossy().into_owned())
         } else {
             let descendant_prefix = format!("{}/", entry.relative_path);
             let mut fallback_thumb: Option<String> = None;
@@ -289,10 +293,11 @@ pub fn get_albums_detached(
                     break;
                 }
                 if let Some(first) = child_media.first() {
-                    let child_thumb_dir = child_entry.path.join(".room237-thumb");
-                    fs::create_dir_all(&child_thumb_dir).map_err(|e| e.to_string())?;
-                    let thumb_path = ensure_thumb(first, &child_thumb_dir)?;
-                    fallback_thumb = Some(thumb_path.to_string_lossy().into_owned());
+                    let child_thumb_dir = album_thumb_dir(&child_entry.path);
+                    let _ = fs::create_dir_all(&child_thumb_dir);
+                    fallback_thumb = ensure_thumb(first, &child_thumb_dir)
+                        .ok()
+                        .map(|t| t.to_string_lossy().into_owned());
                     break;
                 }
             }
@@ -310,15 +315,40 @@ pub fn get_albums_detached(
             size: files,
             relative_path: entry.relative_path.clone(),
             parent: entry.parent.clone(),
+            root_id: root_id.clone(),
         });
 
         enqueue_preload(&entry.path);
     }
 
-    start_preloader_worker(app.clone());
-
     albums.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
-    log::info!("listed {} albums", albums.len());
+    log::info!("listed {} albums in {}", albums.len(), root.display());
+    Ok(albums)
+}
+
+#[tauri::command]
+pub fn get_albums_detached(
+    app: AppHandle<Wry>,
+    root_dir: String,
+) -> Result<Vec<DetachedAlbum>, String> {
+    let albums = list_root_albums(Path::new(&root_dir))?;
+    start_preloader_worker(app);
+    Ok(albums)
+}
+
+#[tauri::command]
+pub fn get_library_tree(
+    app: AppHandle<Wry>,
+    root_dirs: Vec<String>,
+) -> Result<Vec<DetachedAlbum>, String> {
+    let mut albums = Vec::new();
+    for root_dir in root_dirs {
+        match list_root_albums(Path::new(&root_dir)) {
+            Ok(listed) => albums.extend(listed),
+            Err(e) => log::warn!("skipping library root {}: {}", root_dir, e),
+        }
+    }
+    start_preloader_worker(app);
     Ok(albums)
 }
 
@@ -335,6 +365,7 @@ pub async fn get_album_media(
             return Err(format!("{} is not a directory", dir.display()));
         }
 
+        focus_album(&dir);
         let mut preloaded = PRELOADED.lock().unwrap();
         if artifacts_missing(&dir) {
             preloaded.remove(&dir);
@@ -343,7 +374,7 @@ pub async fn get_album_media(
         drop(preloaded);
 
         if needs_preload {
-            if let Some(cancel) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
+            if let Some((_, cancel)) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
                 cancel.store(true, Ordering::Relaxed);
                 log::info!("preload cancelled for {}", dir.display());
             }
@@ -351,7 +382,7 @@ pub async fn get_album_media(
             PRELOADED.lock().unwrap().insert(dir.clone());
             {
                 let mut q = PRELOAD_QUEUE.lock().unwrap();
-                q.retain(|p| p != &dir);
+                q.remove(&dir);
             }
             start_preloader_worker(app_cloned.clone());
         }
@@ -361,14 +392,7 @@ pub async fn get_album_media(
             .map_err(|e| e.to_string())?
             .filter_map(|e| e.ok())
             .map(|e| e.path())
-            .filter(|p| {
-                if let Some(ext) = p.extension().and_then(|s| s.to_str()) {
-                    if ext.eq_ignore_ascii_case("heic") {
-                        return false;
-                    }
-                }
-                has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS)
-            })
+            .filter(|p| has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS))
             .collect();
 
         let mut entries: Vec<DetachedMediaEntry> = Vec::new();
@@ -385,6 +409,9 @@ pub async fn get_album_media(
                         meta: meta_str,
                         name: name.clone(),
                         favorite: if entry.favorite { Some(true) } else { None },
+                        thumb: media_thumb_path(path),
+                        view: media_view_path(path),
+                        preview: media_preview_path(path),
                     });
                     used_cached = true;
                 }
@@ -422,6 +449,9 @@ pub async fn get_album_media(
                         .get(&name)
                         .map(|e| if e.favorite { Some(true) } else { None })
                         .unwrap_or(None),
+                    thumb: media_thumb_path(path),
+                    view: media_view_path(path),
+                    preview: media_preview_path(path),
                 });
             }
         }
@@ -459,7 +489,7 @@ pub fn list_favorites(
             continue;
         }
 
-        let thumb_dir = album.path.join(".room237-thumb");
+        let thumb_dir = album_thumb_dir(&album.path);
         let _ = fs::create_dir_all(&thumb_dir);
 
         for (name, entry) in album_meta.files.iter() {
@@ -493,6 +523,9 @@ pub fn list_favorites(
                 album_name: album.relative_path.clone(),
                 album_id: album.relative_path.clone(),
                 favorite: true,
+                thumb: media_thumb_path(&media_path),
+                view: media_view_path(&media_path),
+                preview: media_preview_path(&media_path),
             });
         }
     }
@@ -529,6 +562,7 @@ pub fn rename_album(
     let normalized_root = root
         .canonicalize()
         .map_err(|e| format!("Failed to resolve root: {e}"))?;
+    ensure_writable(&normalized_root)?;
 
     let target = normalized_root.join(Path::new(&album_id));
     let normalized_target = target
@@ -582,6 +616,7 @@ pub fn rename_album(
     }
 
     fs::rename(&normalized_target, &new_path).map_err(|e| e.to_string())?;
+    relocate_album_artifacts(&normalized_target, &new_path);
 
     drop_preload_for_path(&normalized_target);
 
@@ -613,6 +648,7 @@ pub fn rename_album(
         .strip_prefix(&normalized_root)
         .map_err(|e| e.to_string())
         .map(normalized_relative_path)?;
+    migrate_album_ignores(&normalized_root, &old_relative_path, &new_relative_path);
     let parent_relative = if parent_dir == normalized_root {
         None
     } else {
@@ -652,6 +688,7 @@ pub fn move_album(
     let normalized_root = root
         .canonicalize()
         .map_err(|e| format!("Failed to resolve root: {e}"))?;
+    ensure_writable(&normalized_root)?;
 
     let target = normalized_root.join(Path::new(&album_id));
     let normalized_target = target
@@ -723,6 +760,7 @@ pub fn move_album(
     }
 
     fs::rename(&normalized_target, &new_path).map_err(|e| e.to_string())?;
+    relocate_album_artifacts(&normalized_target, &new_path);
     drop_preload_for_path(&normalized_target);
 
     let old_relative_path = normalized_target
@@ -733,6 +771,7 @@ pub fn move_album(
         .strip_prefix(&normalized_root)
         .map_err(|e| e.to_string())
         .map(normalized_relative_path)?;
+    migrate_album_ignores(&normalized_root, &old_relative_path, &new_relative_path);
     let parent_relative = if normalized_parent == normalized_root {
         None
     } else {
@@ -790,39 +829,20 @@ fn move_one_media(
         return Err("file clash".into());
     }
 
-    let (src_thumb_dir, src_meta_dir) = (
-        source_dir.join(".room237-thumb"),
-        source_dir.join(".room237-metadata"),
-    );
-    let (tgt_thumb_dir, tgt_meta_dir) = (
-        target_dir.join(".room237-thumb"),
-        target_dir.join(".room237-metadata"),
-    );
+    let (src_thumb_dir, src_meta_dir) = (album_thumb_dir(source_dir), album_meta_dir(source_dir));
+    let (tgt_thumb_dir, tgt_meta_dir) = (album_thumb_dir(target_dir), album_meta_dir(target_dir));
 
     if move_artifacts {
         let _guard = STORE_WRITE_LOCK.lock().unwrap();
 
-        transfer_media_metadata_entry_caller_holds_lock(
+        let split = transfer_media_metadata_entry_caller_holds_lock(
             source_dir.as_path(),
             source_name,
             target_dir.as_path(),
             &target_name,
         )?;
 
-        let thumb_name = {
-            let mut os = media_name.file_name().unwrap().to_os_string();
-            os.push(".webp");
-            PathBuf::from(os)
-        };
-        if src_thumb_dir.join(&thumb_name).exists() {
-            fs::create_dir_all(&tgt_thumb_dir).map_err(|e| e.to_string())?;
-            let mut tgt_name = thumb_name.clone();
-            tgt_name.set_file_name(format!("{}.webp", target_name));
-            let _ = fs::rename(
-                src_thumb_dir.join(&thumb_name),
-                tgt_thumb_dir.join(&tgt_name),
-            );
-        }
+        rename_thumb_artifacts(&src_thumb_dir, &tgt_thumb_dir, source_name, &target_name);
 
         let meta_name = {
             let mut os = media_name.file_name().unwrap().to_os_string();
@@ -833,16 +853,27 @@ fn move_one_media(
             fs::create_dir_all(&tgt_meta_dir).map_err(|e| e.to_string())?;
             let mut tgt_meta = meta_name.clone();
             tgt_meta.set_file_name(format!("{}.meta", target_name));
-            let _ = fs::rename(src_meta_dir.join(&meta_name), tgt_meta_dir.join(&tgt_meta));
+            let _ = fs::remove_file(tgt_meta_dir.join(&tgt_meta));
+            let _ = move_file(
+                &src_meta_dir.join(&meta_name),
+                &tgt_meta_dir.join(&tgt_meta),
+            );
         }
 
-        fs::rename(&source_file, &target_file).map_err(|e| e.to_string())?;
+        move_file(&source_file, &target_file).map_err(|e| e.to_string())?;
+        drop(_guard);
+        migrate_moved_media_ignores(
+            source_dir,
+            target_dir,
+            &[(source_name.to_string(), target_name.clone())],
+            split,
+        );
     } else {
         log::info!(
             "Skipped moving thumbnails/metadata for {} due to settings",
             media
         );
-        fs::rename(&source_file, &target_file).map_err(|e| e.to_string())?;
+        move_file(&source_file, &target_file).map_err(|e| e.to_string())?;
     }
 
     log::info!("move {} → {}", source_file.display(), target_file.display());
@@ -859,6 +890,8 @@ pub fn move_media(source: String, target: String, media: String) -> Result<Strin
     if !(source_dir.is_dir() && target_dir.is_dir()) {
         return Err("bad dirs".into());
     }
+    ensure_writable(&source_dir)?;
+    ensure_writable(&target_dir)?;
     move_one_media(&source_dir, &target_dir, &media, move_artifacts).map(|()| "ok".into())
 }
 
@@ -871,6 +904,8 @@ fn move_media_batch_blocking(
     if !(source_dir.is_dir() && target_dir.is_dir()) {
         return Err("bad dirs".into());
     }
+    ensure_writable(&source_dir)?;
+    ensure_writable(&target_dir)?;
 
     if move_artifacts && !media.is_empty() {
         let mut moves: Vec<(String, String)> = Vec::with_capacity(media.len());
@@ -884,17 +919,18 @@ fn move_media_batch_blocking(
             moves.push((source_name.to_string(), target_name));
         }
 
-        let src_thumb = source_dir.join(".room237-thumb");
-        let tgt_thumb = target_dir.join(".room237-thumb");
-        let src_meta_dir = source_dir.join(".room237-metadata");
-        let tgt_meta_dir = target_dir.join(".room237-metadata");
+        let src_thumb = album_thumb_dir(&source_dir);
+        let tgt_thumb = album_thumb_dir(&target_dir);
+        let src_meta_dir = album_meta_dir(&source_dir);
+        let tgt_meta_dir = album_meta_dir(&target_dir);
 
         let _guard = STORE_WRITE_LOCK.lock().unwrap();
-        transfer_media_metadata_batch_caller_holds_lock(&source_dir, &target_dir, &moves)?;
+        let mut split =
+            transfer_media_metadata_batch_caller_holds_lock(&source_dir, &target_dir, &moves)?;
 
         let mut failed = Vec::new();
         for (i, name) in media.iter().enumerate() {
-            let (_source_name, target_name) = &moves[i];
+            let (source_name, target_name) = &moves[i];
             let media_name = PathBuf::from(name);
             let source_file = source_dir.join(&media_name);
             let target_file = target_dir.join(&target_name);
@@ -904,17 +940,7 @@ fn move_media_batch_blocking(
                 continue;
             }
 
-            let thumb_name = {
-                let mut os = media_name.file_name().unwrap().to_os_string();
-                os.push(".webp");
-                PathBuf::from(os)
-            };
-            if src_thumb.join(&thumb_name).exists() {
-                let _ = fs::create_dir_all(&tgt_thumb);
-                let mut tgt_name = thumb_name.clone();
-                tgt_name.set_file_name(format!("{}.webp", target_name));
-                let _ = fs::rename(src_thumb.join(&thumb_name), tgt_thumb.join(&tgt_name));
-            }
+            rename_thumb_artifacts(&src_thumb, &tgt_thumb, source_name, target_name);
 
             let meta_name = {
                 let mut os = media_name.file_name().unwrap().to_os_string();
@@ -925,12 +951,16 @@ fn move_media_batch_blocking(
                 let _ = fs::create_dir_all(&tgt_meta_dir);
                 let mut tgt_meta = meta_name.clone();
                 tgt_meta.set_file_name(format!("{}.meta", target_name));
-                let _ = fs::rename(src_meta_dir.join(&meta_name), tgt_meta_dir.join(&tgt_meta));
+                let _ = fs::remove_file(tgt_meta_dir.join(&tgt_meta));
+                let _ = move_file(
+                    &src_meta_dir.join(&meta_name),
+                    &tgt_meta_dir.join(&tgt_meta),
+                );
             }
 
-            if let Err(e) = fs::rename(&source_file, &target_file) {
+            if let Err(e) = move_file(&source_file, &target_file) {
                 log::warn!(
-                    "move_media_batch: rename failed {} → {}: {}",
+                    "move_media_batch: move failed {} → {}: {}",
                     source_file.display(),
                     target_file.display(),
                     e
@@ -953,7 +983,21 @@ fn move_media_batch_blocking(
                 &source_dir,
                 &rollback,
             );
+            for group in split.iter_mut() {
+                group
+                    .moved
+                    .retain(|f| !rollback.iter().any(|(tgt, _)| tgt == f));
+            }
+            split.retain(|group| !group.moved.is_empty());
         }
+        drop(_guard);
+        let moved: Vec<(String, String)> = media
+            .iter()
+            .zip(moves)
+            .filter(|(name, _)| !failed.contains(name))
+            .map(|(_, m)| m)
+            .collect();
+        migrate_moved_media_ignores(&source_dir, &target_dir, &moved, split);
         Ok(failed)
     } else {
         let failed: Vec<String> = media
@@ -1028,7 +1072,7 @@ fn add_media_files_blocking(
         return Err(format!("{} is not a directory", album_path.display()));
     }
 
-    fs::create_dir_all(album_path.join(".room237-thumb")).ok();
+    fs::create_dir_all(album_thumb_dir(&album_path)).ok();
 
     let mut copied_files: Vec<String> = Vec::new();
 
@@ -1079,7 +1123,7 @@ fn add_media_files_blocking(
     }
 
     let album_meta = read_album_meta(&album_path);
-    let settings = read_settings();
+    let settings = settings_for(&album_path);
 
     let added: Vec<DetachedMediaEntry> = copied_files
         .par_iter()
@@ -1090,7 +1134,7 @@ fn add_media_files_blocking(
                 return None;
             }
 
-            let thumb_dir = album_path.join(".room237-thumb");
+            let thumb_dir = album_thumb_dir(&album_path);
             let _ = ensure_thumb_with_settings(&path, &thumb_dir, &settings);
 
             let meta_str = match get_file_metadata_cached(&path) {
@@ -1121,6 +1165,9 @@ fn add_media_files_blocking(
                 meta: meta_str,
                 name: path.file_name().unwrap().to_string_lossy().into_owned(),
                 favorite,
+                thumb: media_thumb_path(&path),
+                view: media_view_path(&path),
+                preview: media_preview_path(&path),
             })
         })
         .collect();
@@ -1134,6 +1181,7 @@ pub async fn add_media_files(
     dir: String,
     files: Vec<IncomingFile>,
 ) -> Result<Vec<DetachedMediaEntry>, String> {
+    ensure_writable(Path::new(&dir))?;
     async_runtime::spawn_blocking(move || add_media_files_blocking(dir, files))
         .await
         .map_err(|e| e.to_string())?
@@ -0,0 +1,289 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    album::walk_album_paths,
+    library::{forced_read_only, library_root_for, library_roots},
+    preload::{drop_preload_for_path, registered_roots, root_for},
+    settings::{read_settings, SettingsState},
+    util::{move_file, STORE_WRITE_LOCK},
+};
+use once_cell::sync::{Lazy, OnceCell};
+use sha2::{Digest, Sha256};
+use tauri::{path::BaseDirectory, AppHandle, Manager, Wry};
+
+pub const THUMB_DIR: &str = ".room237-thumb";
+pub const META_DIR: &str = ".room237-metadata";
+const CACHE_DIR: &str = "artifacts";
+const WRITE_PROBE: &str = ".room237-write-probe";
+
+static CACHE_ROOT: OnceCell<PathBuf> = OnceCell::new();
+static READ_ONLY_ROOTS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+pub fn init_cache_root(app: &AppHandle<Wry>) -> Result<(), String> {
+    let path = app
+        .path()
+        .resolve(CACHE_DIR, BaseDirectory::AppLocalData)
+        .map_err(|e| e.to_string())?;
+    let _ = CACHE_ROOT.set(path);
+    Ok(())
+}
+
+pub fn root_id(root: &Path) -> String {
+    let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.to_string_lossy().as_bytes());
+    hex::encode(hasher.finalize())[0..16].to_string()
+}
+
+fn external_album_dir_in(root: &Path, dir: &Path) -> Option<PathBuf> {
+    let cache = CACHE_ROOT.get()?;
+    let rel = dir.strip_prefix(root).ok()?;
+    Some(cache.join(root_id(root)).join(rel))
+}
+
+fn cached_root_for(dir: &Path) -> Option<PathBuf> {
+    let cache = CACHE_ROOT.get()?;
+    dir.ancestors()
+        .find(|a| cache.join(root_id(a)).is_dir())
+        .map(Path::to_path_buf)
+}
+
+fn external_album_dir(dir: &Path) -> Option<PathBuf> {
+    let root = root_for(dir)
+        .or_else(|| library_root_for(dir))
+        .or_else(|| cached_root_for(dir))?;
+    external_album_dir_in(&root, dir)
+}
+
+fn probe_writable(dir: &Path) -> bool {
+    let readonly = fs::metadata(dir)
+        .map(|m| m.permissions().readonly())
+        .unwrap_or(true);
+    if readonly {
+        return false;
+    }
+    let probe = dir.join(WRITE_PROBE);
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+pub(crate) fn detect_read_only(root: &Path) -> bool {
+    let read_only = forced_read_only(root) || !probe_writable(root);
+    let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut roots = READ_ONLY_ROOTS.lock().unwrap();
+    if read_only {
+        if roots.insert(root.to_path_buf()) {
+            log::info!("opening {} read-only", root.display());
+        }
+        roots.insert(canonical);
+    } else {
+        roots.remove(root);
+        roots.remove(&canonical);
+    }
+    read_only
+}
+
+pub fn is_read_only(dir: &Path) -> bool {
+    forced_read_only(dir)
+        || READ_ONLY_ROOTS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|root| dir.starts_with(root))
+}
+
+pub(crate) fn ensure_writable(dir: &Path) -> Result<(), String> {
+    if is_read_only(dir) {
+        return Err(format!(
+            "Library containing {} is opened read-only",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+fn artifacts_base(dir: &Path) -> PathBuf {
+    if read_settings().storage.external_cache || is_read_only(dir) {
+        if let Some(base) = external_album_dir(dir) {
+            return base;
+        }
+    }
+    dir.to_path_buf()
+}
+
+pub fn album_thumb_dir(dir: &Path) -> PathBuf {
+    artifacts_base(dir).join(THUMB_DIR)
+}
+
+pub fn album_meta_dir(dir: &Path) -> PathBuf {
+    artifacts_base(dir).join(META_DIR)
+}
+
+pub(crate) fn in_tree_meta_dir(dir: &Path) -> PathBuf {
+    dir.join(META_DIR)
+}
+
+pub fn media_thumb_dir(path: &Path) -> Option<PathBuf> {
+    path.parent().map(album_thumb_dir)
+}
+
+pub(crate) fn artifacts_missing(dir: &Path) -> bool {
+    let base = artifacts_base(dir);
+    !base.join(THUMB_DIR).exists() || !base.join(META_DIR).exists()
+}
+
+pub(crate) fn relocate_album_artifacts(old_dir: &Path, new_dir: &Path) {
+    if !read_settings().storage.external_cache && !is_read_only(new_dir) {
+        return;
+    }
+    let (Some(old), Some(new)) = (external_album_dir(old_dir), external_album_dir(new_dir)) else {
+        return;
+    };
+    if old == new || !old.exists() {
+        return;
+    }
+    if let Some(parent) = new.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(e) = fs::rename(&old, &new) {
+        log::warn!(
+            "failed to relocate artifacts {}→{}: {}",
+            old.display(),
+            new.display(),
+            e
+        );
+    }
+}
+
+pub(crate) fn relocate_root_cache(old_id: &str, new_root: &Path) -> Result<(), String> {
+    move_root_cache(old_id, &root_id(new_root))
+        .map_err(|e| format!("{} for {}", e, new_root.display()))
+}
+
+pub(crate) fn move_root_cache(old_id: &str, new_id: &str) -> Result<(), String> {
+    let Some(cache) = CACHE_ROOT.get() else {
+        return Ok(());
+    };
+    let (old, new) = (cache.join(old_id), cache.join(new_id));
+    if old_id == new_id || !old.exists() {
+        return Ok(());
+    }
+    if new.exists() {
+        return Err("Artifact cache already exists".to_string());
+    }
+    fs::rename(&old, &new).map_err(|e| e.to_string())?;
+    log::info!("relocated artifact cache {} → {}", old_id, new_id);
+    Ok(())
+}
+
+fn move_artifact_dir(src: &Path, dst: &Path) -> Result<u64, String> {
+    if !src.is_dir() {
+        return Ok(0);
+    }
+    fs::create_dir_all(dst).map_err(|e| e.to_string())?;
+    let mut moved = 0;
+    for entry in fs::read_dir(src).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let target = dst.join(entry.file_name());
+        let _ = fs::remove_file(&target);
+        match move_file(&path, &target) {
+            Ok(()) => moved += 1,
+            Err(e) => log::warn!("failed to migrate {}: {}", path.display(), e),
+        }
+    }
+    let _ = fs::remove_dir(src);
+    Ok(moved)
+}
+
+fn migrate_root(root: &Path, to_external: bool) -> Result<u64, String> {
+    let mut moved = 0;
+    for album in walk_album_paths(root)? {
+        let Some(external) = external_album_dir_in(root, &album) else {
+            continue;
+        };
+        let (src, dst) = if to_external {
+            (album.clone(), external)
+        } else {
+            (external, album.clone())
+        };
+        for name in [THUMB_DIR, META_DIR] {
+            moved += move_artifact_dir(&src.join(name), &dst.join(name))?;
+        }
+    }
+    Ok(moved)
+}
+
+#[tauri::command]
+pub fn migrate_artifacts(
+    state: tauri::State<SettingsState>,
+    root_dir: String,
+    to_external: bool,
+) -> Result<u64, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    if CACHE_ROOT.get().is_none() {
+        return Err("Artifact cache directory is not available".into());
+    }
+    ensure_writable(&root)?;
+
+    let mut roots = vec![root.clone()];
+    for other in registered_roots().into_iter().chain(library_roots()) {
+        let nested = roots
+            .iter()
+            .any(|r| other.starts_with(r) || r.starts_with(&other));
+        if other.is_dir() && !nested && !is_read_only(&other) {
+            roots.push(other);
+        }
+    }
+
+    let mut moved = 0;
+    {
+        let _guard = STORE_WRITE_LOCK.lock().unwrap();
+        moved += migrate_root(&root, to_external)?;
+        for other in &roots[1..] {
+            match migrate_root(other, to_external) {
+                Ok(count) => moved += count,
+                Err(e) => log::warn!("failed to migrate artifacts for {}: {}", other.display(), e),
+            }
+        }
+    }
+
+    let mut settings = state.get();
+    settings.storage.external_cache = to_external;
+    state.update(settings)?;
+    for root in &roots {
+        drop_preload_for_path(root);
+    }
+    let destination = if to_external { "cache" } else { "albums" };
+    log::info!(
+        "migrated {} artifacts to {} for {} libraries",
+        moved,
+        destination,
+        roots.len()
+    );
+    Ok(moved)
+}
+
+#[tauri::command]
+pub fn is_library_read_only(root_dir: String) -> Result<bool, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    Ok(detect_read_only(&root))
+}
@@ -1,2 +1,8 @@
-pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "avif", "gif", "bmp", "heic"];
+pub const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "webp", "avif", "gif", "bmp", "heic", "heif",
+];
 pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "mkv", "webm", "avi", "flv", "m4v"];
+pub const VIEW_TRANSCODE_EXTENSIONS: &[&str] = &["heic", "heif"];
+pub const FFMPEG_DECODE_EXTENSIONS: &[&str] = &["heic", "heif", "avif"];
+pub const VIDEO_HASH_POINTS: &[f64] = &[0.1, 0.3, 0.5, 0.7, 0.9];
+pub const ANIMATED_PREVIEW_EXTENSIONS: &[&str] = &["gif", "webp"];
@@ -1,14 +1,29 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashSet,
+    fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
 use rayon::prelude::*;
-use tauri::{AppHandle, Wry};
+use serde::Serialize;
+use tauri::{async_runtime, AppHandle, Wry};
 
 use crate::{
     album::walk_album_paths,
-    constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS},
-    metadata::{get_file_metadata, read_album_meta, write_album_meta},
-    thumb::ensure_thumb,
-    util::{has_extension, heic_to_jpeg},
+    artifacts::{album_meta_dir, album_thumb_dir, ensure_writable},
+    constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS, VIEW_TRANSCODE_EXTENSIONS},
+    duplicates::invalidate_duplicate_index,
+    jobs::{JobHandle, JobKind},
+    metadata::{
+        get_file_metadata, probe, read_album_meta, remove_file_meta_entries,
+        stale_legacy_meta_files, write_album_meta,
+    },
+    thumb::{
+        ensure_thumb, is_truncated_artifact, lock_is_stale, remove_thumb_artifacts,
+        thumb_artifact_sources,
+    },
+    util::{has_extension, newer_than},
 };
 
 #[tauri::command]
@@ -17,33 +32,50 @@ pub async fn rebuild_thumbnails(root_dir: String) -> Result<u64, String> {
     if !root.is_dir() {
         return Err(format!("{} is not a directory", root.display()));
     }
+    ensure_writable(&root)?;
+
+    let job = JobHandle::start(JobKind::RebuildThumbnails, None, Some(&root));
+    async_runtime::spawn_blocking(move || {
+        let result = rebuild_thumbnails_job(&root, &job);
+        job.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
+fn rebuild_thumbnails_job(root: &Path, job: &JobHandle) -> Result<u64, String> {
     let mut written = 0_u64;
 
-    for album in walk_album_paths(&root)? {
-        let thumb_dir = album.join(".room237-thumb");
+    let albums = walk_album_paths(root)?;
+    for (done, album) in albums.iter().enumerate() {
+        job.checkpoint()?;
+        job.progress(done, albums.len());
+        let thumb_dir = album_thumb_dir(album);
         if thumb_dir.exists() {
             fs::remove_dir_all(&thumb_dir).map_err(|e| e.to_string())?;
         }
         fs::create_dir_all(&thumb_dir).map_err(|e| e.to_string())?;
 
-        let media: Vec<_> = fs::read_dir(&album)
+        let media: Vec<_> = fs::read_dir(album)
             .map_err(|e| e.to_string())?
             .filter_map(|e| e.ok())
             .map(|e| e.path())
-            .filter(|p| {
-                if has_extension(p, &["heic"]) {
-                    return false;
-                }
-                has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS)
-            })
+            .filter(|p| has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS))
             .collect();
 
         written += media
             .par_iter()
-            .map(|p| ensure_thumb(p, &thumb_dir).map(|_| 1_u64).unwrap_or(0))
+            .map(|p| match ensure_thumb(p, &thumb_dir) {
+                Ok(_) => 1_u64,
+                Err(e) => {
+                    job.error(format!("{}: {}", p.display(), e));
+                    0
+                }
+            })
             .sum::<u64>();
     }
+    job.progress(albums.len(), albums.len());
 
     Ok(written)
 }
@@ -55,18 +87,27 @@ pub async fn rebuild_metadata(app: AppHandle<Wry>, root_dir: String) -> Result<u
     if !root.is_dir() {
         return Err(format!("{} is not a directory", root.display()));
     }
+    ensure_writable(&root)?;
+
+    let job = JobHandle::start(JobKind::RebuildMetadata, None, Some(&root));
+    async_runtime::spawn_blocking(move || {
+        let result = rebuild_metadata_job(&root, &job);
+        job.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
+fn rebuild_metadata_job(root: &Path, job: &JobHandle) -> Result<u64, String> {
     let mut written = 0_u64;
 
-    for album in walk_album_paths(&root)? {
-        for entry in fs::read_dir(&album).map_err(|e| e.to_string())? {
+    let albums = walk_album_paths(root)?;
+    for (done, album) in albums.iter().enumerate() {
+        job.progress(done, albums.len());
+        for entry in fs::read_dir(album).map_err(|e| e.to_string())? {
+            job.checkpoint()?;
             let path = entry.map_err(|e| e.to_string())?.path();
-            if has_extension(&path, &["heic"]) {
-                let mut jpeg = path.clone();
-                jpeg.set_extension("jpeg");
-                let _ = heic_to_jpeg(&path, &jpeg);
-            }
-
             if !has_extension(&path, IMAGE_EXTENSIONS) && !has_extension(&path, VIDEO_EXTENSIONS) {
                 continue;
             }
@@ -75,6 +116,7 @@ pub async fn rebuild_metadata(app: AppHandle<Wry>, root_dir: String) -> Result<u
             written += 1;
         }
     }
+    job.progress(albums.len(), albums.len());
 
     Ok(written)
 }
@@ -85,16 +127,18 @@ pub fn reset_duplicates(root_dir: String) -> Result<u64, String> {
     if !root.is_dir() {
         return Err(format!("{} is not a directory", root.display()));
     }
+    ensure_writable(&root)?;
 
     let mut removed = 0_u64;
 
     for album in walk_album_paths(&root)? {
         let mut meta = read_album_meta(&album);
-        if !meta.duplicates_ignore.is_empty() {
-            meta.duplicates_ignore.clear();
+        if !meta.ignore_groups.is_empty() {
+            meta.ignore_groups.clear();
             if let Err(e) = write_album_meta(&album, &meta) {
                 log::warn!("failed to clear ignores for {}: {}", album.display(), e);
             } else {
+                invalidate_duplicate_index(&album);
                 removed += 1;
             }
         }
@@ -109,11 +153,12 @@ pub fn clear_room237_artifacts(root_dir: String) -> Result<u64, String> {
     if !root.is_dir() {
         return Err(format!("{} is not a directory", root.display()));
     }
+    ensure_writable(&root)?;
 
     let mut cleared = 0_u64;
 
     for dir in walk_album_paths(&root)? {
-        let meta_dir = dir.join(".room237-metadata");
+        let meta_dir = album_meta_dir(&dir);
         if meta_dir.exists() {
             if let Err(e) = fs::remove_dir_all(&meta_dir) {
                 log::warn!("failed to remove {}: {}", meta_dir.display(), e);
@@ -131,7 +176,7 @@ pub fn clear_room237_artifacts(root_dir: String) -> Result<u64, String> {
             }
         }
 
-        let thumb_dir = dir.join(".room237-thumb");
+        let thumb_dir = album_thumb_dir(&dir);
         if thumb_dir.exists() {
             if let Err(e) = fs::remove_dir_all(&thumb_dir) {
                 log::warn!("failed to remove {}: {}", thumb_dir.display(), e);
@@ -141,8 +186,7 @@ pub fn clear_room237_artifacts(root_dir: String) -> Result<u64, String> {
         }
     }
 
-    for extra in [".room237-metadata", ".room237-thumb"] {
-        let path = root.join(extra);
+    for path in [album_meta_dir(&root), album_thumb_dir(&root)] {
         if path.exists() {
             let _ = fs::remove_dir_all(&path);
         }
@@ -150,3 +194,293 @@ pub fn clear_room237_artifacts(root_dir: String) -> Result<u64, String> {
 
     Ok(cleared)
 }
+
+const CHECK_SAMPLE_LIMIT: usize = 20;
+
+#[derive(Serialize, Default, Clone, Debug)]
+pub struct CheckCategory {
+    pub found: u64,
+    pub fixed: u64,
+    pub samples: Vec<String>,
+}
+
+impl CheckCategory {
+    fn note(&mut self, path: &Path) {
+        self.found += 1;
+        if self.samples.len() < CHECK_SAMPLE_LIMIT {
+            self.samples.push(path.to_string_lossy().into_owned());
+        }
+    }
+
+    fn repair(&mut self, job: &JobHandle, path: &Path, result: std::io::Result<()>) {
+        match result {
+            Ok(()) => self.fixed += 1,
+            Err(e) => job.error(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+#[derive(Serialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryCheckReport {
+    pub albums: u64,
+    pub orphan_thumbs: CheckCategory,
+    pub missing_entries: CheckCategory,
+    pub stale_locks: CheckCategory,
+    pub legacy_meta: CheckCategory,
+    pub truncated_thumbs: CheckCategory,
+}
+
+#[tauri::command]
+pub async fn check_library(root_dir: String, fix: bool) -> Result<LibraryCheckReport, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let job = JobHandle::start(JobKind::CheckLibrary, None, Some(&root));
+    async_runtime::spawn_blocking(move || {
+        let result = check_library_job(&root, fix, &job);
+        job.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn check_library_job(
+    root: &Path,
+    fix: bool,
+    job: &JobHandle,
+) -> Result<LibraryCheckReport, String> {
+    let mut report = LibraryCheckReport::default();
+
+    let albums = walk_album_paths(root)?;
+    for (done, album) in albums.iter().enumerate() {
+        job.checkpoint()?;
+        job.progress(done, albums.len());
+        check_album(album, fix, job, &mut report)?;
+        report.albums += 1;
+    }
+    job.progress(albums.len(), albums.len());
+
+    log::info!(
+        "library check {} fix={}: orphans={} missing={} locks={} legacy={} truncated={}",
+        root.display(),
+        fix,
+        report.orphan_thumbs.found,
+        report.missing_entries.found,
+        report.stale_locks.found,
+        report.legacy_meta.found,
+        report.truncated_thumbs.found
+    );
+    Ok(report)
+}
+
+fn check_album(
+    album: &Path,
+    fix: bool,
+    job: &JobHandle,
+    report: &mut LibraryCheckReport,
+) -> Result<(), String> {
+    let present: HashSet<String> = fs::read_dir(album)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+
+    let thumb_dir = album_thumb_dir(album);
+    let thumb_files: Vec<PathBuf> = fs::read_dir(&thumb_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for path in &thumb_files {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.ends_with(".lock") {
+            if lock_is_stale(path) {
+                report.stale_locks.note(path);
+                if fix {
+                    report.stale_locks.repair(job, path, fs::remove_file(path));
+                }
+            }
+            continue;
+        }
+        let sources = thumb_artifact_sources(name);
+        if sources.is_empty() {
+            continue;
+        }
+        if !sources.iter().any(|s| present.contains(*s)) {
+            report.orphan_thumbs.note(path);
+            if fix {
+                report
+                    .orphan_thumbs
+                    .repair(job, path, fs::remove_file(path));
+            }
+        } else if is_truncated_artifact(path) {
+            report.truncated_thumbs.note(path);
+            if fix {
+                report
+                    .truncated_thumbs
+                    .repair(job, path, fs::remove_file(path));
+            }
+        }
+    }
+
+    let meta = read_album_meta(album);
+    let missing: Vec<String> = meta
+        .files
+        .keys()
+        .filter(|name| !present.contains(*name))
+        .cloned()
+        .collect();
+    for name in &missing {
+        report.missing_entries.note(&album.join(name));
+    }
+    if fix && !missing.is_empty() {
+        match remove_file_meta_entries(album, &missing) {
+            Ok(()) => {
+                invalidate_duplicate_index(album);
+                report.missing_entries.fixed += missing.len() as u64;
+            }
+            Err(e) => job.error(format!("{}: {}", album.display(), e)),
+        }
+    }
+
+    for path in stale_legacy_meta_files(album) {
+        report.legacy_meta.note(&path);
+        if fix {
+            report
+                .legacy_meta
+                .repair(job, &path, fs::remove_file(&path));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HeicSidecarReport {
+    pub sidecars: Vec<String>,
+    pub skipped: Vec<String>,
+    pub removed: u64,
+}
+
+fn has_camera_exif(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return false;
+    };
+    [exif::Tag::Make, exif::Tag::Model]
+        .iter()
+        .any(|tag| exif.get_field(*tag, exif::In::PRIMARY).is_some())
+}
+
+fn is_generated_sidecar(original: &Path, sidecar: &Path) -> bool {
+    if !newer_than(sidecar, original).unwrap_or(false) || has_camera_exif(sidecar) {
+        return false;
+    }
+    let dims = |p: &Path| match probe(&p.to_string_lossy(), false) {
+        Ok((_, Some(w), Some(h))) => Some((w, h)),
+        _ => None,
+    };
+    match (dims(original), dims(sidecar)) {
+        (Some((w, h)), Some(sidecar_dims)) => sidecar_dims == (w, h) || sidecar_dims == (h, w),
+        _ => false,
+    }
+}
+
+#[tauri::command]
+pub async fn remove_heic_sidecars(
+    root_dir: String,
+    dry_run: bool,
+) -> Result<HeicSidecarReport, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    if !dry_run {
+        ensure_writable(&root)?;
+    }
+
+    async_runtime::spawn_blocking(move || remove_heic_sidecars_blocking(&root, dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn remove_heic_sidecars_blocking(root: &Path, dry_run: bool) -> Result<HeicSidecarReport, String> {
+    let mut report = HeicSidecarReport::default();
+
+    for album in walk_album_paths(root)? {
+        let originals: Vec<PathBuf> = fs::read_dir(&album)
+            .map_err(|e| e.to_string())?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && has_extension(p, VIEW_TRANSCODE_EXTENSIONS))
+            .collect();
+        if originals.is_empty() {
+            continue;
+        }
+
+        let mut meta = read_album_meta(&album);
+        let mut meta_changed = false;
+        let thumb_dir = album_thumb_dir(&album);
+
+        for original in originals {
+            let mut sidecar = original.clone();
+            sidecar.set_extension("jpeg");
+            if !sidecar.is_file() {
+                continue;
+            }
+            let (Some(original_name), Some(sidecar_name)) = (
+                original.file_name().and_then(|n| n.to_str()),
+                sidecar.file_name().and_then(|n| n.to_str()),
+            ) else {
+                continue;
+            };
+            if !is_generated_sidecar(&original, &sidecar) {
+                report.skipped.push(sidecar.to_string_lossy().into_owned());
+                continue;
+            }
+            report.sidecars.push(sidecar.to_string_lossy().into_owned());
+            if dry_run {
+                continue;
+            }
+
+            if let Err(e) = trash::delete(&sidecar) {
+                log::warn!("failed to trash {}: {}", sidecar.display(), e);
+                continue;
+            }
+            remove_thumb_artifacts(&thumb_dir, sidecar_name);
+            report.removed += 1;
+            log::info!("trashed heic sidecar {}", sidecar.display());
+
+            if let Some(sidecar_entry) = meta.files.remove(sidecar_name) {
+                let entry = meta.files.entry(original_name.to_string()).or_default();
+                entry.favorite |= sidecar_entry.favorite;
+                meta_changed = true;
+            }
+            meta_changed |= meta
+                .rename_ignored(|name| (name == sidecar_name).then(|| original_name.to_string()));
+        }
+
+        if meta_changed {
+            if let Err(e) = write_album_meta(&album, &meta) {
+                log::warn!("failed to update metadata for {}: {}", album.display(), e);
+            }
+        }
+    }
+
+    Ok(report)
+}
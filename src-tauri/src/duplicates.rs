@@ -1,57 +1,250 @@
 use std::{
+    cmp::Ordering,
     collections::{HashMap, HashSet},
     fs,
+    io::{self, Read},
     path::{Path, PathBuf},
+    process::Command,
+    sync::{atomic, Arc, Mutex},
+    thread,
+    time::{Duration, UNIX_EPOCH},
 };
 
 use base64::Engine;
-use img_hash::{HasherConfig, ImageHash};
-use tauri::{AppHandle, Wry};
+use image::DynamicImage;
+use img_hash::{Hasher, HasherConfig, ImageHash};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Wry};
 use tokio::task;
 
 use crate::{
-    constants::IMAGE_EXTENSIONS,
+    album::{normalized_relative_path, walk_album_paths},
+    artifacts::{album_meta_dir, album_thumb_dir, ensure_writable, media_thumb_dir},
+    constants::{FFMPEG_DECODE_EXTENSIONS, IMAGE_EXTENSIONS, VIDEO_EXTENSIONS, VIDEO_HASH_POINTS},
+    jobs::{JobHandle, JobKind},
+    library::settings_for,
     metadata::{
-        mark_hash_failed, read_album_meta, write_album_file_hash, write_album_meta, AlbumMeta,
+        datetime_original, file_meta, mark_hash_failed, probe_duration, read_album_meta,
+        remove_file_meta_entries, set_media_favorite, set_media_timestamp, write_album_file_hash,
+        write_album_meta, write_album_video_hash, write_file_digests, AlbumMeta, FileDigest,
+        IgnoreGroup, SplitIgnore,
     },
-    preload::{wait_for_album_hashes, TaskPriority},
-    settings::{read_settings, HashConfig},
-    thumb::{ensure_thumb_with_settings, thumb_path},
-    util::has_extension,
+    preload::{preload_app, root_for, wait_for_album_hashes, TaskPriority},
+    settings::{DuplicatesSettings, HashAlgorithm, HashConfig},
+    thumb::{
+        ensure_thumb_with_settings, ensure_view_transcode, remove_thumb_artifacts, thumb_path,
+    },
+    util::{ffmpeg_timeout, has_extension, run_command_with_timeout},
 };
 
-fn load_ignore_set(dir: &Path) -> HashSet<(String, String)> {
-    let album = read_album_meta(dir);
-    album
-        .duplicates_ignore
-        .into_iter()
-        .map(|[a, b]| if a <= b { (a, b) } else { (b, a) })
-        .collect()
+#[derive(Default)]
+struct IgnoreSet {
+    groups: HashMap<String, Vec<u32>>,
+    next: u32,
+}
+
+impl IgnoreSet {
+    fn add<I: IntoIterator<Item = String>>(&mut self, files: I) {
+        let id = self.next;
+        self.next += 1;
+        for file in files {
+            self.groups.entry(file).or_default().push(id);
+        }
+    }
+
+    fn contains(&self, a: &str, b: &str) -> bool {
+        match (self.groups.get(a), self.groups.get(b)) {
+            (Some(ga), Some(gb)) => ga.iter().any(|g| gb.contains(g)),
+            _ => false,
+        }
+    }
+}
+
+fn load_ignore_set(dir: &Path) -> IgnoreSet {
+    let mut set = IgnoreSet::default();
+    for group in read_album_meta(dir).ignore_groups {
+        set.add(group.files);
+    }
+    set
+}
+
+struct CachedIgnores {
+    ids: Vec<String>,
+    set: Arc<IgnoreSet>,
+}
+
+static IGNORE_CACHE: Lazy<Mutex<HashMap<PathBuf, CachedIgnores>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cached_ignore_set(dir: &Path) -> Arc<IgnoreSet> {
+    if let Some(cached) = IGNORE_CACHE.lock().unwrap().get(dir) {
+        return cached.set.clone();
+    }
+    let groups = read_album_meta(dir).ignore_groups;
+    let ids = groups.iter().map(|g| g.id.clone()).collect();
+    let mut set = IgnoreSet::default();
+    for group in groups {
+        set.add(group.files);
+    }
+    let set = Arc::new(set);
+    IGNORE_CACHE.lock().unwrap().insert(
+        dir.to_path_buf(),
+        CachedIgnores {
+            ids,
+            set: set.clone(),
+        },
+    );
+    set
 }
 
-fn save_ignore_set(dir: &Path, set: &HashSet<(String, String)>) -> Result<(), String> {
+pub(crate) fn note_ignore_groups(dir: &Path, groups: &[IgnoreGroup]) {
+    let mut cache = IGNORE_CACHE.lock().unwrap();
+    let stale = cache
+        .get(dir)
+        .map(|cached| !cached.ids.iter().eq(groups.iter().map(|g| &g.id)))
+        .unwrap_or(false);
+    if stale {
+        cache.remove(dir);
+    }
+}
+
+fn add_ignore_group(dir: &Path, files: Vec<String>) -> Result<(), String> {
     let mut album: AlbumMeta = read_album_meta(dir);
-    album.duplicates_ignore = set.iter().map(|(a, b)| [a.clone(), b.clone()]).collect();
+    if album.add_ignore_group(files).is_none() {
+        return Ok(());
+    }
     write_album_meta(dir, &album)
 }
 
-fn pair_key(a: &str, b: &str) -> (String, String) {
-    if a <= b {
-        (a.to_string(), b.to_string())
+const PARTIAL_DIGEST_BYTES: u64 = 64 * 1024;
+const SIMILAR_DEFAULT_LIMIT: usize = 50;
+const DUPLICATE_INDEX_FILE: &str = "duplicates.json";
+
+fn image_hasher(cfg: &HashConfig) -> Hasher {
+    let config = HasherConfig::new()
+        .hash_size(cfg.size.0, cfg.size.1)
+        .resize_filter(cfg.resize_filter.to_filter_type())
+        .hash_alg(cfg.alg.to_img_hash_alg());
+    if cfg.alg.uses_dct() {
+        config.preproc_dct().to_hasher()
+    } else {
+        config.to_hasher()
+    }
+}
+
+// Two Haar levels keep only the LL band, i.e. 4x4 block averages, thresholded by the median.
+fn wavelet_hash(img: &DynamicImage, cfg: &HashConfig) -> Result<ImageHash, String> {
+    let (w, h) = cfg.size;
+    let gray = img
+        .resize_exact(w * 4, h * 4, cfg.resize_filter.to_filter_type())
+        .to_luma8();
+    let mut ll = Vec::with_capacity((w * h) as usize);
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = 0u32;
+            for dy in 0..4 {
+                for dx in 0..4 {
+                    sum += gray.get_pixel(x * 4 + dx, y * 4 + dy)[0] as u32;
+                }
+            }
+            ll.push(sum);
+        }
+    }
+    let mut sorted = ll.clone();
+    sorted.sort_unstable();
+    let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0);
+    let mut bytes = vec![0u8; ll.len().div_ceil(8)];
+    for (i, v) in ll.iter().enumerate() {
+        if *v > median {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    ImageHash::from_bytes(&bytes).map_err(|_| "invalid wavelet hash".to_string())
+}
+
+fn color_moments(img: &DynamicImage) -> Vec<u8> {
+    let rgb = img.thumbnail(64, 64).to_rgb8();
+    let n = (rgb.width() * rgb.height()).max(1) as f64;
+    let mut out = Vec::with_capacity(9);
+    for c in 0..3 {
+        let mean = rgb.pixels().map(|p| p[c] as f64).sum::<f64>() / n;
+        let (mut m2, mut m3) = (0.0, 0.0);
+        for p in rgb.pixels() {
+            let d = p[c] as f64 - mean;
+            m2 += d * d;
+            m3 += d * d * d;
+        }
+        let std = (m2 / n).sqrt();
+        let skew = (m3 / n).cbrt();
+        out.push(mean.round().clamp(0.0, 255.0) as u8);
+        out.push((std * 2.0).round().clamp(0.0, 255.0) as u8);
+        out.push((skew + 128.0).round().clamp(0.0, 255.0) as u8);
+    }
+    out
+}
+
+fn color_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| x.abs_diff(*y) as u32).sum()
+}
+
+fn primary_hash(img: &DynamicImage, cfg: &HashConfig) -> Result<ImageHash, String> {
+    if cfg.alg == HashAlgorithm::Wavelet {
+        wavelet_hash(img, cfg)
     } else {
-        (b.to_string(), a.to_string())
+        Ok(image_hasher(cfg).hash_image(img))
     }
 }
 
-pub fn hash_image(p: &Path, cfg: &HashConfig) -> Result<ImageHash, String> {
+struct ImageSignals {
+    hash: ImageHash,
+    wavelet: Option<ImageHash>,
+    color: Option<Vec<u8>>,
+}
+
+fn hash_image(p: &Path, cfg: &HashConfig) -> Result<ImageSignals, String> {
     log::debug!("hashing image {}", p.display());
     let img = image::open(p).map_err(|e| format!("{}: {}", p.display(), e))?;
-    let hasher = HasherConfig::new()
-        .hash_size(cfg.size.0, cfg.size.1)
-        .resize_filter(cfg.resize_filter.to_filter_type())
-        .hash_alg(cfg.alg.to_img_hash_alg())
-        .to_hasher();
-    Ok(hasher.hash_image(&img))
+    let wavelet = if cfg.alg == HashAlgorithm::Combined {
+        Some(wavelet_hash(&img, cfg)?)
+    } else {
+        None
+    };
+    Ok(ImageSignals {
+        hash: primary_hash(&img, cfg)?,
+        wavelet,
+        color: cfg.color_hash.then(|| color_moments(&img)),
+    })
+}
+
+fn video_frame_hash(p: &Path, seconds: f64, cfg: &HashConfig) -> Result<ImageHash, String> {
+    let mut cmd = Command::new(ffmpeg_sidecar::paths::ffmpeg_path());
+    cmd.arg("-hide_banner")
+        .args(["-loglevel", "error"])
+        .arg("-ss")
+        .arg(format!("{:.3}", seconds))
+        .arg("-i")
+        .arg(p)
+        .args([
+            "-frames:v",
+            "1",
+            "-an",
+            "-vf",
+            "scale=64:64,format=gray",
+            "-f",
+            "image2pipe",
+            "-vcodec",
+            "png",
+            "-",
+        ]);
+    let output = run_command_with_timeout(cmd, ffmpeg_timeout(), true)?;
+    if output.stdout.is_empty() {
+        return Err(format!("no frame at {:.3}s", seconds));
+    }
+    let img = image::load_from_memory(&output.stdout).map_err(|e| e.to_string())?;
+    primary_hash(&img, cfg)
 }
 
 fn decode_hash_bits_base64(s: &str, expected_bits: u32) -> Option<Vec<u64>> {
@@ -91,8 +284,66 @@ fn load_cached_bits_from_meta(meta: &AlbumMeta, name: &str, cfg: &HashConfig) ->
         .and_then(|h| decode_hash_bits_base64(h, cfg.bits))
 }
 
-fn is_hash_failed_in_meta(meta: &AlbumMeta, name: &str) -> bool {
-    meta.files.get(name).map(|e| e.hash_failed).unwrap_or(false)
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Signals {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wavelet: Option<Vec<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<Vec<u8>>,
+}
+
+fn load_cached_signals(meta: &AlbumMeta, name: &str, cfg: &HashConfig) -> Signals {
+    let Some(entry) = meta.files.get(name) else {
+        return Signals::default();
+    };
+    Signals {
+        wavelet: entry
+            .wavelet_hash
+            .as_deref()
+            .and_then(|h| decode_hash_bits_base64(h, cfg.bits)),
+        color: entry
+            .color_hash
+            .as_deref()
+            .and_then(|h| base64::engine::general_purpose::STANDARD.decode(h).ok()),
+    }
+}
+
+fn signals_agree(a: &Signals, b: &Signals, threshold: u32, cfg: &HashConfig) -> bool {
+    let wavelet = match (&a.wavelet, &b.wavelet) {
+        (Some(x), Some(y)) => within_threshold(x, y, threshold),
+        _ => cfg.alg != HashAlgorithm::Combined,
+    };
+    let color = match (&a.color, &b.color) {
+        (Some(x), Some(y)) => color_distance(x, y) <= cfg.color_threshold,
+        _ => true,
+    };
+    wavelet && color
+}
+
+fn load_cached_video_frames(
+    meta: &AlbumMeta,
+    name: &str,
+    cfg: &HashConfig,
+) -> Option<Vec<Vec<u64>>> {
+    let entry = meta.files.get(name)?;
+    if entry.video_hash_version.as_deref() != Some(&cfg.video_hash_version)
+        || entry.video_hashes.len() != VIDEO_HASH_POINTS.len()
+    {
+        return None;
+    }
+    entry
+        .video_hashes
+        .iter()
+        .map(|h| decode_hash_bits_base64(h, cfg.bits))
+        .collect()
+}
+
+fn is_hash_blocked_in_meta(meta: &AlbumMeta, path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|name| meta.files.get(name))
+        .map(|e| e.hash_blocked(path))
+        .unwrap_or(false)
 }
 
 fn hash_to_chunks(h: &ImageHash) -> Option<Vec<u64>> {
@@ -120,8 +371,17 @@ fn blocks_from_bits(bits: &[u64]) -> Vec<u16> {
     out
 }
 
-fn save_hash(p: &Path, h: &ImageHash, cfg: &HashConfig) -> Result<(), String> {
-    write_album_file_hash(p, h.to_base64(), cfg.hash_version.clone(), cfg.bits)
+fn save_hash(p: &Path, h: &ImageSignals, cfg: &HashConfig) -> Result<(), String> {
+    write_album_file_hash(
+        p,
+        h.hash.to_base64(),
+        cfg.hash_version.clone(),
+        cfg.bits,
+        h.wavelet.as_ref().map(|w| w.to_base64()),
+        h.color
+            .as_ref()
+            .map(|c| base64::engine::general_purpose::STANDARD.encode(c)),
+    )
 }
 
 #[derive(Clone)]
@@ -129,6 +389,7 @@ struct Entry {
     name: String,
     bits: Vec<u64>,
     blocks: Vec<u16>,
+    signals: Signals,
 }
 
 #[inline(always)]
@@ -154,28 +415,42 @@ pub(crate) fn compute_hash_for_path(
     cfg: &HashConfig,
     settings: &crate::settings::AdvancedSettings,
 ) -> Option<Vec<u64>> {
+    compute_hash_signals_for_path(meta, p, cfg, settings).map(|(bits, _)| bits)
+}
+
+pub(crate) fn compute_hash_signals_for_path(
+    meta: &AlbumMeta,
+    p: &Path,
+    cfg: &HashConfig,
+    settings: &crate::settings::AdvancedSettings,
+) -> Option<(Vec<u64>, Signals)> {
     let Some(name) = p.file_name().and_then(|n| n.to_str()) else {
         return None;
     };
 
     if let Some(bits) = load_cached_bits_from_meta(meta, name, cfg) {
-        return Some(bits);
+        return Some((bits, load_cached_signals(meta, name, cfg)));
     }
 
-    let thumb_dir = p.parent()?.join(".room237-thumb");
+    let thumb_dir = media_thumb_dir(p)?;
     let mut last_error: Option<String> = None;
 
-    let try_hash_path = |target: &Path| -> Result<Vec<u64>, String> {
+    let try_hash_path = |target: &Path| -> Result<(Vec<u64>, Signals), String> {
         let hash = hash_image(target, cfg)?;
-        let bits = hash_to_chunks(&hash).ok_or_else(|| "unexpected hash length".to_string())?;
+        let bits =
+            hash_to_chunks(&hash.hash).ok_or_else(|| "unexpected hash length".to_string())?;
         save_hash(p, &hash, cfg).map_err(|e| {
             log::error!("Failed to persist hash for {}: {}", p.display(), e);
             e
         })?;
-        Ok(bits)
+        let signals = Signals {
+            wavelet: hash.wavelet.as_ref().and_then(hash_to_chunks),
+            color: hash.color,
+        };
+        Ok((bits, signals))
     };
 
-    let attempt_thumb = |last_error: &mut Option<String>| -> Option<Vec<u64>> {
+    let attempt_thumb = |last_error: &mut Option<String>| -> Option<(Vec<u64>, Signals)> {
         let thumb = thumb_path(p, &thumb_dir).ok()?;
         let thumb_path = if thumb.exists() {
             thumb
@@ -189,7 +464,7 @@ pub(crate) fn compute_hash_for_path(
             }
         };
         match try_hash_path(&thumb_path) {
-            Ok(bits) => Some(bits),
+            Ok(hashed) => Some(hashed),
             Err(e) => {
                 *last_error = Some(e);
                 None
@@ -197,9 +472,20 @@ pub(crate) fn compute_hash_for_path(
         }
     };
 
-    let attempt_original = |last_error: &mut Option<String>| -> Option<Vec<u64>> {
-        match try_hash_path(p) {
-            Ok(bits) => Some(bits),
+    let attempt_original = |last_error: &mut Option<String>| -> Option<(Vec<u64>, Signals)> {
+        let decodable = if has_extension(p, FFMPEG_DECODE_EXTENSIONS) {
+            match ensure_view_transcode(p, &thumb_dir, settings) {
+                Ok(v) => v,
+                Err(e) => {
+                    *last_error = Some(e);
+                    return None;
+                }
+            }
+        } else {
+            p.to_path_buf()
+        };
+        match try_hash_path(&decodable) {
+            Ok(hashed) => Some(hashed),
             Err(e) => {
                 *last_error = Some(e);
                 None
@@ -214,300 +500,1969 @@ pub(crate) fn compute_hash_for_path(
     };
 
     if result.is_none() {
-        if let Some(err) = last_error {
-            log::error!("Failed to hash {}: {}", p.display(), err);
-        }
-        let _ = mark_hash_failed(p);
+        let reason = last_error.unwrap_or_else(|| "no hashable image".to_string());
+        log::error!("Failed to hash {}: {}", p.display(), reason);
+        let _ = mark_hash_failed(p, &reason);
     }
 
     result
 }
 
-#[tauri::command]
-pub async fn find_duplicates(
-    _app: AppHandle<Wry>,
-    dir: String,
-) -> Result<Vec<Vec<String>>, String> {
-    let dirp = PathBuf::from(&dir);
-    if !dirp.is_dir() {
-        return Err(format!("{} is not a directory", dirp.display()));
+pub(crate) fn compute_video_hash_for_path(
+    meta: &AlbumMeta,
+    p: &Path,
+    cfg: &HashConfig,
+) -> Option<Vec<Vec<u64>>> {
+    let name = p.file_name().and_then(|n| n.to_str())?;
+    if let Some(frames) = load_cached_video_frames(meta, name, cfg) {
+        return Some(frames);
     }
 
-    let ignored = load_ignore_set(&dirp);
-    let settings = read_settings();
-    let hash_cfg = settings.hash_config();
-    let max_files = settings.duplicates.max_files_per_album;
+    log::debug!("hashing video {}", p.display());
+    let result = (|| -> Result<Vec<Vec<u64>>, String> {
+        let duration = probe_duration(p)
+            .filter(|d| *d > 0.0)
+            .ok_or_else(|| "unknown duration".to_string())?;
+        let mut encoded = Vec::with_capacity(VIDEO_HASH_POINTS.len());
+        let mut frames = Vec::with_capacity(VIDEO_HASH_POINTS.len());
+        for point in VIDEO_HASH_POINTS {
+            let hash = video_frame_hash(p, duration * point, cfg)?;
+            frames.push(hash_to_chunks(&hash).ok_or("unexpected hash length")?);
+            encoded.push(hash.to_base64());
+        }
+        write_album_video_hash(p, encoded, cfg.video_hash_version.clone())?;
+        Ok(frames)
+    })();
+
+    match result {
+        Ok(frames) => Some(frames),
+        Err(e) => {
+            log::error!("Failed to hash video {}: {}", p.display(), e);
+            let _ = mark_hash_failed(p, &e);
+            None
+        }
+    }
+}
 
-    let task_dir = dirp.clone();
-    let hash_cfg_clone = hash_cfg.clone();
-    let groups = task::spawn_blocking(move || {
-        let t0 = std::time::Instant::now();
+struct VideoEntry {
+    name: String,
+    frames: Vec<Vec<u64>>,
+}
 
-        log::info!("duplicates: scan start {}", task_dir.display());
+fn video_frames_match(a: &[Vec<u64>], b: &[Vec<u64>], threshold: u32) -> bool {
+    if a.is_empty() || a.len() != b.len() {
+        return false;
+    }
+    let matching = a
+        .iter()
+        .zip(b.iter())
+        .filter(|(fa, fb)| within_threshold(fa, fb, threshold))
+        .count();
+    matching + 1 >= a.len()
+}
 
-        let files: Vec<PathBuf> = fs::read_dir(&task_dir)
-            .map_err(|e| e.to_string())?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
-            .filter(|e| has_extension(e.path().as_path(), IMAGE_EXTENSIONS))
-            .map(|e| e.path())
-            .collect();
+fn uf_find(parent: &mut [usize], mut x: usize) -> usize {
+    let mut root = x;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    while parent[x] != x {
+        let next = parent[x];
+        parent[x] = root;
+        x = next;
+    }
+    root
+}
 
-        if files.is_empty() {
-            log::info!("duplicates: no files {}", task_dir.display());
-            return Ok::<Vec<Vec<String>>, String>(Vec::new());
-        }
+fn uf_union(parent: &mut [usize], rank: &mut [usize], a: usize, b: usize) {
+    let mut ra = uf_find(parent, a);
+    let mut rb = uf_find(parent, b);
+    if ra == rb {
+        return;
+    }
+    if rank[ra] < rank[rb] {
+        std::mem::swap(&mut ra, &mut rb);
+    }
+    parent[rb] = ra;
+    if rank[ra] == rank[rb] {
+        rank[ra] += 1;
+    }
+}
 
-        if max_files > 0 && files.len() as u32 > max_files {
-            return Err(format!(
-                "Album too large for duplicate scan ({} > {}).",
-                files.len(),
-                max_files
-            ));
+fn cluster_videos<F>(videos: Vec<VideoEntry>, threshold: u32, is_ignored: &F) -> Vec<Vec<String>>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let n = videos.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = vec![0; n];
+    for a in 0..n {
+        for b in (a + 1)..n {
+            if video_frames_match(&videos[a].frames, &videos[b].frames, threshold)
+                && !is_ignored(&videos[a].name, &videos[b].name)
+            {
+                uf_union(&mut parent, &mut rank, a, b);
+            }
         }
+    }
 
-        log::info!(
-            "duplicates: hashing cached lookup files={} bits={} threshold={} version={}",
-            files.len(),
-            hash_cfg_clone.bits,
-            hash_cfg_clone.effective_threshold,
-            hash_cfg_clone.hash_version
-        );
-
-        wait_for_album_hashes(&task_dir, &files, &hash_cfg_clone, TaskPriority::High)?;
-        let album_meta = read_album_meta(&task_dir);
+    let mut groups_map: HashMap<usize, Vec<String>> = HashMap::new();
+    for (idx, v) in videos.into_iter().enumerate() {
+        let root = uf_find(&mut parent, idx);
+        groups_map.entry(root).or_default().push(v.name);
+    }
+    let mut groups: Vec<Vec<String>> = groups_map.into_values().filter(|g| g.len() > 1).collect();
+    for g in &mut groups {
+        g.sort();
+    }
+    groups
+}
 
-        let mut entries: Vec<Entry> = Vec::new();
-        let mut skipped_failed = 0usize;
-        let mut missing_hash = 0usize;
-        let mut bad_hash_bytes = 0usize;
-        let mut used_cached = 0usize;
+#[derive(Default)]
+struct BlockIndex {
+    buckets: HashMap<u32, Vec<u32>>,
+}
 
-        let mut t_failed_check = std::time::Duration::ZERO;
-        let mut t_cached_decode = std::time::Duration::ZERO;
+impl BlockIndex {
+    fn build<I, B>(blocks: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u16]>,
+    {
+        let mut index = Self::default();
+        for (i, blocks) in blocks.into_iter().enumerate() {
+            index.insert(i as u32, blocks.as_ref());
+        }
+        index
+    }
 
-        for p in &files {
-            let Some(name) = p.file_name().and_then(|n| n.to_str()) else {
-                continue;
-            };
+    fn insert(&mut self, idx: u32, blocks: &[u16]) {
+        for (bi, block) in blocks.iter().enumerate() {
+            self.buckets
+                .entry(block_key(bi, *block))
+                .or_default()
+                .push(idx);
+        }
+    }
 
-            let t = std::time::Instant::now();
-            if is_hash_failed_in_meta(&album_meta, name) {
-                t_failed_check += t.elapsed();
-                skipped_failed += 1;
-                continue;
+    fn remove(&mut self, idx: u32, blocks: &[u16]) {
+        for (bi, block) in blocks.iter().enumerate() {
+            let key = block_key(bi, *block);
+            if let Some(bucket) = self.buckets.get_mut(&key) {
+                bucket.retain(|&i| i != idx);
+                if bucket.is_empty() {
+                    self.buckets.remove(&key);
+                }
             }
-            t_failed_check += t.elapsed();
+        }
+    }
+
+    fn bucket(&self, block_index: usize, value: u16) -> &[u32] {
+        self.buckets
+            .get(&block_key(block_index, value))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
 
-            let t = std::time::Instant::now();
-            let bits_opt = load_cached_bits_from_meta(&album_meta, name, &hash_cfg_clone);
-            t_cached_decode += t.elapsed();
+struct ClusterStats {
+    hashes: usize,
+    candidates: u64,
+    dist_pass: u64,
+    unions: u64,
+}
 
-            match bits_opt {
-                Some(bits) => {
-                    used_cached += 1;
-                    let blocks = blocks_from_bits(&bits);
-                    entries.push(Entry {
-                        name: name.to_string(),
-                        bits,
-                        blocks,
-                    });
+fn cluster_entries<F>(
+    entries: Vec<Entry>,
+    cfg: &HashConfig,
+    is_ignored: F,
+) -> (Vec<Vec<String>>, ClusterStats)
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let threshold = cfg.effective_threshold;
+    log::info!("duplicates: index {} hashes", entries.len());
+
+    let n = entries.len();
+    let index = BlockIndex::build(entries.iter().map(|e| &e.blocks));
+
+    log::info!("duplicates: compare candidates for {} hashes", n);
+
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = vec![0; n];
+
+    let mut stamp: Vec<u32> = vec![0; n];
+    let mut token: u32 = 1;
+
+    let mut candidate_visits: u64 = 0;
+    let mut _unique_candidates: u64 = 0;
+    let mut _dist_checks: u64 = 0;
+    let mut dist_pass: u64 = 0;
+    let mut _ignore_hits: u64 = 0;
+    let mut unions: u64 = 0;
+
+    for a in 0..n {
+        let ea = &entries[a];
+
+        for (bi, block_val) in ea.blocks.iter().enumerate() {
+            for &b in index.bucket(bi, *block_val) {
+                candidate_visits += 1;
+                let b = b as usize;
+                if b <= a {
+                    continue;
+                }
+                if stamp[b] == token {
+                    continue;
+                }
+                stamp[b] = token;
+                _unique_candidates += 1;
+
+                _dist_checks += 1;
+                if !within_threshold(&ea.bits, &entries[b].bits, threshold)
+                    || !signals_agree(&ea.signals, &entries[b].signals, threshold, cfg)
+                {
+                    continue;
                 }
-                None => {
-                    missing_hash += 1;
-                    bad_hash_bytes += 1;
+                dist_pass += 1;
+
+                if is_ignored(&ea.name, &entries[b].name) {
+                    _ignore_hits += 1;
+                    continue;
                 }
+
+                uf_union(&mut parent, &mut rank, a, b);
+                unions += 1;
             }
         }
 
-        log::info!(
-            "duplicates: collected entries={} cached={} missing={} bad_bytes={} skipped_failed={} failed_check={:?} cached_decode={:?}",
-            entries.len(),
-            used_cached,
-            missing_hash,
-            bad_hash_bytes,
-            skipped_failed,
-            t_failed_check,
-            t_cached_decode
-        );
-
-        if entries.len() < 2 {
-            log::info!(
-                "duplicates: insufficient hashed files {}/{} {}",
-                entries.len(),
-                files.len(),
-                task_dir.display()
-            );
-            return Ok::<Vec<Vec<String>>, String>(Vec::new());
+        token = token.wrapping_add(1);
+        if token == 0 {
+            stamp.fill(0);
+            token = 1;
         }
+    }
+
+    let mut groups_map: HashMap<usize, Vec<String>> = HashMap::new();
+    for (idx, e) in entries.into_iter().enumerate() {
+        let root = uf_find(&mut parent, idx);
+        groups_map.entry(root).or_default().push(e.name);
+    }
 
-        log::info!("duplicates: index {} hashes", entries.len());
+    let mut groups: Vec<Vec<String>> = groups_map.into_values().filter(|g| g.len() > 1).collect();
 
-        let n = entries.len();
-        let total_blocks: usize = entries.iter().map(|e| e.blocks.len()).sum();
-        let mut pairs: Vec<(u32, u32)> = Vec::with_capacity(total_blocks);
-        for (i, e) in entries.iter().enumerate() {
-            let idx = i as u32;
-            for (bi, block) in e.blocks.iter().enumerate() {
-                pairs.push((block_key(bi, *block), idx));
-            }
-        }
+    for g in &mut groups {
+        g.sort();
+    }
+    groups.sort_by(|a, b| a[0].cmp(&b[0]));
 
-        pairs.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    let stats = ClusterStats {
+        hashes: parent.len(),
+        candidates: candidate_visits,
+        dist_pass,
+        unions,
+    };
+    (groups, stats)
+}
 
-        let mut keys: Vec<u32> = Vec::new();
-        let mut ranges: Vec<(usize, usize)> = Vec::new();
-        let mut i = 0usize;
-        while i < pairs.len() {
-            let k = pairs[i].0;
-            let start = i;
-            i += 1;
-            while i < pairs.len() && pairs[i].0 == k {
-                i += 1;
-            }
-            keys.push(k);
-            ranges.push((start, i));
-        }
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateKind {
+    Exact,
+    Perceptual,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuplicateMember {
+    pub file: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub size: u64,
+    pub format: String,
+    pub has_exif_date: bool,
+    pub favorite: bool,
+    pub sharpness: Option<f32>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    pub files: Vec<String>,
+    pub members: Vec<DuplicateMember>,
+    pub keeper: Option<String>,
+}
+
+struct ExactCandidate {
+    key: String,
+    path: PathBuf,
+    digest: FileDigest,
+    dirty: bool,
+}
+
+fn album_media_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    Ok(fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .map(|e| e.path())
+        .filter(|p| has_extension(p, IMAGE_EXTENSIONS) || has_extension(p, VIDEO_EXTENSIONS))
+        .collect())
+}
 
-        log::info!("duplicates: compare candidates for {} hashes", n);
+fn exact_candidate(key: String, path: &Path, meta: &AlbumMeta) -> Option<ExactCandidate> {
+    let md = fs::metadata(path).ok()?;
+    let size = md.len();
+    let mtime = md
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let name = path.file_name()?.to_str()?;
+    let cached = meta
+        .files
+        .get(name)
+        .and_then(|e| e.digest.clone())
+        .filter(|d| d.size == size && d.mtime == mtime);
+    let dirty = cached.is_none();
+    Some(ExactCandidate {
+        key,
+        path: path.to_path_buf(),
+        digest: cached.unwrap_or(FileDigest {
+            size,
+            mtime,
+            partial: None,
+            full: None,
+        }),
+        dirty,
+    })
+}
 
-        let mut parent: Vec<usize> = (0..n).collect();
-        let mut rank: Vec<usize> = vec![0; n];
+fn sha256_file(path: &Path, limit: u64) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file.take(limit), &mut hasher).map_err(|e| e.to_string())?;
+    Ok(hex::encode(hasher.finalize()))
+}
 
-        fn find(parent: &mut [usize], mut x: usize) -> usize {
-            let mut root = x;
-            while parent[root] != root {
-                root = parent[root];
+fn ensure_digest(candidate: &mut ExactCandidate, full: bool) -> Option<String> {
+    let limit = if full { u64::MAX } else { PARTIAL_DIGEST_BYTES };
+    let covers_file = candidate.digest.size <= PARTIAL_DIGEST_BYTES;
+    let slot = if full && !covers_file {
+        &mut candidate.digest.full
+    } else {
+        &mut candidate.digest.partial
+    };
+    if slot.is_none() {
+        match sha256_file(&candidate.path, limit) {
+            Ok(digest) => {
+                *slot = Some(digest);
+                candidate.dirty = true;
             }
-            while parent[x] != x {
-                let next = parent[x];
-                parent[x] = root;
-                x = next;
+            Err(e) => {
+                log::warn!(
+                    "duplicates: digest failed {}: {}",
+                    candidate.path.display(),
+                    e
+                );
+                return None;
             }
-            root
         }
+    }
+    slot.clone()
+}
+
+fn split_ignored<F>(keys: Vec<String>, is_ignored: &F) -> Vec<Vec<String>>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    for key in keys {
+        match groups
+            .iter_mut()
+            .find(|g| g.iter().all(|k| !is_ignored(k, &key)))
+        {
+            Some(group) => group.push(key),
+            None => groups.push(vec![key]),
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+fn exact_groups<F>(candidates: &mut [ExactCandidate], is_ignored: &F) -> Vec<Vec<String>>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let mut by_size: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (i, c) in candidates.iter().enumerate() {
+        if c.digest.size > 0 {
+            by_size.entry(c.digest.size).or_default().push(i);
+        }
+    }
 
-        fn union(parent: &mut [usize], rank: &mut [usize], a: usize, b: usize) {
-            let mut ra = find(parent, a);
-            let mut rb = find(parent, b);
-            if ra == rb {
-                return;
+    let mut groups = Vec::new();
+    for bucket in by_size.into_values().filter(|b| b.len() > 1) {
+        let mut by_partial: HashMap<String, Vec<usize>> = HashMap::new();
+        for i in bucket {
+            if let Some(digest) = ensure_digest(&mut candidates[i], false) {
+                by_partial.entry(digest).or_default().push(i);
             }
-            if rank[ra] < rank[rb] {
-                std::mem::swap(&mut ra, &mut rb);
+        }
+        for bucket in by_partial.into_values().filter(|b| b.len() > 1) {
+            let mut by_full: HashMap<String, Vec<usize>> = HashMap::new();
+            for i in bucket {
+                if let Some(digest) = ensure_digest(&mut candidates[i], true) {
+                    by_full.entry(digest).or_default().push(i);
+                }
             }
-            parent[rb] = ra;
-            if rank[ra] == rank[rb] {
-                rank[ra] += 1;
+            for bucket in by_full.into_values().filter(|b| b.len() > 1) {
+                let mut keys: Vec<String> =
+                    bucket.iter().map(|&i| candidates[i].key.clone()).collect();
+                keys.sort();
+                groups.extend(split_ignored(keys, is_ignored));
             }
         }
+    }
+    groups
+}
 
-        let mut stamp: Vec<u32> = vec![0; n];
-        let mut token: u32 = 1;
-
-        let mut candidate_visits: u64 = 0;
-        let mut _unique_candidates: u64 = 0;
-        let mut _dist_checks: u64 = 0;
-        let mut dist_pass: u64 = 0;
-        let mut _ignore_hits: u64 = 0;
-        let mut unions: u64 = 0;
+fn persist_digests(candidates: &[ExactCandidate]) {
+    let mut by_dir: HashMap<&Path, Vec<(String, FileDigest)>> = HashMap::new();
+    for c in candidates
+        .iter()
+        .filter(|c| c.dirty && c.digest.partial.is_some())
+    {
+        let (Some(dir), Some(name)) =
+            (c.path.parent(), c.path.file_name().and_then(|n| n.to_str()))
+        else {
+            continue;
+        };
+        by_dir
+            .entry(dir)
+            .or_default()
+            .push((name.to_string(), c.digest.clone()));
+    }
+    for (dir, digests) in by_dir {
+        if let Err(e) = write_file_digests(dir, &digests) {
+            log::warn!(
+                "duplicates: failed to cache digests in {}: {}",
+                dir.display(),
+                e
+            );
+        }
+    }
+}
 
-        for a in 0..n {
-            let ea = &entries[a];
+fn sharpness_score(p: &Path) -> Option<f32> {
+    let thumb = media_thumb_dir(p)
+        .and_then(|dir| thumb_path(p, &dir).ok())
+        .filter(|t| t.exists());
+    let img = match thumb {
+        Some(t) => image::open(t).ok()?,
+        None if has_extension(p, IMAGE_EXTENSIONS)
+            && !has_extension(p, FFMPEG_DECODE_EXTENSIONS) =>
+        {
+            image::open(p).ok()?.thumbnail(512, 512)
+        }
+        None => return None,
+    };
+    let luma = img.to_luma8();
+    let (w, h) = luma.dimensions();
+    if w < 3 || h < 3 {
+        return None;
+    }
+    let px = |x: u32, y: u32| luma.get_pixel(x, y)[0] as f64;
+    let mut sum = 0f64;
+    let mut sum_sq = 0f64;
+    for y in 1..h - 1 {
+        for x in 1..w - 1 {
+            let lap = 4.0 * px(x, y) - px(x - 1, y) - px(x + 1, y) - px(x, y - 1) - px(x, y + 1);
+            sum += lap;
+            sum_sq += lap * lap;
+        }
+    }
+    let n = ((w - 2) * (h - 2)) as f64;
+    let mean = sum / n;
+    Some((sum_sq / n - mean * mean) as f32)
+}
 
-            for (bi, block_val) in ea.blocks.iter().enumerate() {
-                let k = block_key(bi, *block_val);
-                let pos = match keys.binary_search(&k) {
-                    Ok(p) => p,
-                    Err(_) => continue,
-                };
-                let (start, end) = ranges[pos];
+fn describe_member(
+    key: &str,
+    path: &Path,
+    metas: &mut HashMap<PathBuf, AlbumMeta>,
+) -> DuplicateMember {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or(key);
+    let favorite = path
+        .parent()
+        .map(|dir| {
+            metas
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| read_album_meta(dir))
+                .files
+                .get(name)
+                .map(|e| e.favorite)
+                .unwrap_or(false)
+        })
+        .unwrap_or(false);
+    let meta = file_meta(path);
+    let is_video = has_extension(path, VIDEO_EXTENSIONS);
+    DuplicateMember {
+        file: key.to_string(),
+        width: meta.as_ref().and_then(|m| m.w),
+        height: meta.as_ref().and_then(|m| m.h),
+        size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        format: path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase(),
+        has_exif_date: if is_video {
+            meta.as_ref().and_then(|m| m.s).is_some()
+        } else {
+            datetime_original(path).is_some()
+        },
+        favorite,
+        sharpness: if is_video {
+            None
+        } else {
+            sharpness_score(path)
+        },
+    }
+}
 
-                for t in start..end {
-                    candidate_visits += 1;
-                    let b = pairs[t].1 as usize;
-                    if b <= a {
-                        continue;
-                    }
-                    if stamp[b] == token {
-                        continue;
-                    }
-                    stamp[b] = token;
-                    _unique_candidates += 1;
+fn compare_members(a: &DuplicateMember, b: &DuplicateMember) -> Ordering {
+    let pixels = |m: &DuplicateMember| m.width.unwrap_or(0) as u64 * m.height.unwrap_or(0) as u64;
+    pixels(a)
+        .cmp(&pixels(b))
+        .then_with(|| {
+            a.sharpness
+                .partial_cmp(&b.sharpness)
+                .unwrap_or(Ordering::Equal)
+        })
+        .then_with(|| a.has_exif_date.cmp(&b.has_exif_date))
+        .then_with(|| a.size.cmp(&b.size))
+        .then_with(|| a.favorite.cmp(&b.favorite))
+}
 
-                    _dist_checks += 1;
-                    if !within_threshold(&ea.bits, &entries[b].bits, hash_cfg_clone.effective_threshold)
-                    {
-                        continue;
-                    }
-                    dist_pass += 1;
+fn suggest_keeper(members: &[DuplicateMember]) -> Option<String> {
+    members
+        .iter()
+        .rev()
+        .max_by(|a, b| compare_members(a, b))
+        .map(|m| m.file.clone())
+}
 
-                    let key = pair_key(&ea.name, &entries[b].name);
-                    if ignored.contains(&key) {
-                        _ignore_hits += 1;
-                        continue;
-                    }
+fn describe_group(
+    kind: DuplicateKind,
+    files: Vec<String>,
+    paths: &HashMap<String, PathBuf>,
+    metas: &mut HashMap<PathBuf, AlbumMeta>,
+) -> DuplicateGroup {
+    let members: Vec<DuplicateMember> = files
+        .iter()
+        .filter_map(|f| paths.get(f).map(|p| describe_member(f, p, metas)))
+        .collect();
+    let keeper = suggest_keeper(&members);
+    DuplicateGroup {
+        kind,
+        files,
+        members,
+        keeper,
+    }
+}
 
-                    union(&mut parent, &mut rank, a, b);
-                    unions += 1;
-                }
-            }
+fn build_groups<F>(
+    mut candidates: Vec<ExactCandidate>,
+    entries: Vec<Entry>,
+    videos: Vec<VideoEntry>,
+    cfg: &HashConfig,
+    is_ignored: F,
+) -> (Vec<DuplicateGroup>, ClusterStats)
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let exact = exact_groups(&mut candidates, &is_ignored);
+    persist_digests(&candidates);
+
+    let redundant: HashSet<String> = exact
+        .iter()
+        .flat_map(|g| g.iter().skip(1).cloned())
+        .collect();
+    let entries: Vec<Entry> = entries
+        .into_iter()
+        .filter(|e| !redundant.contains(&e.name))
+        .collect();
+    let videos: Vec<VideoEntry> = videos
+        .into_iter()
+        .filter(|v| !redundant.contains(&v.name))
+        .collect();
+    let (mut perceptual, stats) = cluster_entries(entries, cfg, &is_ignored);
+    perceptual.extend(cluster_videos(videos, cfg.effective_threshold, &is_ignored));
+
+    let paths: HashMap<String, PathBuf> = candidates.into_iter().map(|c| (c.key, c.path)).collect();
+    let mut metas: HashMap<PathBuf, AlbumMeta> = HashMap::new();
+    let mut groups: Vec<DuplicateGroup> = exact
+        .into_iter()
+        .map(|files| (DuplicateKind::Exact, files))
+        .chain(
+            perceptual
+                .into_iter()
+                .map(|files| (DuplicateKind::Perceptual, files)),
+        )
+        .map(|(kind, files)| describe_group(kind, files, &paths, &mut metas))
+        .collect();
+    groups.sort_by(|a, b| a.files[0].cmp(&b.files[0]));
+    (groups, stats)
+}
 
-            token = token.wrapping_add(1);
-            if token == 0 {
-                stamp.fill(0);
-                token = 1;
-            }
-        }
+#[derive(Serialize, Deserialize)]
+struct IndexedHash {
+    name: String,
+    bits: Vec<u64>,
+    #[serde(default)]
+    signals: Signals,
+}
 
-        let mut groups_map: HashMap<usize, Vec<String>> = HashMap::new();
-        for (idx, e) in entries.into_iter().enumerate() {
-            let root = find(&mut parent, idx);
-            groups_map.entry(root).or_default().push(e.name);
-        }
+#[derive(Serialize, Deserialize)]
+struct DuplicateIndex {
+    hash_version: String,
+    threshold: u32,
+    #[serde(default)]
+    color_threshold: u32,
+    entries: Vec<IndexedHash>,
+    groups: Vec<DuplicateGroup>,
+    #[serde(skip)]
+    blocks: Option<BlockIndex>,
+}
 
-        let mut groups: Vec<Vec<String>> =
-            groups_map.into_values().filter(|g| g.len() > 1).collect();
+impl DuplicateIndex {
+    fn matches(&self, cfg: &HashConfig) -> bool {
+        self.hash_version == cfg.hash_version
+            && self.threshold == cfg.effective_threshold
+            && self.color_threshold == cfg.color_threshold
+    }
 
-        for g in &mut groups {
-            g.sort();
+    fn ensure_blocks(&mut self) {
+        if self.blocks.is_none() {
+            self.blocks = Some(BlockIndex::build(
+                self.entries.iter().map(|e| blocks_from_bits(&e.bits)),
+            ));
         }
-        groups.sort_by(|a, b| a[0].cmp(&b[0]));
+    }
+}
 
-        log::info!(
-            "duplicates: done {} groups from {} cached hashes {} threshold={} elapsed={:?} candidates={} dist_pass={} unions={}",
-            groups.len(),
-            parent.len(),
-            task_dir.display(),
-            hash_cfg_clone.effective_threshold,
-            t0.elapsed(),
-            candidate_visits,
-            dist_pass,
-            unions
-        );
-        Ok(groups)
-    })
-    .await
-    .map_err(|e| e.to_string())??;
+static DUPLICATE_INDEXES: Lazy<Mutex<HashMap<PathBuf, DuplicateIndex>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static REFRESHING_INDEXES: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static DIRTY_INDEXES: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static INDEX_FLUSH_LOCK: Mutex<()> = Mutex::new(());
+static INDEX_FLUSHER: OnceCell<()> = OnceCell::new();
+const INDEX_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
 
-    Ok(groups)
+fn index_path(dir: &Path) -> PathBuf {
+    album_meta_dir(dir).join(DUPLICATE_INDEX_FILE)
 }
 
-#[tauri::command]
-pub fn mark_non_duplicates(dir: String, files: Vec<String>) -> Result<(), String> {
-    let dirp = PathBuf::from(&dir);
-    if !dirp.is_dir() {
+fn loaded_index<'a>(
+    indexes: &'a mut HashMap<PathBuf, DuplicateIndex>,
+    dir: &Path,
+) -> Option<&'a mut DuplicateIndex> {
+    if !indexes.contains_key(dir) {
+        let index = fs::read_to_string(index_path(dir))
+            .ok()
+            .and_then(|txt| serde_json::from_str::<DuplicateIndex>(&txt).ok())?;
+        indexes.insert(dir.to_path_buf(), index);
+    }
+    indexes.get_mut(dir)
+}
+
+fn write_index(dir: &Path, json: Result<String, String>) {
+    let result = json.and_then(|json| {
+        fs::create_dir_all(album_meta_dir(dir)).map_err(|e| e.to_string())?;
+        fs::write(index_path(dir), json).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        log::warn!(
+            "duplicates: failed to persist index for {}: {}",
+            dir.display(),
+            e
+        );
+    }
+}
+
+fn mark_index_dirty(dir: &Path) {
+    DIRTY_INDEXES.lock().unwrap().insert(dir.to_path_buf());
+    INDEX_FLUSHER.get_or_init(|| {
+        thread::spawn(|| loop {
+            thread::sleep(INDEX_FLUSH_INTERVAL);
+            flush_duplicate_indexes();
+        });
+    });
+}
+
+pub(crate) fn flush_duplicate_indexes() {
+    let _flush = INDEX_FLUSH_LOCK.lock().unwrap();
+    let dirty: Vec<PathBuf> = DIRTY_INDEXES.lock().unwrap().drain().collect();
+    for dir in dirty {
+        let json = {
+            let indexes = DUPLICATE_INDEXES.lock().unwrap();
+            let Some(index) = indexes.get(&dir) else {
+                continue;
+            };
+            serde_json::to_string(index).map_err(|e| e.to_string())
+        };
+        write_index(&dir, json);
+    }
+}
+
+fn store_index(dir: &Path, index: DuplicateIndex) {
+    let _flush = INDEX_FLUSH_LOCK.lock().unwrap();
+    DIRTY_INDEXES.lock().unwrap().remove(dir);
+    write_index(
+        dir,
+        serde_json::to_string(&index).map_err(|e| e.to_string()),
+    );
+    DUPLICATE_INDEXES
+        .lock()
+        .unwrap()
+        .insert(dir.to_path_buf(), index);
+}
+
+pub(crate) fn invalidate_duplicate_index(dir: &Path) {
+    let _flush = INDEX_FLUSH_LOCK.lock().unwrap();
+    DIRTY_INDEXES.lock().unwrap().remove(dir);
+    DUPLICATE_INDEXES.lock().unwrap().remove(dir);
+    IGNORE_CACHE.lock().unwrap().remove(dir);
+    let _ = fs::remove_file(index_path(dir));
+}
+
+fn cached_groups(dir: &Path, cfg: &HashConfig) -> Option<Vec<DuplicateGroup>> {
+    let mut indexes = DUPLICATE_INDEXES.lock().unwrap();
+    loaded_index(&mut indexes, dir)
+        .filter(|index| index.matches(cfg))
+        .map(|index| index.groups.clone())
+}
+
+fn groups_present(dir: &Path, groups: &[DuplicateGroup]) -> bool {
+    groups
+        .iter()
+        .flat_map(|g| g.files.iter())
+        .all(|f| dir.join(f).is_file())
+}
+
+fn group_signature(groups: &[DuplicateGroup]) -> Vec<(DuplicateKind, Vec<String>)> {
+    groups.iter().map(|g| (g.kind, g.files.clone())).collect()
+}
+
+fn emit_duplicates_updated(app: &AppHandle<Wry>, dir: &Path, groups: &[DuplicateGroup]) {
+    let _ = app.emit(
+        "duplicates-updated",
+        json!({
+            "dir": dir.to_string_lossy(),
+            "groups": groups,
+        }),
+    );
+}
+
+pub(crate) fn note_album_hash(path: &Path, bits: &[u64], signals: &Signals, cfg: &HashConfig) {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return;
+    };
+
+    let ignored = cached_ignore_set(dir);
+    let blocks = blocks_from_bits(bits);
+
+    let regrouped = {
+        let mut indexes = DUPLICATE_INDEXES.lock().unwrap();
+        let Some(index) = loaded_index(&mut indexes, dir) else {
+            return;
+        };
+        if !index.matches(cfg) {
+            return;
+        }
+        index.ensure_blocks();
+
+        let existing = index.entries.iter().position(|e| e.name == name);
+        let mut orphaned: Vec<Vec<String>> = Vec::new();
+        let mut dropped = false;
+        let idx = match existing {
+            Some(pos) => {
+                let entry = &index.entries[pos];
+                if entry.bits == bits && entry.signals == *signals {
+                    return;
+                }
+                let old_blocks = blocks_from_bits(&entry.bits);
+                if let Some(block_index) = index.blocks.as_mut() {
+                    block_index.remove(pos as u32, &old_blocks);
+                }
+                index.groups.retain_mut(|group| {
+                    if !group.files.iter().any(|f| f == name) {
+                        return true;
+                    }
+                    dropped = true;
+                    if group.kind == DuplicateKind::Perceptual {
+                        orphaned.push(group.files.iter().filter(|f| *f != name).cloned().collect());
+                        return false;
+                    }
+                    group.files.retain(|f| f != name);
+                    group.members.retain(|m| m.file != name);
+                    group.keeper = suggest_keeper(&group.members);
+                    group.files.len() > 1
+                });
+                index.entries[pos] = IndexedHash {
+                    name: name.to_string(),
+                    bits: bits.to_vec(),
+                    signals: signals.clone(),
+                };
+                pos as u32
+            }
+            None => {
+                index.entries.push(IndexedHash {
+                    name: name.to_string(),
+                    bits: bits.to_vec(),
+                    signals: signals.clone(),
+                });
+                (index.entries.len() - 1) as u32
+            }
+        };
+        if let Some(block_index) = index.blocks.as_mut() {
+            block_index.insert(idx, &blocks);
+        }
+
+        let redundant: HashSet<String> = index
+            .groups
+            .iter()
+            .filter(|g| g.kind == DuplicateKind::Exact)
+            .flat_map(|g| g.files.iter().skip(1).cloned())
+            .collect();
+        let mut matched: HashSet<String> = HashSet::new();
+        if let Some(block_index) = &index.blocks {
+            for (bi, block) in blocks.iter().enumerate() {
+                for &other_idx in block_index.bucket(bi, *block) {
+                    if other_idx == idx {
+                        continue;
+                    }
+                    let other = &index.entries[other_idx as usize];
+                    if matched.contains(&other.name)
+                        || redundant.contains(&other.name)
+                        || ignored.contains(name, &other.name)
+                    {
+                        continue;
+                    }
+                    if within_threshold(bits, &other.bits, index.threshold)
+                        && signals_agree(signals, &other.signals, index.threshold, cfg)
+                    {
+                        matched.insert(other.name.clone());
+                    }
+                }
+            }
+        }
+
+        let mut clusters: Vec<Vec<String>> = Vec::new();
+        if !orphaned.is_empty() {
+            let positions: HashMap<&str, usize> = index
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (e.name.as_str(), i))
+                .collect();
+            for members in orphaned {
+                let entries: Vec<Entry> = members
+                    .iter()
+                    .filter_map(|m| positions.get(m.as_str()))
+                    .map(|&i| {
+                        let e = &index.entries[i];
+                        Entry {
+                            name: e.name.clone(),
+                            bits: e.bits.clone(),
+                            blocks: blocks_from_bits(&e.bits),
+                            signals: e.signals.clone(),
+                        }
+                    })
+                    .collect();
+                let (groups, _) = cluster_entries(entries, cfg, |a, b| ignored.contains(a, b));
+                clusters.extend(groups);
+            }
+        }
+
+        if !matched.is_empty() {
+            matched.insert(name.to_string());
+            for files in index
+                .groups
+                .iter()
+                .filter(|g| g.kind == DuplicateKind::Perceptual)
+                .map(|g| &g.files)
+                .chain(clusters.iter())
+            {
+                if files.iter().any(|f| matched.contains(f)) {
+                    matched.extend(files.iter().cloned());
+                }
+            }
+            clusters.retain(|files| !files.iter().any(|f| matched.contains(f)));
+            let mut files: Vec<String> = matched.into_iter().collect();
+            files.sort();
+            clusters.push(files);
+        }
+
+        mark_index_dirty(dir);
+        if clusters.is_empty() && !dropped {
+            return;
+        }
+        clusters
+    };
+
+    let paths: HashMap<String, PathBuf> = regrouped
+        .iter()
+        .flatten()
+        .map(|f| (f.clone(), dir.join(f)))
+        .collect();
+    let mut metas: HashMap<PathBuf, AlbumMeta> = HashMap::new();
+    let fresh: Vec<DuplicateGroup> = regrouped
+        .into_iter()
+        .map(|files| describe_group(DuplicateKind::Perceptual, files, &paths, &mut metas))
+        .collect();
+
+    let groups = {
+        let mut indexes = DUPLICATE_INDEXES.lock().unwrap();
+        let Some(index) = loaded_index(&mut indexes, dir).filter(|i| i.matches(cfg)) else {
+            return;
+        };
+        if !fresh.is_empty() {
+            let touched: HashSet<&String> = fresh.iter().flat_map(|g| g.files.iter()).collect();
+            index.groups.retain(|g| {
+                g.kind != DuplicateKind::Perceptual || !g.files.iter().any(|f| touched.contains(f))
+            });
+            index.groups.extend(fresh.iter().cloned());
+            index.groups.sort_by(|a, b| a.files[0].cmp(&b.files[0]));
+            mark_index_dirty(dir);
+        }
+        index.groups.clone()
+    };
+
+    if let Some(app) = preload_app() {
+        emit_duplicates_updated(&app, dir, &groups);
+    }
+}
+
+fn scan_album_duplicates(
+    task_dir: &Path,
+    hash_cfg: &HashConfig,
+    limits: &DuplicatesSettings,
+    job: &ScanJob,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let t0 = std::time::Instant::now();
+    let ignored = load_ignore_set(task_dir);
+
+    log::info!("duplicates: scan start {}", task_dir.display());
+
+    let mut files = album_media_files(task_dir)?;
+
+    if files.is_empty() {
+        log::info!("duplicates: no files {}", task_dir.display());
+        return Ok(Vec::new());
+    }
+
+    let max_files = limits.max_files_per_album as usize;
+    if max_files > 0 && files.len() > max_files {
+        let warning = format!(
+            "Only the first {} of {} files in {} were scanned for duplicates.",
+            max_files,
+            files.len(),
+            task_dir.display()
+        );
+        log::warn!("duplicates: {}", warning);
+        job.job.error(warning);
+        files.truncate(max_files);
+    }
+
+    let chunk_size = match limits.scan_chunk_size {
+        0 => files.len(),
+        n => n as usize,
+    };
+
+    log::info!(
+        "duplicates: hashing cached lookup scan={} files={} chunk={} bits={} threshold={} version={}",
+        job.id(),
+        files.len(),
+        chunk_size,
+        hash_cfg.bits,
+        hash_cfg.effective_threshold,
+        hash_cfg.hash_version
+    );
+
+    let mut hashed = 0usize;
+    job.progress(ScanPhase::Hashing, hashed, files.len());
+    for chunk in files.chunks(chunk_size) {
+        if job.check().is_err() {
+            break;
+        }
+        if let Err(e) = wait_for_album_hashes(
+            task_dir,
+            chunk,
+            hash_cfg,
+            TaskPriority::High,
+            Some(job.job.cancel_flag()),
+        ) {
+            if job.cancelled() {
+                break;
+            }
+            return Err(e);
+        }
+        hashed += chunk.len();
+        job.progress(ScanPhase::Hashing, hashed, files.len());
+    }
+    let album_meta = read_album_meta(task_dir);
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut videos: Vec<VideoEntry> = Vec::new();
+    let mut skipped_failed = 0usize;
+    let mut missing_hash = 0usize;
+    let mut bad_hash_bytes = 0usize;
+    let mut used_cached = 0usize;
+
+    let mut t_failed_check = std::time::Duration::ZERO;
+    let mut t_cached_decode = std::time::Duration::ZERO;
+
+    for p in &files {
+        let Some(name) = p.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+
+        let t = std::time::Instant::now();
+        if is_hash_blocked_in_meta(&album_meta, p) {
+            t_failed_check += t.elapsed();
+            skipped_failed += 1;
+            continue;
+        }
+        t_failed_check += t.elapsed();
+
+        if has_extension(p, VIDEO_EXTENSIONS) {
+            match load_cached_video_frames(&album_meta, name, &hash_cfg) {
+                Some(frames) => {
+                    used_cached += 1;
+                    videos.push(VideoEntry {
+                        name: name.to_string(),
+                        frames,
+                    });
+                }
+                None => missing_hash += 1,
+            }
+            continue;
+        }
+
+        let t = std::time::Instant::now();
+        let bits_opt = load_cached_bits_from_meta(&album_meta, name, &hash_cfg);
+        t_cached_decode += t.elapsed();
+
+        match bits_opt {
+            Some(bits) => {
+                used_cached += 1;
+                let blocks = blocks_from_bits(&bits);
+                entries.push(Entry {
+                    name: name.to_string(),
+                    bits,
+                    blocks,
+                    signals: load_cached_signals(&album_meta, name, &hash_cfg),
+                });
+            }
+            None => {
+                missing_hash += 1;
+                bad_hash_bytes += 1;
+            }
+        }
+    }
+
+    log::info!(
+        "duplicates: collected entries={} videos={} cached={} missing={} bad_bytes={} skipped_failed={} failed_check={:?} cached_decode={:?}",
+        entries.len(),
+        videos.len(),
+        used_cached,
+        missing_hash,
+        bad_hash_bytes,
+        skipped_failed,
+        t_failed_check,
+        t_cached_decode
+    );
+
+    if entries.len() + videos.len() < 2 {
+        log::info!(
+            "duplicates: insufficient hashed files {}/{} {}",
+            entries.len() + videos.len(),
+            files.len(),
+            task_dir.display()
+        );
+    }
+
+    let candidates: Vec<ExactCandidate> = files
+        .iter()
+        .filter_map(|p| {
+            let name = p.file_name()?.to_str()?;
+            exact_candidate(name.to_string(), p, &album_meta)
+        })
+        .collect();
+
+    let indexed: Vec<IndexedHash> = entries
+        .iter()
+        .map(|e| IndexedHash {
+            name: e.name.clone(),
+            bits: e.bits.clone(),
+            signals: e.signals.clone(),
+        })
+        .collect();
+
+    let compared = entries.len() + videos.len();
+    job.progress(ScanPhase::Comparing, 0, compared);
+    let (groups, stats) = build_groups(candidates, entries, videos, hash_cfg, |a, b| {
+        ignored.contains(a, b)
+    });
+    job.progress(ScanPhase::Done, compared, compared);
+
+    log::info!(
+        "duplicates: done {} groups ({} exact) from {} cached hashes {} threshold={} elapsed={:?} candidates={} dist_pass={} unions={}",
+        groups.len(),
+        groups.iter().filter(|g| matches!(g.kind, DuplicateKind::Exact)).count(),
+        stats.hashes,
+        task_dir.display(),
+        hash_cfg.effective_threshold,
+        t0.elapsed(),
+        stats.candidates,
+        stats.dist_pass,
+        stats.unions
+    );
+    if job.cancelled() {
+        log::info!(
+            "duplicates: scan {} cancelled, returning partial groups for {}",
+            job.id(),
+            task_dir.display()
+        );
+        return Ok(groups);
+    }
+    store_index(
+        task_dir,
+        DuplicateIndex {
+            hash_version: hash_cfg.hash_version.clone(),
+            threshold: hash_cfg.effective_threshold,
+            color_threshold: hash_cfg.color_threshold,
+            entries: indexed,
+            groups: groups.clone(),
+            blocks: None,
+        },
+    );
+    Ok(groups)
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ScanPhase {
+    Hashing,
+    Comparing,
+    Done,
+}
+
+struct ScanJob {
+    job: JobHandle,
+    dir: PathBuf,
+    app: AppHandle<Wry>,
+}
+
+impl ScanJob {
+    fn start(app: AppHandle<Wry>, id: Option<String>, dir: &Path) -> Self {
+        Self {
+            job: JobHandle::start(JobKind::DuplicateScan, id, Some(dir)),
+            dir: dir.to_path_buf(),
+            app,
+        }
+    }
+
+    fn id(&self) -> &str {
+        self.job.id()
+    }
+
+    fn check(&self) -> Result<(), String> {
+        self.job.checkpoint()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.job.cancel_flag().load(atomic::Ordering::SeqCst)
+    }
+
+    fn progress(&self, phase: ScanPhase, completed: usize, total: usize) {
+        self.job.progress(completed, total);
+        let _ = self.app.emit(
+            "duplicate-scan-progress",
+            json!({
+                "scan_id": self.id(),
+                "dir": self.dir.to_string_lossy(),
+                "phase": phase,
+                "completed": completed,
+                "total": total,
+            }),
+        );
+    }
+
+    fn run<T, F>(self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Self) -> Result<T, String>,
+    {
+        let result = f(&self);
+        self.job.finish(&result);
+        result
+    }
+}
+
+fn refresh_in_background(
+    app: AppHandle<Wry>,
+    dir: PathBuf,
+    cfg: HashConfig,
+    limits: DuplicatesSettings,
+) {
+    if !REFRESHING_INDEXES.lock().unwrap().insert(dir.clone()) {
+        return;
+    }
+    task::spawn_blocking(move || {
+        let before = cached_groups(&dir, &cfg).map(|g| group_signature(&g));
+        let job = ScanJob::start(app.clone(), None, &dir);
+        let result = job.run(|job| scan_album_duplicates(&dir, &cfg, &limits, job));
+        REFRESHING_INDEXES.lock().unwrap().remove(&dir);
+        match result {
+            Ok(groups) => {
+                if before != Some(group_signature(&groups)) {
+                    emit_duplicates_updated(&app, &dir, &groups);
+                }
+            }
+            Err(e) => log::warn!("duplicates: refresh failed {}: {}", dir.display(), e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn find_duplicates(
+    app: AppHandle<Wry>,
+    dir: String,
+    scan_id: Option<String>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let dirp = PathBuf::from(&dir);
+    if !dirp.is_dir() {
+        return Err(format!("{} is not a directory", dirp.display()));
+    }
+
+    let settings = settings_for(&dirp);
+    let hash_cfg = settings.hash_config();
+    let limits = settings.duplicates.clone();
+
+    if let Some(groups) = cached_groups(&dirp, &hash_cfg) {
+        log::info!(
+            "duplicates: serving {} indexed groups for {}",
+            groups.len(),
+            dirp.display()
+        );
+        if !groups_present(&dirp, &groups) {
+            refresh_in_background(app, dirp, hash_cfg, limits);
+        }
+        return Ok(groups);
+    }
+
+    let job = ScanJob::start(app, scan_id, &dirp);
+    task::spawn_blocking(move || {
+        job.run(|job| scan_album_duplicates(&dirp, &hash_cfg, &limits, job))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn mark_non_duplicates(dir: String, files: Vec<String>) -> Result<(), String> {
+    let dirp = PathBuf::from(&dir);
+    if !dirp.is_dir() {
         return Err(format!("{} is not a directory", dirp.display()));
     }
+    ensure_writable(&dirp)?;
+    if files.len() < 2 {
+        return Ok(());
+    }
+
+    add_ignore_group(&dirp, files)?;
+    invalidate_duplicate_index(&dirp);
+    Ok(())
+}
+
+fn library_key(album_rel: &str, name: &str) -> String {
+    if album_rel.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", album_rel, name)
+    }
+}
+
+#[tauri::command]
+pub async fn find_library_duplicates(
+    app: AppHandle<Wry>,
+    root_dir: String,
+    scan_id: Option<String>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let settings = settings_for(&root);
+    let hash_cfg = settings.hash_config();
+    let limits = settings.duplicates.clone();
+    let job = ScanJob::start(app, scan_id, &root);
+
+    task::spawn_blocking(move || job.run(|job| library_scan(&root, &hash_cfg, &limits, job)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn library_scan(
+    root: &Path,
+    hash_cfg: &HashConfig,
+    limits: &DuplicatesSettings,
+    job: &ScanJob,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let t0 = std::time::Instant::now();
+    log::info!(
+        "duplicates: library scan start {} scan={}",
+        root.display(),
+        job.id()
+    );
+
+    let mut ignored = load_ignore_set(root);
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut videos: Vec<VideoEntry> = Vec::new();
+    let mut candidates: Vec<ExactCandidate> = Vec::new();
+    let mut albums: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+    for album in walk_album_paths(root)? {
+        let files = album_media_files(&album)?;
+        if !files.is_empty() {
+            albums.push((album, files));
+        }
+    }
+    let total: usize = albums.iter().map(|(_, files)| files.len()).sum();
+    let chunk_size = match limits.scan_chunk_size {
+        0 => total.max(1),
+        n => n as usize,
+    };
+
+    let mut hashed = 0usize;
+    job.progress(ScanPhase::Hashing, hashed, total);
+    'albums: for (album, files) in &albums {
+        for chunk in files.chunks(chunk_size) {
+            if job.check().is_err() {
+                break 'albums;
+            }
+            if let Err(e) = wait_for_album_hashes(
+                album,
+                chunk,
+                hash_cfg,
+                TaskPriority::High,
+                Some(job.job.cancel_flag()),
+            ) {
+                if job.cancelled() {
+                    break 'albums;
+                }
+                return Err(e);
+            }
+            hashed += chunk.len();
+            job.progress(ScanPhase::Hashing, hashed, total);
+        }
+        let album_meta = read_album_meta(album);
+        let album_rel = album
+            .strip_prefix(root)
+            .map(normalized_relative_path)
+            .unwrap_or_default();
+
+        for group in &album_meta.ignore_groups {
+            ignored.add(group.files.iter().map(|f| library_key(&album_rel, f)));
+        }
+
+        candidates.extend(files.iter().filter_map(|p| {
+            let name = p.file_name()?.to_str()?;
+            exact_candidate(library_key(&album_rel, name), p, &album_meta)
+        }));
+
+        for p in files {
+            let Some(name) = p.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if is_hash_blocked_in_meta(&album_meta, p) {
+                continue;
+            }
+            if has_extension(p, VIDEO_EXTENSIONS) {
+                if let Some(frames) = load_cached_video_frames(&album_meta, name, hash_cfg) {
+                    videos.push(VideoEntry {
+                        name: library_key(&album_rel, name),
+                        frames,
+                    });
+                }
+                continue;
+            }
+            if let Some(bits) = load_cached_bits_from_meta(&album_meta, name, hash_cfg) {
+                let blocks = blocks_from_bits(&bits);
+                entries.push(Entry {
+                    name: library_key(&album_rel, name),
+                    bits,
+                    blocks,
+                    signals: load_cached_signals(&album_meta, name, hash_cfg),
+                });
+            }
+        }
+    }
+
+    let compared = entries.len() + videos.len();
+    job.progress(ScanPhase::Comparing, 0, compared);
+    let (groups, stats) = build_groups(candidates, entries, videos, hash_cfg, |a, b| {
+        ignored.contains(a, b)
+    });
+    job.progress(ScanPhase::Done, compared, compared);
+
+    log::info!(
+        "duplicates: library done {} groups ({} exact) from {} cached hashes across {} albums {} threshold={} elapsed={:?} candidates={} dist_pass={} unions={}",
+        groups.len(),
+        groups.iter().filter(|g| matches!(g.kind, DuplicateKind::Exact)).count(),
+        stats.hashes,
+        albums.len(),
+        root.display(),
+        hash_cfg.effective_threshold,
+        t0.elapsed(),
+        stats.candidates,
+        stats.dist_pass,
+        stats.unions
+    );
+    Ok(groups)
+}
+
+#[tauri::command]
+pub fn mark_library_non_duplicates(root_dir: String, files: Vec<String>) -> Result<(), String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    ensure_writable(&root)?;
     if files.len() < 2 {
         return Ok(());
     }
 
-    let mut set = load_ignore_set(&dirp);
-    for i in 0..files.len() {
-        for j in (i + 1)..files.len() {
-            set.insert(pair_key(&files[i], &files[j]));
+    add_ignore_group(&root, files)
+}
+
+#[derive(Serialize)]
+pub struct IgnoredFile {
+    name: String,
+    path: String,
+    thumb: Option<String>,
+    exists: bool,
+}
+
+#[derive(Serialize)]
+pub struct IgnoredGroup {
+    id: String,
+    files: Vec<IgnoredFile>,
+}
+
+fn describe_ignored_file(dir: &Path, name: &str) -> IgnoredFile {
+    let path = dir.join(name);
+    let thumb = media_thumb_dir(&path)
+        .and_then(|thumb_dir| thumb_path(&path, &thumb_dir).ok())
+        .filter(|t| t.is_file())
+        .map(|t| t.to_string_lossy().into_owned());
+    IgnoredFile {
+        name: name.to_string(),
+        exists: path.is_file(),
+        path: path.to_string_lossy().into_owned(),
+        thumb,
+    }
+}
+
+#[tauri::command]
+pub async fn list_ignored_duplicates(dir: String) -> Result<Vec<IgnoredGroup>, String> {
+    let dirp = PathBuf::from(&dir);
+    if !dirp.is_dir() {
+        return Err(format!("{} is not a directory", dirp.display()));
+    }
+    task::spawn_blocking(move || {
+        read_album_meta(&dirp)
+            .ignore_groups
+            .into_iter()
+            .map(|group| IgnoredGroup {
+                id: group.id,
+                files: group
+                    .files
+                    .iter()
+                    .map(|f| describe_ignored_file(&dirp, f))
+                    .collect(),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn unignore_duplicates(
+    dir: String,
+    group_id: String,
+    files: Option<Vec<String>>,
+) -> Result<bool, String> {
+    let dirp = PathBuf::from(&dir);
+    if !dirp.is_dir() {
+        return Err(format!("{} is not a directory", dirp.display()));
+    }
+    ensure_writable(&dirp)?;
+    let mut album = read_album_meta(&dirp);
+    let Some(pos) = album.ignore_groups.iter().position(|g| g.id == group_id) else {
+        return Ok(false);
+    };
+    let group = album.ignore_groups.remove(pos);
+
+    let released: HashSet<String> = files.unwrap_or_default().into_iter().collect();
+    if !released.is_empty() {
+        let kept: Vec<String> = group
+            .files
+            .into_iter()
+            .filter(|f| !released.contains(f))
+            .collect();
+        album.add_ignore_group(kept);
+    }
+
+    write_album_meta(&dirp, &album)?;
+    invalidate_duplicate_index(&dirp);
+    Ok(true)
+}
+
+fn migrate_library_ignores<F>(root: &Path, rename: F)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut meta = read_album_meta(root);
+    if meta.ignore_groups.is_empty() || !meta.rename_ignored(rename) {
+        return;
+    }
+    if let Err(e) = write_album_meta(root, &meta) {
+        log::warn!(
+            "duplicates: failed to migrate ignores in {}: {}",
+            root.display(),
+            e
+        );
+    }
+}
+
+pub(crate) fn migrate_moved_media_ignores(
+    source_dir: &Path,
+    target_dir: &Path,
+    moves: &[(String, String)],
+    split: Vec<SplitIgnore>,
+) {
+    let Some(root) = root_for(source_dir) else {
+        return;
+    };
+    let (Ok(source_rel), Ok(target_rel)) = (
+        source_dir.strip_prefix(&root).map(normalized_relative_path),
+        target_dir.strip_prefix(&root).map(normalized_relative_path),
+    ) else {
+        return;
+    };
+    let renames: HashMap<String, String> = moves
+        .iter()
+        .map(|(s, t)| (library_key(&source_rel, s), library_key(&target_rel, t)))
+        .collect();
+    let mut meta = read_album_meta(&root);
+    let mut changed = meta.rename_ignored(|key| renames.get(key).cloned());
+    for group in split {
+        let files: Vec<String> = group
+            .kept
+            .iter()
+            .map(|f| library_key(&source_rel, f))
+            .chain(group.moved.iter().map(|f| library_key(&target_rel, f)))
+            .collect();
+        changed |= meta.add_ignore_group(files).is_some();
+    }
+    if !changed {
+        return;
+    }
+    if let Err(e) = write_album_meta(&root, &meta) {
+        log::warn!(
+            "duplicates: failed to migrate ignores in {}: {}",
+            root.display(),
+            e
+        );
+    }
+}
+
+pub(crate) fn migrate_album_ignores(root: &Path, old_rel: &str, new_rel: &str) {
+    let prefix = format!("{}/", old_rel);
+    migrate_library_ignores(root, |key| {
+        key.strip_prefix(&prefix)
+            .map(|rest| library_key(new_rel, rest))
+    });
+}
+
+fn is_favorite(path: &Path) -> bool {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return false;
+    };
+    read_album_meta(dir)
+        .files
+        .get(name)
+        .map(|e| e.favorite)
+        .unwrap_or(false)
+}
+
+fn resolve_group_member(base: &Path, scope: &Path, name: &str) -> Result<Option<PathBuf>, String> {
+    let Ok(path) = base.join(name).canonicalize() else {
+        return Ok(None);
+    };
+    if !path.starts_with(scope) {
+        return Err(format!("{} is outside the library", name));
+    }
+    if !path.is_file()
+        || !(has_extension(&path, IMAGE_EXTENSIONS) || has_extension(&path, VIDEO_EXTENSIONS))
+    {
+        return Err(format!("{} is not a media file", name));
+    }
+    Ok(Some(path))
+}
+
+#[tauri::command]
+pub async fn resolve_duplicate_group(
+    dir: String,
+    keep: String,
+    files: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let base = PathBuf::from(&dir);
+    if !base.is_dir() {
+        return Err(format!("{} is not a directory", base.display()));
+    }
+
+    task::spawn_blocking(move || {
+        let base = base.canonicalize().map_err(|e| e.to_string())?;
+        let scope = root_for(&base)
+            .and_then(|root| root.canonicalize().ok())
+            .unwrap_or_else(|| base.clone());
+        let keep_path = resolve_group_member(&base, &scope, &keep)?
+            .ok_or_else(|| format!("{} does not exist", base.join(&keep).display()))?;
+        let keep_dir = keep_path.parent().ok_or("Invalid path")?.to_path_buf();
+        let keep_name = keep_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("Invalid filename")?
+            .to_string();
+
+        let mut others: Vec<(String, PathBuf)> = Vec::new();
+        for f in files {
+            let Some(p) = resolve_group_member(&base, &scope, &f)? else {
+                continue;
+            };
+            if p != keep_path && !others.iter().any(|(_, o)| *o == p) {
+                others.push((f, p));
+            }
         }
+        if others.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        ensure_writable(&keep_dir)?;
+        for (_, p) in &others {
+            ensure_writable(p.parent().ok_or("Invalid path")?)?;
+        }
+
+        let keep_shoot = file_meta(&keep_path).and_then(|m| m.s);
+        let favorite = others.iter().any(|(_, p)| is_favorite(p));
+        let earliest = others
+            .iter()
+            .filter_map(|(_, p)| file_meta(p).and_then(|m| m.s))
+            .min()
+            .filter(|s| keep_shoot.map(|k| *s < k).unwrap_or(true));
+
+        if favorite && !is_favorite(&keep_path) {
+            set_media_favorite(keep_path.to_string_lossy().into_owned(), true)?;
+        }
+        if let Some(timestamp) = earliest {
+            set_media_timestamp(
+                keep_dir.to_string_lossy().into_owned(),
+                vec![keep_name],
+                timestamp,
+            )?;
+        }
+
+        trash::delete_all(others.iter().map(|(_, p)| p)).map_err(|e| e.to_string())?;
+
+        let mut by_dir: HashMap<PathBuf, Vec<String>> = HashMap::new();
+        for (_, p) in &others {
+            let (Some(dir), Some(name)) = (p.parent(), p.file_name().and_then(|n| n.to_str()))
+            else {
+                continue;
+            };
+            by_dir
+                .entry(dir.to_path_buf())
+                .or_default()
+                .push(name.to_string());
+        }
+        for (dir, names) in by_dir {
+            invalidate_duplicate_index(&dir);
+            let thumb_dir = album_thumb_dir(&dir);
+            for name in &names {
+                remove_thumb_artifacts(&thumb_dir, name);
+            }
+            remove_file_meta_entries(&dir, &names)?;
+        }
+
+        log::info!(
+            "duplicates: kept {} trashed {} favorite={} timestamp={:?}",
+            keep_path.display(),
+            others.len(),
+            favorite,
+            earliest
+        );
+        Ok(others.into_iter().map(|(f, _)| f).collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SimilarScope {
+    #[default]
+    Album,
+    Root,
+}
+
+#[derive(Serialize)]
+pub struct SimilarMatch {
+    pub path: String,
+    pub distance: u32,
+}
+
+fn hamming(a: &[u64], b: &[u64]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum()
+}
+
+fn nearest_matches<F>(
+    index: &BlockIndex,
+    entries: &[IndexedHash],
+    target: &[u64],
+    max_distance: u32,
+    limit: usize,
+    accept: F,
+) -> (Vec<(u32, usize)>, bool)
+where
+    F: Fn(&IndexedHash) -> bool,
+{
+    let target_blocks = blocks_from_bits(target);
+    let mut seen = vec![false; entries.len()];
+    let mut matches: Vec<(u32, usize)> = Vec::new();
+    for (bi, block) in target_blocks.iter().enumerate() {
+        for &idx in index.bucket(bi, *block) {
+            let idx = idx as usize;
+            if seen[idx] {
+                continue;
+            }
+            seen[idx] = true;
+            let distance = hamming(target, &entries[idx].bits);
+            if distance <= max_distance && accept(&entries[idx]) {
+                matches.push((distance, idx));
+            }
+        }
+    }
+    matches.sort_unstable();
+
+    let blocks = target_blocks.len() as u32;
+    let complete = max_distance < blocks
+        || matches
+            .get(limit.saturating_sub(1))
+            .map(|(d, _)| *d < blocks)
+            .unwrap_or(false);
+    if !complete {
+        for (idx, e) in entries.iter().enumerate() {
+            if seen[idx] {
+                continue;
+            }
+            let distance = hamming(target, &e.bits);
+            if distance <= max_distance && accept(e) {
+                matches.push((distance, idx));
+            }
+        }
+        matches.sort_unstable();
+    }
+    (matches, complete)
+}
+
+#[tauri::command]
+pub async fn find_similar(
+    path: String,
+    max_distance: Option<u32>,
+    limit: Option<usize>,
+    scope: Option<SimilarScope>,
+) -> Result<Vec<SimilarMatch>, String> {
+    let target = PathBuf::from(&path);
+    if !target.is_file() {
+        return Err(format!("{} does not exist", path));
+    }
+    if !has_extension(&target, IMAGE_EXTENSIONS) {
+        return Err(format!("{} is not an image", path));
+    }
+    let album = target.parent().ok_or("Invalid path")?.to_path_buf();
+    let settings = settings_for(&target);
+    let hash_cfg = settings.hash_config();
+    let max_distance = max_distance
+        .unwrap_or(hash_cfg.effective_threshold)
+        .min(hash_cfg.bits);
+    let limit = limit.unwrap_or(SIMILAR_DEFAULT_LIMIT);
+
+    task::spawn_blocking(move || {
+        let t0 = std::time::Instant::now();
+        let target_bits =
+            compute_hash_for_path(&read_album_meta(&album), &target, &hash_cfg, &settings)
+                .ok_or_else(|| format!("Failed to hash {}", target.display()))?;
+        let target_name = target.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let target_signals = load_cached_signals(&read_album_meta(&album), target_name, &hash_cfg);
+        let agrees = |signals: &Signals| {
+            signals_agree(&target_signals, signals, max_distance, &hash_cfg)
+        };
+
+        let albums = match scope.unwrap_or_default() {
+            SimilarScope::Album => vec![album.clone()],
+            SimilarScope::Root => {
+                let root = root_for(&album).unwrap_or_else(|| album.clone());
+                walk_album_paths(&root)?
+            }
+        };
+
+        let mut matches: Vec<(u32, PathBuf)> = Vec::new();
+        let mut hashes = 0usize;
+        let mut indexed = 0usize;
+        for dir in &albums {
+            let mut indexes = DUPLICATE_INDEXES.lock().unwrap();
+            if let Some(index) = loaded_index(&mut indexes, dir)
+                .filter(|index| index.hash_version == hash_cfg.hash_version)
+            {
+                index.ensure_blocks();
+                let Some(blocks) = &index.blocks else {
+                    continue;
+                };
+                let (found, complete) = nearest_matches(
+                    blocks,
+                    &index.entries,
+                    &target_bits,
+                    max_distance,
+                    limit.saturating_add(1),
+                    |e| agrees(&e.signals),
+                );
+                hashes += index.entries.len();
+                indexed += complete as usize;
+                matches.extend(
+                    found
+                        .into_iter()
+                        .map(|(distance, idx)| (distance, dir.join(&index.entries[idx].name))),
+                );
+                continue;
+            }
+            drop(indexes);
+
+            let meta = read_album_meta(dir);
+            for name in meta.files.keys() {
+                if is_hash_blocked_in_meta(&meta, &dir.join(name)) {
+                    continue;
+                }
+                if let Some(bits) = load_cached_bits_from_meta(&meta, name, &hash_cfg) {
+                    hashes += 1;
+                    let distance = hamming(&target_bits, &bits);
+                    if distance <= max_distance
+                        && agrees(&load_cached_signals(&meta, name, &hash_cfg))
+                    {
+                        matches.push((distance, dir.join(name)));
+                    }
+                }
+            }
+        }
+        matches.sort();
+
+        let found: Vec<SimilarMatch> = matches
+            .into_iter()
+            .filter(|(_, p)| *p != target && p.is_file())
+            .take(limit)
+            .map(|(distance, p)| SimilarMatch {
+                path: p.to_string_lossy().into_owned(),
+                distance,
+            })
+            .collect();
+
+        log::info!(
+            "duplicates: similar to {} found={} hashes={} albums={} max_distance={} indexed={} elapsed={:?}",
+            target.display(),
+            found.len(),
+            hashes,
+            albums.len(),
+            max_distance,
+            indexed,
+            t0.elapsed()
+        );
+        Ok(found)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEAR: u64 = 0b1;
+    const EVERY_BLOCK: u64 = 1 | (1 << 16) | (1 << 32) | (1 << 48);
+
+    fn indexed(bits: &[u64]) -> (BlockIndex, Vec<IndexedHash>) {
+        let entries: Vec<IndexedHash> = bits
+            .iter()
+            .enumerate()
+            .map(|(i, b)| IndexedHash {
+                name: format!("{}.jpg", i),
+                bits: vec![*b],
+                signals: Signals::default(),
+            })
+            .collect();
+        let index = BlockIndex::build(entries.iter().map(|e| blocks_from_bits(&e.bits)));
+        (index, entries)
+    }
+
+    #[test]
+    fn bucket_lookup_matches_block_position_and_value() {
+        let (mut index, _) = indexed(&[0, NEAR]);
+        assert_eq!(index.bucket(0, 0), &[0]);
+        assert_eq!(index.bucket(0, 1), &[1]);
+        assert_eq!(index.bucket(1, 0), &[0, 1]);
+        assert!(index.bucket(1, 1).is_empty());
+
+        index.remove(0, &blocks_from_bits(&[0]));
+        assert!(index.bucket(0, 0).is_empty());
+        assert_eq!(index.bucket(1, 0), &[1]);
+    }
+
+    #[test]
+    fn distances_below_block_count_are_found_through_buckets() {
+        let (index, entries) = indexed(&[NEAR, EVERY_BLOCK, u64::MAX]);
+        let (matches, complete) = nearest_matches(&index, &entries, &[0], 3, 10, |_| true);
+        assert!(complete);
+        assert_eq!(matches, vec![(1, 0)]);
+    }
+
+    #[test]
+    fn full_limit_below_block_count_skips_the_fallback() {
+        let (index, entries) = indexed(&[NEAR, EVERY_BLOCK, u64::MAX]);
+        let (matches, complete) = nearest_matches(&index, &entries, &[0], 64, 1, |_| true);
+        assert!(complete);
+        assert_eq!(matches, vec![(1, 0)]);
+    }
+
+    #[test]
+    fn incomplete_lookup_falls_back_to_a_full_scan() {
+        let (index, entries) = indexed(&[NEAR, EVERY_BLOCK, u64::MAX]);
+        let (matches, complete) = nearest_matches(&index, &entries, &[0], 64, 3, |_| true);
+        assert!(!complete);
+        assert_eq!(matches, vec![(1, 0), (4, 1), (64, 2)]);
     }
-    save_ignore_set(&dirp, &set)
 }
@@ -0,0 +1,386 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::preload::{cancel_preload_service, preload_app};
+
+pub(crate) const PRELOAD_JOB: &str = "preload";
+pub(crate) const THUMBNAILS_JOB: &str = "thumbnails";
+pub(crate) const METADATA_JOB: &str = "metadata";
+pub(crate) const HASHES_JOB: &str = "hashes";
+
+const MAX_JOB_ERRORS: usize = 20;
+const MAX_FINISHED_JOBS: usize = 50;
+const JOB_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Preload,
+    Thumbnails,
+    Metadata,
+    Hashes,
+    RebuildThumbnails,
+    RebuildMetadata,
+    DuplicateScan,
+    CheckLibrary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Failed,
+    Done,
+}
+
+impl JobState {
+    fn finished(self) -> bool {
+        matches!(
+            self,
+            JobState::Cancelled | JobState::Failed | JobState::Done
+        )
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct JobInfo {
+    id: String,
+    kind: JobKind,
+    state: JobState,
+    target: Option<String>,
+    completed: usize,
+    total: usize,
+    errors: Vec<String>,
+    created_at: u64,
+    updated_at: u64,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    service: bool,
+    paused: bool,
+    cancel: Arc<AtomicBool>,
+    last_emit: Option<Instant>,
+}
+
+static JOBS: Lazy<Mutex<HashMap<String, JobEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static JOBS_CV: Condvar = Condvar::new();
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn emit_job(entry: &mut JobEntry, force: bool) {
+    let now = Instant::now();
+    if !force
+        && entry
+            .last_emit
+            .map(|t| now.duration_since(t) < JOB_EMIT_INTERVAL)
+            .unwrap_or(false)
+    {
+        return;
+    }
+    entry.last_emit = Some(now);
+    if let Some(app) = preload_app() {
+        let _ = app.emit("job-updated", &entry.info);
+    }
+}
+
+fn update<F>(id: &str, f: F)
+where
+    F: FnOnce(&mut JobEntry) -> bool,
+{
+    let mut jobs = JOBS.lock().unwrap();
+    if let Some(entry) = jobs.get_mut(id) {
+        let prev = entry.info.state;
+        let force = f(entry) || entry.info.state != prev;
+        entry.info.updated_at = now_ms();
+        emit_job(entry, force);
+    }
+}
+
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(u64, String)> = jobs
+        .iter()
+        .filter(|(_, e)| !e.service && e.info.state.finished())
+        .map(|(id, e)| (e.info.updated_at, id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+        jobs.remove(id);
+    }
+}
+
+fn push_error(info: &mut JobInfo, message: String) {
+    if info.errors.len() >= MAX_JOB_ERRORS {
+        info.errors.remove(0);
+    }
+    info.errors.push(message);
+}
+
+pub(crate) fn job_state(id: &str) -> Option<JobState> {
+    JOBS.lock().unwrap().get(id).map(|e| e.info.state)
+}
+
+pub(crate) fn wait_while_paused(id: &str) {
+    let mut jobs = JOBS.lock().unwrap();
+    while jobs
+        .get(id)
+        .map(|e| e.paused && !e.cancel.load(Ordering::SeqCst))
+        .unwrap_or(false)
+    {
+        jobs = JOBS_CV.wait(jobs).unwrap();
+    }
+}
+
+pub(crate) fn update_service(id: &str, kind: JobKind, completed: usize, total: usize) {
+    let mut jobs = JOBS.lock().unwrap();
+    let entry = jobs.entry(id.to_string()).or_insert_with(|| {
+        let now = now_ms();
+        JobEntry {
+            info: JobInfo {
+                id: id.to_string(),
+                kind,
+                state: JobState::Done,
+                target: None,
+                completed: 0,
+                total: 0,
+                errors: Vec::new(),
+                created_at: now,
+                updated_at: now,
+            },
+            service: true,
+            paused: false,
+            cancel: Arc::new(AtomicBool::new(false)),
+            last_emit: None,
+        }
+    });
+    let prev = entry.info.state;
+    let outstanding = total > completed;
+    entry.info.completed = completed;
+    entry.info.total = total;
+    entry.info.state = if entry.paused {
+        JobState::Paused
+    } else if outstanding {
+        JobState::Running
+    } else if prev == JobState::Cancelled {
+        JobState::Cancelled
+    } else {
+        JobState::Done
+    };
+    if outstanding {
+        entry.cancel.store(false, Ordering::SeqCst);
+    }
+    entry.info.updated_at = now_ms();
+    let force = entry.info.state != prev;
+    emit_job(entry, force);
+}
+
+pub(crate) fn service_error(id: &str, message: String) {
+    update(id, |entry| {
+        push_error(&mut entry.info, message);
+        false
+    });
+}
+
+pub(crate) struct JobHandle {
+    id: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    pub(crate) fn start(kind: JobKind, id: Option<String>, target: Option<&Path>) -> Self {
+        let id =
+            id.unwrap_or_else(|| format!("job-{}", NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst)));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let now = now_ms();
+        let mut entry = JobEntry {
+            info: JobInfo {
+                id: id.clone(),
+                kind,
+                state: JobState::Queued,
+                target: target.map(|t| t.to_string_lossy().into_owned()),
+                completed: 0,
+                total: 0,
+                errors: Vec::new(),
+                created_at: now,
+                updated_at: now,
+            },
+            service: false,
+            paused: false,
+            cancel: cancel.clone(),
+            last_emit: None,
+        };
+        emit_job(&mut entry, true);
+        let mut jobs = JOBS.lock().unwrap();
+        jobs.insert(id.clone(), entry);
+        prune_finished(&mut jobs);
+        Self { id, cancel }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn cancel_flag(&self) -> &AtomicBool {
+        &self.cancel
+    }
+
+    pub(crate) fn checkpoint(&self) -> Result<(), String> {
+        wait_while_paused(&self.id);
+        if self.cancel.load(Ordering::SeqCst) {
+            return Err(format!("Job {} cancelled", self.id));
+        }
+        update(&self.id, |entry| {
+            if entry.info.state == JobState::Queued {
+                entry.info.state = JobState::Running;
+            }
+            false
+        });
+        Ok(())
+    }
+
+    pub(crate) fn progress(&self, completed: usize, total: usize) {
+        update(&self.id, |entry| {
+            entry.info.completed = completed;
+            entry.info.total = total;
+            if entry.info.state == JobState::Queued {
+                entry.info.state = JobState::Running;
+            }
+            false
+        });
+    }
+
+    pub(crate) fn error(&self, message: String) {
+        update(&self.id, |entry| {
+            push_error(&mut entry.info, message);
+            false
+        });
+    }
+
+    pub(crate) fn finish<T>(&self, result: &Result<T, String>) {
+        let cancelled = self.cancel.load(Ordering::SeqCst);
+        update(&self.id, |entry| {
+            entry.paused = false;
+            entry.info.state = match result {
+                _ if cancelled => JobState::Cancelled,
+                Ok(_) => JobState::Done,
+                Err(e) => {
+                    push_error(&mut entry.info, e.clone());
+                    JobState::Failed
+                }
+            };
+            true
+        });
+    }
+}
+
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        let cancelled = self.cancel.load(Ordering::SeqCst);
+        update(&self.id, |entry| {
+            if entry.info.state.finished() {
+                return false;
+            }
+            entry.paused = false;
+            entry.info.state = if cancelled {
+                JobState::Cancelled
+            } else {
+                JobState::Done
+            };
+            true
+        });
+        JOBS_CV.notify_all();
+    }
+}
+
+#[tauri::command]
+pub fn list_jobs() -> Vec<JobInfo> {
+    let jobs = JOBS.lock().unwrap();
+    let mut out: Vec<JobInfo> = jobs.values().map(|e| e.info.clone()).collect();
+    out.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    out
+}
+
+#[tauri::command]
+pub fn pause_job(job_id: String) -> Result<bool, String> {
+    let mut paused = false;
+    update(&job_id, |entry| {
+        if entry.info.state.finished() && !entry.service {
+            return false;
+        }
+        entry.paused = true;
+        entry.info.state = JobState::Paused;
+        paused = true;
+        true
+    });
+    Ok(paused)
+}
+
+#[tauri::command]
+pub fn resume_job(job_id: String) -> Result<bool, String> {
+    let mut resumed = false;
+    update(&job_id, |entry| {
+        if !entry.paused {
+            return false;
+        }
+        entry.paused = false;
+        entry.info.state = if entry.service && entry.info.total <= entry.info.completed {
+            JobState::Done
+        } else {
+            JobState::Running
+        };
+        resumed = true;
+        true
+    });
+    JOBS_CV.notify_all();
+    Ok(resumed)
+}
+
+#[tauri::command]
+pub fn cancel_job(job_id: String) -> Result<bool, String> {
+    let mut service = false;
+    let mut cancelled = false;
+    update(&job_id, |entry| {
+        if entry.info.state.finished() {
+            return false;
+        }
+        entry.cancel.store(true, Ordering::SeqCst);
+        entry.paused = false;
+        service = entry.service;
+        if service {
+            entry.info.state = JobState::Cancelled;
+        }
+        cancelled = true;
+        true
+    });
+    JOBS_CV.notify_all();
+    if service {
+        cancel_preload_service(&job_id);
+    }
+    if cancelled {
+        log::info!("jobs: cancel requested for {}", job_id);
+    }
+    Ok(cancelled)
+}
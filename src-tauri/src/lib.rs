@@ -1,12 +1,16 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod album;
+mod artifacts;
 mod clipboard;
 mod constants;
 mod debugging;
 mod duplicates;
+mod jobs;
+mod library;
 mod metadata;
 mod preload;
+mod scheduler;
 mod settings;
 mod thumb;
 mod util;
@@ -15,17 +19,33 @@ use anyhow::anyhow;
 use tauri::Manager;
 
 pub use album::{
-    add_media_files, get_album_media, get_album_size, get_albums_detached, list_favorites,
-    move_album, move_media, move_media_batch, register_new_media, rename_album,
+    add_media_files, get_album_media, get_album_size, get_albums_detached, get_library_tree,
+    list_favorites, move_album, move_media, move_media_batch, register_new_media, rename_album,
 };
+pub use artifacts::{is_library_read_only, migrate_artifacts};
 pub use clipboard::set_clipboard_files;
 pub use debugging::{
-    clear_room237_artifacts, rebuild_metadata, rebuild_thumbnails, reset_duplicates,
+    check_library, clear_room237_artifacts, rebuild_metadata, rebuild_thumbnails,
+    remove_heic_sidecars, reset_duplicates,
 };
-pub use duplicates::{find_duplicates, mark_non_duplicates};
-pub use metadata::{get_file_metadata, set_media_favorite, set_media_timestamp};
-pub use preload::{is_preloading, lock_until_preloaded, set_allow_open};
+pub use duplicates::{
+    find_duplicates, find_library_duplicates, find_similar, list_ignored_duplicates,
+    mark_library_non_duplicates, mark_non_duplicates, resolve_duplicate_group, unignore_duplicates,
+};
+pub use jobs::{cancel_job, list_jobs, pause_job, resume_job};
+pub use library::{
+    add_library, list_libraries, relocate_library, remove_library, set_library_overrides,
+    LibraryState,
+};
+pub use metadata::{
+    get_file_metadata, list_failures, retry_failures, set_media_favorite, set_media_timestamp,
+};
+pub use preload::{
+    close_library_root, is_preloading, lock_until_preloaded, prioritize_media, set_allow_open,
+};
+pub use scheduler::notify_user_activity;
 pub use settings::{get_settings, reset_settings, update_settings, SettingsState};
+pub use thumb::{get_animated_preview, get_thumb, set_poster_frame};
 pub use util::get_file_manager_name;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -36,22 +56,40 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .invoke_handler(tauri::generate_handler![
             get_file_metadata,
+            list_failures,
+            retry_failures,
             get_album_media,
             get_album_size,
             get_albums_detached,
+            get_library_tree,
+            close_library_root,
             move_media,
             move_media_batch,
             is_preloading,
             lock_until_preloaded,
             set_allow_open,
+            prioritize_media,
+            notify_user_activity,
             rebuild_thumbnails,
             rebuild_metadata,
             register_new_media,
             find_duplicates,
             mark_non_duplicates,
+            find_library_duplicates,
+            mark_library_non_duplicates,
+            list_ignored_duplicates,
+            unignore_duplicates,
+            resolve_duplicate_group,
+            find_similar,
+            list_jobs,
+            pause_job,
+            resume_job,
+            cancel_job,
             reset_duplicates,
             add_media_files,
+            check_library,
             clear_room237_artifacts,
+            remove_heic_sidecars,
             set_media_favorite,
             list_favorites,
             rename_album,
@@ -62,6 +100,16 @@ pub fn run() {
             update_settings,
             reset_settings,
             set_clipboard_files,
+            get_thumb,
+            get_animated_preview,
+            set_poster_frame,
+            migrate_artifacts,
+            is_library_read_only,
+            list_libraries,
+            add_library,
+            remove_library,
+            relocate_library,
+            set_library_overrides,
         ])
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -78,9 +126,22 @@ pub fn run() {
             let settings_state = SettingsState::load(&app.handle())
                 .map_err(|e| anyhow!("Failed to load settings: {e}"))?;
             app.manage(settings_state);
+            let library_state = LibraryState::load(&app.handle())
+                .map_err(|e| anyhow!("Failed to load library registry: {e}"))?;
+            app.manage(library_state);
+            artifacts::init_cache_root(&app.handle())
+                .map_err(|e| anyhow!("Failed to resolve artifact cache: {e}"))?;
+            preload::init_queue_store(&app.handle())
+                .map_err(|e| anyhow!("Failed to resolve preload queue store: {e}"))?;
             ffmpeg_sidecar::download::auto_download().unwrap();
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("tauri run failed");
+        .build(tauri::generate_context!())
+        .expect("tauri build failed")
+        .run(|_, event| {
+            if let tauri::RunEvent::Exit = event {
+                preload::save_queue_state();
+                duplicates::flush_duplicate_indexes();
+            }
+        });
 }
@@ -0,0 +1,340 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tauri::{path::BaseDirectory, AppHandle, Manager, Wry};
+
+use crate::{
+    artifacts::{move_root_cache, relocate_root_cache, root_id},
+    preload::unregister_root,
+    settings::{read_settings, AdvancedSettings, DuplicatesSettings, ThumbnailSettings},
+};
+
+const LIBRARIES_FILE: &str = "libraries.json";
+
+static LIBRARY_HANDLE: OnceCell<LibraryState> = OnceCell::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    pub id: String,
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub last_opened: Option<u64>,
+    #[serde(default)]
+    pub duplicates: Option<DuplicatesSettings>,
+    #[serde(default)]
+    pub thumbnails: Option<ThumbnailSettings>,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl LibraryEntry {
+    fn clamp(mut self) -> Self {
+        self.duplicates = self.duplicates.map(DuplicatesSettings::clamp);
+        self.thumbnails = self.thumbnails.map(ThumbnailSettings::clamp);
+        self
+    }
+
+    fn apply(&self, settings: &mut AdvancedSettings) {
+        if let Some(duplicates) = &self.duplicates {
+            settings.duplicates = duplicates.clone();
+        }
+        if let Some(thumbnails) = &self.thumbnails {
+            settings.thumbnails = thumbnails.clone();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LibraryRegistry {
+    #[serde(default)]
+    pub libraries: Vec<LibraryEntry>,
+}
+
+impl LibraryRegistry {
+    fn entry_for(&self, path: &Path) -> Option<&LibraryEntry> {
+        self.libraries
+            .iter()
+            .filter(|e| path.starts_with(&e.path))
+            .max_by_key(|e| Path::new(&e.path).components().count())
+    }
+
+    fn entry_mut(&mut self, id: &str) -> Result<&mut LibraryEntry, String> {
+        self.libraries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| format!("Unknown library {}", id))
+    }
+}
+
+#[derive(Clone)]
+pub struct LibraryState {
+    path: PathBuf,
+    inner: Arc<RwLock<LibraryRegistry>>,
+}
+
+impl LibraryState {
+    pub fn load(app: &AppHandle<Wry>) -> Result<Self, String> {
+        let path = app
+            .path()
+            .resolve(LIBRARIES_FILE, BaseDirectory::AppConfig)
+            .map_err(|e| e.to_string())?;
+        let mut registry = fs::read_to_string(&path)
+            .ok()
+            .and_then(|txt| serde_json::from_str::<LibraryRegistry>(&txt).ok())
+            .unwrap_or_default();
+        registry.libraries = registry
+            .libraries
+            .into_iter()
+            .map(LibraryEntry::clamp)
+            .collect();
+        let state = Self {
+            path,
+            inner: Arc::new(RwLock::new(registry)),
+        };
+        let _ = LIBRARY_HANDLE.set(state.clone());
+        Ok(state)
+    }
+
+    fn persist(&self, registry: &LibraryRegistry) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self) -> LibraryRegistry {
+        self.inner.read().map(|g| g.clone()).unwrap_or_default()
+    }
+
+    fn modify<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut LibraryRegistry) -> Result<T, String>,
+    {
+        let mut guard = self.inner.write().map_err(|e| e.to_string())?;
+        let mut next = guard.clone();
+        let result = f(&mut next)?;
+        self.persist(&next)?;
+        *guard = next;
+        Ok(result)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn display_name(root: &Path) -> String {
+    root.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| root.to_string_lossy().into_owned())
+}
+
+pub fn settings_for(path: &Path) -> AdvancedSettings {
+    let mut settings = read_settings();
+    if let Some(registry) = LIBRARY_HANDLE.get().and_then(|s| s.inner.read().ok()) {
+        if let Some(entry) = registry.entry_for(path) {
+            entry.apply(&mut settings);
+        }
+    }
+    settings
+}
+
+pub(crate) fn forced_read_only(path: &Path) -> bool {
+    LIBRARY_HANDLE
+        .get()
+        .and_then(|s| s.inner.read().ok())
+        .and_then(|registry| registry.entry_for(path).map(|e| e.read_only))
+        .unwrap_or(false)
+}
+
+pub(crate) fn library_root_for(path: &Path) -> Option<PathBuf> {
+    let registry = LIBRARY_HANDLE.get()?.inner.read().ok()?;
+    registry.entry_for(path).map(|e| PathBuf::from(&e.path))
+}
+
+pub(crate) fn library_roots() -> Vec<PathBuf> {
+    LIBRARY_HANDLE
+        .get()
+        .map(|state| {
+            state
+                .get()
+                .libraries
+                .iter()
+                .map(|e| PathBuf::from(&e.path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn record_opened(root: &Path) {
+    let Some(state) = LIBRARY_HANDLE.get() else {
+        return;
+    };
+    let known = state
+        .get()
+        .libraries
+        .iter()
+        .any(|e| Path::new(&e.path) == root);
+    if !known {
+        return;
+    }
+    let result = state.modify(|registry| {
+        for entry in registry.libraries.iter_mut() {
+            if Path::new(&entry.path) == root {
+                entry.last_opened = Some(now_ms());
+            }
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("failed to record {} as opened: {}", root.display(), e);
+    }
+}
+
+#[tauri::command]
+pub fn list_libraries(state: tauri::State<LibraryState>) -> Result<Vec<LibraryEntry>, String> {
+    let mut libraries = state.get().libraries;
+    libraries.sort_by(|a, b| b.last_opened.cmp(&a.last_opened));
+    Ok(libraries)
+}
+
+#[tauri::command]
+pub fn add_library(
+    state: tauri::State<LibraryState>,
+    root_dir: String,
+    name: Option<String>,
+) -> Result<LibraryEntry, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    state.modify(|registry| {
+        if let Some(entry) = registry
+            .libraries
+            .iter_mut()
+            .find(|e| Path::new(&e.path) == root)
+        {
+            if let Some(name) = name {
+                entry.name = name;
+            }
+            return Ok(entry.clone());
+        }
+        let entry = LibraryEntry {
+            id: root_id(&root),
+            path: root_dir.clone(),
+            name: name.unwrap_or_else(|| display_name(&root)),
+            last_opened: None,
+            duplicates: None,
+            thumbnails: None,
+            read_only: false,
+        };
+        registry.libraries.push(entry.clone());
+        log::info!("added library {} ({})", entry.name, entry.path);
+        Ok(entry)
+    })
+}
+
+#[tauri::command]
+pub fn remove_library(state: tauri::State<LibraryState>, id: String) -> Result<bool, String> {
+    let removed = state.modify(|registry| {
+        let idx = registry.libraries.iter().position(|e| e.id == id);
+        Ok(idx.map(|i| registry.libraries.remove(i)))
+    })?;
+    match removed {
+        Some(entry) => {
+            unregister_root(Path::new(&entry.path));
+            log::info!("removed library {} ({})", entry.name, entry.path);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub fn relocate_library(
+    state: tauri::State<LibraryState>,
+    id: String,
+    new_path: String,
+) -> Result<LibraryEntry, String> {
+    let new_root = PathBuf::from(&new_path);
+    if !new_root.is_dir() {
+        return Err(format!("{} is not a directory", new_root.display()));
+    }
+    let new_id = root_id(&new_root);
+    let mut previous: Option<(String, PathBuf)> = None;
+    let result = state.modify(|registry| {
+        if registry
+            .libraries
+            .iter()
+            .any(|e| e.id != id && (e.id == new_id || Path::new(&e.path) == new_root))
+        {
+            return Err(format!("{} is already registered", new_root.display()));
+        }
+        let entry = registry.entry_mut(&id)?;
+        let old_root = PathBuf::from(&entry.path);
+        relocate_root_cache(&entry.id, &new_root)?;
+        previous = Some((entry.id.clone(), old_root.clone()));
+        if entry.name == display_name(&old_root) {
+            entry.name = display_name(&new_root);
+        }
+        entry.id = new_id.clone();
+        entry.path = new_path.clone();
+        Ok(entry.clone())
+    });
+    let Some((old_id, old_root)) = previous else {
+        return result;
+    };
+    match result {
+        Ok(entry) => {
+            unregister_root(&old_root);
+            log::info!(
+                "relocated library {} → {}",
+                old_root.display(),
+                new_root.display()
+            );
+            Ok(entry)
+        }
+        Err(e) => {
+            if let Err(undo) = move_root_cache(&new_id, &old_id) {
+                log::warn!(
+                    "failed to restore artifact cache for {}: {}",
+                    old_root.display(),
+                    undo
+                );
+            }
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn set_library_overrides(
+    state: tauri::State<LibraryState>,
+    id: String,
+    duplicates: Option<DuplicatesSettings>,
+    thumbnails: Option<ThumbnailSettings>,
+    read_only: Option<bool>,
+) -> Result<LibraryEntry, String> {
+    state.modify(|registry| {
+        let entry = registry.entry_mut(&id)?;
+        entry.duplicates = duplicates;
+        entry.thumbnails = thumbnails;
+        entry.read_only = read_only.unwrap_or(false);
+        *entry = entry.clone().clamp();
+        Ok(entry.clone())
+    })
+}
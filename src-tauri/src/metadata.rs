@@ -1,25 +1,34 @@
 #[cfg(target_os = "windows")]
 use std::ffi::OsString;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{self, File},
     io::BufReader,
     path::{Path, PathBuf},
     process::Command,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
+    album::walk_album_paths,
+    artifacts::{album_meta_dir, album_thumb_dir, ensure_writable, in_tree_meta_dir, is_read_only},
+    duplicates::note_ignore_groups,
+    preload::{enqueue_hash_retries, enqueue_meta_task, enqueue_thumb_task, TaskPriority},
     settings::read_settings,
-    util::{metadata_probe_timeout, run_command_with_timeout, STORE_WRITE_LOCK},
+    thumb::{media_preview_path, media_thumb_path, media_view_path},
+    util::{ffmpeg_version, metadata_probe_timeout, run_command_with_timeout, STORE_WRITE_LOCK},
 };
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use exif::{Field, In, Reader, Tag, Value};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::async_runtime;
 
-const META_DIR: &str = ".room237-metadata";
 const META_FILE_EXT: &str = ".meta";
 const ALBUM_META_FILE: &str = "album.json";
+const FAILURE_BACKOFF_BASE_SECS: u64 = 60;
+const FAILURE_BACKOFF_MAX_SECS: u64 = 24 * 60 * 60;
 
 pub struct DetachedFileMeta {
     pub a: Option<u64>,
@@ -95,6 +104,12 @@ pub struct DetachedMediaEntry {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub favorite: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -106,6 +121,7 @@ pub struct DetachedAlbum {
     pub relative_path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
+    pub root_id: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -125,6 +141,16 @@ pub struct CachedHash {
     pub bits: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FileDigest {
+    pub size: u64,
+    pub mtime: u64,
+    #[serde(default)]
+    pub partial: Option<String>,
+    #[serde(default)]
+    pub full: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct FileMetaEntry {
     #[serde(default)]
@@ -138,21 +164,207 @@ pub struct FileMetaEntry {
     #[serde(default)]
     pub hash_bits: Option<u32>,
     #[serde(default)]
+    pub wavelet_hash: Option<String>,
+    #[serde(default)]
+    pub color_hash: Option<String>,
+    #[serde(default)]
+    pub video_hashes: Vec<String>,
+    #[serde(default)]
+    pub video_hash_version: Option<String>,
+    #[serde(default)]
     pub thumb_version: Option<String>,
     #[serde(default)]
+    pub sized_thumb_versions: HashMap<u32, String>,
+    #[serde(default)]
+    pub preview_version: Option<String>,
+    #[serde(default)]
+    pub poster_seconds: Option<f32>,
+    #[serde(default)]
+    pub digest: Option<FileDigest>,
+    #[serde(default)]
     pub hash_failed: bool,
     #[serde(default)]
     pub thumb_failed: bool,
     #[serde(default)]
     pub meta_failed: bool,
+    #[serde(default)]
+    pub hash_failure: Option<FailureInfo>,
+    #[serde(default)]
+    pub thumb_failure: Option<FailureInfo>,
+    #[serde(default)]
+    pub meta_failure: Option<FailureInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FailureInfo {
+    pub reason: String,
+    pub at: u64,
+    pub attempts: u32,
+    #[serde(default)]
+    pub mtime: Option<u64>,
+    #[serde(default)]
+    pub ffmpeg: Option<String>,
+}
+
+impl FailureInfo {
+    fn next(previous: Option<&FailureInfo>, path: &Path, reason: &str) -> Self {
+        Self {
+            reason: reason.to_string(),
+            at: now_ms(),
+            attempts: previous.map(|f| f.attempts).unwrap_or(0).saturating_add(1),
+            mtime: file_mtime_ms(path),
+            ffmpeg: ffmpeg_version(),
+        }
+    }
+
+    fn backoff_ms(&self) -> u64 {
+        let shift = self.attempts.saturating_sub(1).min(16);
+        (FAILURE_BACKOFF_BASE_SECS << shift).min(FAILURE_BACKOFF_MAX_SECS) * 1000
+    }
+
+    pub(crate) fn retry_due(&self, path: &Path) -> bool {
+        let changed = file_mtime_ms(path) != self.mtime || ffmpeg_version() != self.ffmpeg;
+        changed && now_ms().saturating_sub(self.at) >= self.backoff_ms()
+    }
+}
+
+fn failure_active(failed: bool, failure: Option<&FailureInfo>, path: &Path) -> bool {
+    failed && !failure.map(|f| f.retry_due(path)).unwrap_or(true)
+}
+
+impl FileMetaEntry {
+    pub(crate) fn thumb_blocked(&self, path: &Path) -> bool {
+        failure_active(self.thumb_failed, self.thumb_failure.as_ref(), path)
+    }
+
+    pub(crate) fn meta_blocked(&self, path: &Path) -> bool {
+        failure_active(self.meta_failed, self.meta_failure.as_ref(), path)
+    }
+
+    pub(crate) fn hash_blocked(&self, path: &Path) -> bool {
+        failure_active(self.hash_failed, self.hash_failure.as_ref(), path)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct IgnoreGroup {
+    pub id: String,
+    pub files: Vec<String>,
+}
+
+impl IgnoreGroup {
+    pub(crate) fn new(mut files: Vec<String>) -> Self {
+        files.sort();
+        files.dedup();
+        let mut hasher = Sha256::new();
+        for file in &files {
+            hasher.update(file.as_bytes());
+            hasher.update([0]);
+        }
+        let id = hex::encode(&hasher.finalize()[..8]);
+        Self { id, files }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct AlbumMeta {
     #[serde(default)]
     pub files: HashMap<String, FileMetaEntry>,
+    #[serde(default, skip_serializing)]
+    duplicates_ignore: Vec<[String; 2]>,
     #[serde(default)]
-    pub duplicates_ignore: Vec<[String; 2]>,
+    pub ignore_groups: Vec<IgnoreGroup>,
+}
+
+impl AlbumMeta {
+    fn migrate_legacy_ignores(&mut self) {
+        for [a, b] in std::mem::take(&mut self.duplicates_ignore) {
+            self.add_ignore_group(vec![a, b]);
+        }
+    }
+
+    pub(crate) fn add_ignore_group(&mut self, files: Vec<String>) -> Option<String> {
+        let group = IgnoreGroup::new(files);
+        if group.files.len() < 2 {
+            return None;
+        }
+        let id = group.id.clone();
+        if !self.ignore_groups.iter().any(|g| g.id == id) {
+            self.ignore_groups.push(group);
+        }
+        Some(id)
+    }
+
+    pub(crate) fn rename_ignored<F>(&mut self, rename: F) -> bool
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut changed = false;
+        for group in std::mem::take(&mut self.ignore_groups) {
+            let mut files = Vec::with_capacity(group.files.len());
+            for file in group.files {
+                match rename(&file) {
+                    Some(next) => {
+                        files.push(next);
+                        changed = true;
+                    }
+                    None => files.push(file),
+                }
+            }
+            self.add_ignore_group(files);
+        }
+        changed
+    }
+
+    pub(crate) fn take_ignored(
+        &mut self,
+        names: &HashSet<&str>,
+    ) -> Vec<(Vec<String>, Vec<String>)> {
+        let mut taken = Vec::new();
+        for group in std::mem::take(&mut self.ignore_groups) {
+            let (moved, kept): (Vec<String>, Vec<String>) = group
+                .files
+                .iter()
+                .cloned()
+                .partition(|f| names.contains(f.as_str()));
+            if moved.is_empty() {
+                self.ignore_groups.push(group);
+                continue;
+            }
+            self.add_ignore_group(kept.clone());
+            taken.push((moved, kept));
+        }
+        taken
+    }
+}
+
+pub(crate) struct SplitIgnore {
+    pub(crate) kept: Vec<String>,
+    pub(crate) moved: Vec<String>,
+}
+
+fn transfer_ignore_groups(
+    source: &mut AlbumMeta,
+    target: &mut AlbumMeta,
+    moves: &[(String, String)],
+) -> Vec<SplitIgnore> {
+    let names: HashMap<&str, &str> = moves
+        .iter()
+        .map(|(s, t)| (s.as_str(), t.as_str()))
+        .collect();
+    let keys: HashSet<&str> = names.keys().copied().collect();
+    let mut split = Vec::new();
+    for (moved, kept) in source.take_ignored(&keys) {
+        let moved: Vec<String> = moved
+            .iter()
+            .filter_map(|f| names.get(f.as_str()).map(|t| t.to_string()))
+            .collect();
+        target.add_ignore_group(moved.clone());
+        if !kept.is_empty() && !moved.is_empty() {
+            split.push(SplitIgnore { kept, moved });
+        }
+    }
+    split
 }
 
 #[cfg(target_family = "unix")]
@@ -206,16 +418,12 @@ fn write_embedded_timestamp(_path: &Path, _timestamp: u64) -> Result<(), String>
     Err("Embedded timestamp storage unsupported on this platform".to_string())
 }
 
-fn meta_dir(dir: &Path) -> PathBuf {
-    dir.join(META_DIR)
-}
-
 fn album_meta_path(dir: &Path) -> PathBuf {
-    meta_dir(dir).join(ALBUM_META_FILE)
+    album_meta_dir(dir).join(ALBUM_META_FILE)
 }
 
 fn ensure_meta_dir(dir: &Path) -> std::io::Result<()> {
-    fs::create_dir_all(meta_dir(dir))
+    fs::create_dir_all(album_meta_dir(dir))
 }
 
 pub(crate) fn write_file_meta(dir: &Path, name: &str, entry: &FileMetaEntry) -> Result<(), String> {
@@ -225,7 +433,23 @@ pub(crate) fn write_file_meta(dir: &Path, name: &str, entry: &FileMetaEntry) ->
     write_album_meta_without_lock(dir, &album)
 }
 
-pub(crate) fn mark_thumb_failed(path: &Path) -> Result<(), String> {
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn file_mtime_ms(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+pub(crate) fn mark_thumb_failed(path: &Path, reason: &str) -> Result<(), String> {
     let dir = path.parent().ok_or("Invalid path")?;
     let name = path
         .file_name()
@@ -235,6 +459,11 @@ pub(crate) fn mark_thumb_failed(path: &Path) -> Result<(), String> {
     let mut album = read_album_meta(dir);
     let entry = album.files.entry(name.clone()).or_default();
     entry.thumb_failed = true;
+    entry.thumb_failure = Some(FailureInfo::next(
+        entry.thumb_failure.as_ref(),
+        path,
+        reason,
+    ));
     write_file_meta(dir, &name, entry)
 }
 
@@ -248,10 +477,11 @@ pub(crate) fn clear_thumb_failed(path: &Path) -> Result<(), String> {
     let mut album = read_album_meta(dir);
     let entry = album.files.entry(name.clone()).or_default();
     entry.thumb_failed = false;
+    entry.thumb_failure = None;
     write_file_meta(dir, &name, entry)
 }
 
-pub(crate) fn mark_meta_failed(path: &Path) -> Result<(), String> {
+pub(crate) fn mark_meta_failed(path: &Path, reason: &str) -> Result<(), String> {
     let dir = path.parent().ok_or("Invalid path")?;
     let name = path
         .file_name()
@@ -261,6 +491,7 @@ pub(crate) fn mark_meta_failed(path: &Path) -> Result<(), String> {
     let mut album = read_album_meta(dir);
     let entry = album.files.entry(name.clone()).or_default();
     entry.meta_failed = true;
+    entry.meta_failure = Some(FailureInfo::next(entry.meta_failure.as_ref(), path, reason));
     write_file_meta(dir, &name, entry)
 }
 
@@ -277,7 +508,7 @@ pub(crate) fn is_thumb_failed(path: &Path) -> bool {
     album
         .files
         .get(name)
-        .map(|e| e.thumb_failed)
+        .map(|e| e.thumb_blocked(path))
         .unwrap_or(false)
 }
 
@@ -294,11 +525,11 @@ pub(crate) fn is_meta_failed(path: &Path) -> bool {
     album
         .files
         .get(name)
-        .map(|e| e.meta_failed)
+        .map(|e| e.meta_blocked(path))
         .unwrap_or(false)
 }
 
-pub(crate) fn mark_hash_failed(path: &Path) -> Result<(), String> {
+pub(crate) fn mark_hash_failed(path: &Path, reason: &str) -> Result<(), String> {
     let dir = path.parent().ok_or("Invalid path")?;
     let name = path
         .file_name()
@@ -311,36 +542,41 @@ pub(crate) fn mark_hash_failed(path: &Path) -> Result<(), String> {
     entry.hash_version = None;
     entry.hash_bits = None;
     entry.hash_failed = true;
+    entry.hash_failure = Some(FailureInfo::next(entry.hash_failure.as_ref(), path, reason));
     write_file_meta(dir, &name, entry)
 }
 
 fn read_album_meta_without_lock(dir: &Path) -> AlbumMeta {
-    let _ = ensure_meta_dir(dir);
-    let album_path = album_meta_path(dir);
+    let read_only = is_read_only(dir);
+    let mut meta_dirs = vec![album_meta_dir(dir)];
+    if read_only {
+        meta_dirs.push(in_tree_meta_dir(dir));
+    }
 
-    if let Ok(txt) = fs::read_to_string(&album_path) {
-        if let Ok(parsed) = serde_json::from_str::<AlbumMeta>(&txt) {
-            return parsed;
+    for meta_dir in &meta_dirs {
+        if let Ok(txt) = fs::read_to_string(meta_dir.join(ALBUM_META_FILE)) {
+            if let Ok(mut parsed) = serde_json::from_str::<AlbumMeta>(&txt) {
+                parsed.migrate_legacy_ignores();
+                return parsed;
+            }
         }
     }
 
     let mut album = AlbumMeta::default();
-    let meta_paths: Vec<(String, PathBuf)> = if let Ok(entries) = fs::read_dir(meta_dir(dir)) {
-        entries
-            .flatten()
-            .filter_map(|entry| {
-                let path = entry.path();
-                if !path.is_file() {
-                    return None;
-                }
-                let name = path.file_name()?.to_str()?;
-                let stem = name.strip_suffix(META_FILE_EXT)?;
-                Some((stem.to_string(), path))
-            })
-            .collect()
-    } else {
-        Vec::new()
-    };
+    let meta_paths: Vec<(String, PathBuf)> = meta_dirs
+        .iter()
+        .filter_map(|meta_dir| fs::read_dir(meta_dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if !path.is_file() {
+                return None;
+            }
+            let name = path.file_name()?.to_str()?;
+            let stem = name.strip_suffix(META_FILE_EXT)?;
+            Some((stem.to_string(), path))
+        })
+        .collect();
 
     let file_entries: Vec<(String, FileMetaEntry)> = meta_paths
         .par_iter()
@@ -355,6 +591,10 @@ fn read_album_meta_without_lock(dir: &Path) -> AlbumMeta {
         album.files.insert(stem, entry);
     }
 
+    if read_only {
+        return album;
+    }
+
     if !album.files.is_empty() || !meta_paths.is_empty() {
         log::info!(
             "Migrating album metadata to unified format: {}",
@@ -370,6 +610,48 @@ fn read_album_meta_without_lock(dir: &Path) -> AlbumMeta {
     album
 }
 
+pub(crate) fn stale_legacy_meta_files(dir: &Path) -> Vec<PathBuf> {
+    let read_only = is_read_only(dir);
+    let meta_dir = album_meta_dir(dir);
+    let mut meta_dirs = vec![meta_dir.clone()];
+    if read_only {
+        meta_dirs.push(in_tree_meta_dir(dir));
+    }
+    let unified = meta_dirs.iter().any(|meta_dir| {
+        fs::read_to_string(meta_dir.join(ALBUM_META_FILE))
+            .ok()
+            .and_then(|txt| serde_json::from_str::<AlbumMeta>(&txt).ok())
+            .is_some()
+    });
+
+    let mut stale: Vec<PathBuf> = Vec::new();
+    if unified {
+        stale.extend(
+            fs::read_dir(&meta_dir)
+                .into_iter()
+                .flat_map(|entries| entries.flatten())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .map(|n| n.ends_with(META_FILE_EXT))
+                            .unwrap_or(false)
+                }),
+        );
+    }
+    if read_only {
+        stale.retain(|path| !path.starts_with(dir));
+        return stale;
+    }
+    let legacy_meta_file = dir.join(".room237-meta.json");
+    if legacy_meta_file.is_file() {
+        stale.push(legacy_meta_file);
+    }
+    stale
+}
+
 pub(crate) fn read_album_meta(dir: &Path) -> AlbumMeta {
     read_album_meta_without_lock(dir)
 }
@@ -379,6 +661,7 @@ fn write_album_meta_without_lock(dir: &Path, data: &AlbumMeta) -> Result<(), Str
 
     let album_json = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
     fs::write(album_meta_path(dir), album_json).map_err(|e| e.to_string())?;
+    note_ignore_groups(dir, &data.ignore_groups);
 
     Ok(())
 }
@@ -393,25 +676,21 @@ pub(crate) fn transfer_media_metadata_entry_caller_holds_lock(
     source_name: &str,
     target_dir: &Path,
     target_name: &str,
-) -> Result<(), String> {
-    let mut source_album = read_album_meta_without_lock(source_dir);
-    let entry = source_album.files.remove(source_name).unwrap_or_default();
-    write_album_meta_without_lock(source_dir, &source_album)?;
-
-    let mut target_album = read_album_meta_without_lock(target_dir);
-    target_album.files.insert(target_name.to_string(), entry);
-    write_album_meta_without_lock(target_dir, &target_album)?;
-
-    Ok(())
+) -> Result<Vec<SplitIgnore>, String> {
+    transfer_media_metadata_batch_caller_holds_lock(
+        source_dir,
+        target_dir,
+        &[(source_name.to_string(), target_name.to_string())],
+    )
 }
 
 pub(crate) fn transfer_media_metadata_batch_caller_holds_lock(
     source_dir: &Path,
     target_dir: &Path,
     moves: &[(String, String)],
-) -> Result<(), String> {
+) -> Result<Vec<SplitIgnore>, String> {
     if moves.is_empty() {
-        return Ok(());
+        return Ok(Vec::new());
     }
     let mut source_album = read_album_meta_without_lock(source_dir);
     let mut target_album = read_album_meta_without_lock(target_dir);
@@ -419,9 +698,19 @@ pub(crate) fn transfer_media_metadata_batch_caller_holds_lock(
         let entry = source_album.files.remove(source_name).unwrap_or_default();
         target_album.files.insert(target_name.clone(), entry);
     }
+    let split = transfer_ignore_groups(&mut source_album, &mut target_album, moves);
     write_album_meta_without_lock(source_dir, &source_album)?;
     write_album_meta_without_lock(target_dir, &target_album)?;
-    Ok(())
+    Ok(split)
+}
+
+pub(crate) fn remove_file_meta_entries(dir: &Path, names: &[String]) -> Result<(), String> {
+    let _guard = STORE_WRITE_LOCK.lock().unwrap();
+    let mut album = read_album_meta_without_lock(dir);
+    for name in names {
+        album.files.remove(name);
+    }
+    write_album_meta_without_lock(dir, &album)
 }
 
 pub(crate) fn load_album_file_hash(path: &Path) -> Option<CachedHash> {
@@ -442,6 +731,8 @@ pub(crate) fn write_album_file_hash(
     hash_b64: String,
     hash_version: String,
     hash_bits: u32,
+    wavelet_hash: Option<String>,
+    color_hash: Option<String>,
 ) -> Result<(), String> {
     let dir = path.parent().ok_or("Invalid path")?;
     let name = path
@@ -454,11 +745,50 @@ pub(crate) fn write_album_file_hash(
     entry.hash = Some(hash_b64);
     entry.hash_version = Some(hash_version);
     entry.hash_bits = Some(hash_bits);
+    entry.wavelet_hash = wavelet_hash;
+    entry.color_hash = color_hash;
     entry.hash_failed = false;
+    entry.hash_failure = None;
     entry.meta_failed = false;
+    entry.meta_failure = None;
     write_file_meta(dir, &name, entry)
 }
 
+pub(crate) fn write_album_video_hash(
+    path: &Path,
+    hashes: Vec<String>,
+    hash_version: String,
+) -> Result<(), String> {
+    let dir = path.parent().ok_or("Invalid path")?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid filename")?
+        .to_string();
+    let mut album = read_album_meta(dir);
+    let entry = album.files.entry(name.clone()).or_default();
+    entry.video_hashes = hashes;
+    entry.video_hash_version = Some(hash_version);
+    entry.hash_failed = false;
+    entry.hash_failure = None;
+    write_file_meta(dir, &name, entry)
+}
+
+pub(crate) fn write_file_digests(
+    dir: &Path,
+    digests: &[(String, FileDigest)],
+) -> Result<(), String> {
+    if digests.is_empty() {
+        return Ok(());
+    }
+    let _guard = STORE_WRITE_LOCK.lock().unwrap();
+    let mut album = read_album_meta_without_lock(dir);
+    for (name, digest) in digests {
+        album.files.entry(name.clone()).or_default().digest = Some(digest.clone());
+    }
+    write_album_meta_without_lock(dir, &album)
+}
+
 pub(crate) fn load_thumb_version(path: &Path) -> Option<String> {
     let dir = path.parent()?;
     let name = path.file_name()?.to_str()?;
@@ -480,6 +810,79 @@ pub(crate) fn write_thumb_version(path: &Path, thumb_version: &str) -> Result<()
     let entry = album.files.entry(name.clone()).or_default();
     entry.thumb_version = Some(thumb_version.to_string());
     entry.thumb_failed = false;
+    entry.thumb_failure = None;
+    write_file_meta(dir, &name, entry)
+}
+
+pub(crate) fn load_sized_thumb_version(path: &Path, dim: u32) -> Option<String> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_str()?;
+    let album = read_album_meta(dir);
+    album
+        .files
+        .get(name)
+        .and_then(|entry| entry.sized_thumb_versions.get(&dim).cloned())
+}
+
+pub(crate) fn write_sized_thumb_version(
+    path: &Path,
+    dim: u32,
+    thumb_version: &str,
+) -> Result<(), String> {
+    let dir = path.parent().ok_or("Invalid path")?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid filename")?
+        .to_string();
+    let mut album = read_album_meta(dir);
+    let entry = album.files.entry(name.clone()).or_default();
+    entry
+        .sized_thumb_versions
+        .insert(dim, thumb_version.to_string());
+    write_file_meta(dir, &name, entry)
+}
+
+pub(crate) fn load_preview_version(path: &Path) -> Option<String> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_str()?;
+    let album = read_album_meta(dir);
+    album
+        .files
+        .get(name)
+        .and_then(|entry| entry.preview_version.clone())
+}
+
+pub(crate) fn write_preview_version(path: &Path, preview_version: &str) -> Result<(), String> {
+    let dir = path.parent().ok_or("Invalid path")?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid filename")?
+        .to_string();
+    let mut album = read_album_meta(dir);
+    let entry = album.files.entry(name.clone()).or_default();
+    entry.preview_version = Some(preview_version.to_string());
+    write_file_meta(dir, &name, entry)
+}
+
+pub(crate) fn load_poster_frame(path: &Path) -> Option<f32> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_str()?;
+    let album = read_album_meta(dir);
+    album.files.get(name).and_then(|entry| entry.poster_seconds)
+}
+
+pub(crate) fn write_poster_frame(path: &Path, seconds: Option<f32>) -> Result<(), String> {
+    let dir = path.parent().ok_or("Invalid path")?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid filename")?
+        .to_string();
+    let mut album = read_album_meta(dir);
+    let entry = album.files.entry(name.clone()).or_default();
+    entry.poster_seconds = seconds;
     write_file_meta(dir, &name, entry)
 }
 
@@ -507,6 +910,7 @@ pub fn get_metadata_with_favorite(path: &Path) -> Result<StoredMetadata, String>
     let mut updated = album.files.get(&name).cloned().unwrap_or_default();
     updated.meta = Some(meta.clone());
     updated.meta_failed = false;
+    updated.meta_failure = None;
     let _ = write_file_meta(dir, &name, &updated);
 
     Ok(StoredMetadata { meta, favorite })
@@ -576,6 +980,28 @@ pub fn probe(
     Ok((shoot, width, height))
 }
 
+pub fn probe_duration(path: &Path) -> Option<f64> {
+    let mut cmd = Command::new(ffmpeg_sidecar::paths::ffmpeg_path());
+    cmd.arg("-hide_banner")
+        .arg("-i")
+        .arg(path)
+        .args(["-t", "0", "-f", "null", "-"]);
+    let output = run_command_with_timeout(cmd, metadata_probe_timeout(), true).ok()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr
+        .lines()
+        .find(|l| l.trim_start().starts_with("Duration:"))?;
+    let value = line.trim_start()["Duration:".len()..]
+        .split(',')
+        .next()?
+        .trim();
+    let mut secs = 0.0;
+    for part in value.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(secs)
+}
+
 #[tauri::command]
 pub fn get_file_metadata(path: &str) -> Result<String, String> {
     let p = Path::new(path);
@@ -648,6 +1074,12 @@ pub fn get_file_metadata_cached(path: &Path) -> Result<String, String> {
     get_metadata_with_favorite(path).map(|m| m.meta)
 }
 
+pub(crate) fn file_meta(path: &Path) -> Option<DetachedFileMeta> {
+    get_file_metadata_cached(path)
+        .ok()
+        .and_then(|m| unpack_file_meta(&m).ok())
+}
+
 #[tauri::command]
 pub fn set_media_timestamp(
     album_path: String,
@@ -661,6 +1093,7 @@ pub fn set_media_timestamp(
     if names.is_empty() {
         return Ok(Vec::new());
     }
+    ensure_writable(&dir)?;
 
     let mut album_meta = read_album_meta(&dir);
     let mut updated = Vec::new();
@@ -689,12 +1122,16 @@ pub fn set_media_timestamp(
         let entry = album_meta.files.entry(name.clone()).or_default();
         entry.meta = Some(packed.clone());
         entry.meta_failed = false;
+        entry.meta_failure = None;
         write_file_meta(&dir, &name, entry)?;
 
         updated.push(DetachedMediaEntry {
             meta: packed,
             name: name.clone(),
             favorite: if entry.favorite { Some(true) } else { None },
+            thumb: media_thumb_path(&file_path),
+            view: media_view_path(&file_path),
+            preview: media_preview_path(&file_path),
         });
     }
 
@@ -708,11 +1145,12 @@ pub fn set_media_favorite(path: String, favorite: bool) -> Result<DetachedMediaE
         return Err(format!("{} does not exist", path));
     }
 
-    let meta = get_file_metadata(&path)?;
     let dir = p
         .parent()
         .ok_or("Invalid path")
         .map_err(|e| e.to_string())?;
+    ensure_writable(dir)?;
+    let meta = get_file_metadata(&path)?;
     let mut album = read_album_meta(dir);
     let name = p
         .file_name()
@@ -731,5 +1169,206 @@ pub fn set_media_favorite(path: String, favorite: bool) -> Result<DetachedMediaE
         meta,
         name,
         favorite: if favorite { Some(true) } else { None },
+        thumb: media_thumb_path(p),
+        view: media_view_path(p),
+        preview: media_preview_path(p),
     })
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Thumbnail,
+    Metadata,
+    Hash,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureEntry {
+    pub path: String,
+    pub album: String,
+    pub name: String,
+    pub kind: FailureKind,
+    pub reason: Option<String>,
+    pub at: Option<u64>,
+    pub attempts: u32,
+    pub retry_due: bool,
+}
+
+fn entry_failures(entry: &FileMetaEntry) -> [(FailureKind, bool, Option<&FailureInfo>); 3] {
+    [
+        (
+            FailureKind::Thumbnail,
+            entry.thumb_failed,
+            entry.thumb_failure.as_ref(),
+        ),
+        (
+            FailureKind::Metadata,
+            entry.meta_failed,
+            entry.meta_failure.as_ref(),
+        ),
+        (
+            FailureKind::Hash,
+            entry.hash_failed,
+            entry.hash_failure.as_ref(),
+        ),
+    ]
+}
+
+fn collect_failures(root: &Path) -> Result<Vec<FailureEntry>, String> {
+    let mut failures = Vec::new();
+    for album in walk_album_paths(root)? {
+        let meta = read_album_meta(&album);
+        for (name, entry) in &meta.files {
+            let path = album.join(name);
+            for (kind, failed, info) in entry_failures(entry) {
+                if !failed {
+                    continue;
+                }
+                failures.push(FailureEntry {
+                    path: path.to_string_lossy().to_string(),
+                    album: album.to_string_lossy().to_string(),
+                    name: name.clone(),
+                    kind,
+                    reason: info.map(|f| f.reason.clone()),
+                    at: info.map(|f| f.at),
+                    attempts: info.map(|f| f.attempts).unwrap_or(1),
+                    retry_due: info.map(|f| f.retry_due(&path)).unwrap_or(true),
+                });
+            }
+        }
+    }
+    failures.sort_by(|a, b| b.at.cmp(&a.at).then_with(|| a.path.cmp(&b.path)));
+    Ok(failures)
+}
+
+#[tauri::command]
+pub async fn list_failures(root_dir: String) -> Result<Vec<FailureEntry>, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    async_runtime::spawn_blocking(move || collect_failures(&root))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn clear_failures(
+    album: &Path,
+    names: &[String],
+    kinds: &[FailureKind],
+) -> Result<Vec<(PathBuf, FailureKind)>, String> {
+    let _guard = STORE_WRITE_LOCK.lock().unwrap();
+    let mut meta = read_album_meta_without_lock(album);
+    let mut cleared = Vec::new();
+    for name in names {
+        let Some(entry) = meta.files.get_mut(name) else {
+            continue;
+        };
+        let path = album.join(name);
+        if kinds.contains(&FailureKind::Thumbnail) && entry.thumb_failed {
+            entry.thumb_failed = false;
+            entry.thumb_failure = None;
+            cleared.push((path.clone(), FailureKind::Thumbnail));
+        }
+        if kinds.contains(&FailureKind::Metadata) && entry.meta_failed {
+            entry.meta_failed = false;
+            entry.meta_failure = None;
+            cleared.push((path.clone(), FailureKind::Metadata));
+        }
+        if kinds.contains(&FailureKind::Hash) && entry.hash_failed {
+            entry.hash_failed = false;
+            entry.hash_failure = None;
+            cleared.push((path, FailureKind::Hash));
+        }
+    }
+    if !cleared.is_empty() {
+        write_album_meta_without_lock(album, &meta)?;
+    }
+    Ok(cleared)
+}
+
+fn retry_failures_blocking(
+    root: &Path,
+    paths: Option<Vec<String>>,
+    kinds: Option<Vec<FailureKind>>,
+) -> Result<usize, String> {
+    let kinds = kinds.unwrap_or_else(|| {
+        vec![
+            FailureKind::Thumbnail,
+            FailureKind::Metadata,
+            FailureKind::Hash,
+        ]
+    });
+    let mut targets: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    match paths {
+        Some(paths) => {
+            for path in paths.iter().map(PathBuf::from) {
+                if !path.starts_with(root) {
+                    continue;
+                }
+                let (Some(dir), Some(name)) =
+                    (path.parent(), path.file_name().and_then(|n| n.to_str()))
+                else {
+                    continue;
+                };
+                targets
+                    .entry(dir.to_path_buf())
+                    .or_default()
+                    .push(name.to_string());
+            }
+        }
+        None => {
+            for failure in collect_failures(root)? {
+                targets
+                    .entry(PathBuf::from(failure.album))
+                    .or_default()
+                    .push(failure.name);
+            }
+        }
+    }
+
+    let mut retried = 0;
+    let mut hashes = Vec::new();
+    for (album, mut names) in targets {
+        names.sort();
+        names.dedup();
+        for (path, kind) in clear_failures(&album, &names, &kinds)? {
+            retried += 1;
+            if !path.is_file() {
+                continue;
+            }
+            match kind {
+                FailureKind::Thumbnail => {
+                    enqueue_thumb_task(path, album_thumb_dir(&album), false, TaskPriority::High);
+                }
+                FailureKind::Metadata => {
+                    enqueue_meta_task(path, false, TaskPriority::High);
+                }
+                FailureKind::Hash => hashes.push(path),
+            }
+        }
+    }
+    enqueue_hash_retries(&hashes);
+
+    log::info!("retrying {} failed tasks under {}", retried, root.display());
+    Ok(retried)
+}
+
+#[tauri::command]
+pub async fn retry_failures(
+    root_dir: String,
+    paths: Option<Vec<String>>,
+    kinds: Option<Vec<FailureKind>>,
+) -> Result<usize, String> {
+    let root = PathBuf::from(&root_dir);
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    async_runtime::spawn_blocking(move || retry_failures_blocking(&root, paths, kinds))
+        .await
+        .map_err(|e| e.to_string())?
+}
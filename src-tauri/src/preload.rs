@@ -7,35 +7,46 @@ use std::{
         Arc, Condvar, Mutex,
     },
     thread,
-    time::Duration,
+    time::{Duration, UNIX_EPOCH},
 };
 
-use once_cell::sync::Lazy;
-use serde::Serialize;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tauri::{AppHandle, Emitter, Wry};
+use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager, Wry};
 
 use crate::{
     album::walk_album_paths,
-    constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS},
-    duplicates::compute_hash_for_path,
+    artifacts::{album_thumb_dir, artifacts_missing, root_id},
+    constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS, VIDEO_HASH_POINTS},
+    duplicates::{compute_hash_signals_for_path, compute_video_hash_for_path, note_album_hash},
+    jobs::{
+        job_state, service_error, update_service, wait_while_paused, JobKind, JobState, HASHES_JOB,
+        METADATA_JOB, PRELOAD_JOB, THUMBNAILS_JOB,
+    },
+    library::settings_for,
     metadata::{
         clear_thumb_failed, get_file_metadata_cached, is_meta_failed, is_thumb_failed,
         load_album_file_hash, mark_hash_failed, mark_meta_failed, mark_thumb_failed,
         read_album_meta, AlbumMeta, FileMetaEntry,
     },
-    settings::{read_settings, HashConfig},
-    thumb::{ensure_thumb_with_settings, thumb_path},
-    util::{has_extension, heic_to_jpeg, newer_than, set_low_priority_current_thread},
+    scheduler,
+    settings::{read_settings, AdvancedSettings, HashConfig},
+    thumb::{
+        ensure_animated_preview, ensure_thumb_with_settings, ensure_view_transcode,
+        needs_view_transcode, preview_path, supports_animated_preview, thumb_path, view_path,
+    },
+    util::{has_extension, newer_than, set_low_priority_current_thread},
 };
 
 #[allow(dead_code)]
 const PRELOAD_SCREEN_THRESHOLD: usize = 100;
+const QUEUE_STATE_FILE: &str = "preload-queue.json";
+const QUEUE_PERSIST_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
 enum PreloadStage {
-    Conversion,
     Thumbnails,
     Metadata,
     Idle,
@@ -44,18 +55,16 @@ enum PreloadStage {
 impl PreloadStage {
     fn as_u8(self) -> u8 {
         match self {
-            PreloadStage::Conversion => 0,
-            PreloadStage::Thumbnails => 1,
-            PreloadStage::Metadata => 2,
-            PreloadStage::Idle => 3,
+            PreloadStage::Thumbnails => 0,
+            PreloadStage::Metadata => 1,
+            PreloadStage::Idle => 2,
         }
     }
 
     fn from_u8(value: u8) -> Self {
         match value {
-            0 => PreloadStage::Conversion,
-            1 => PreloadStage::Thumbnails,
-            2 => PreloadStage::Metadata,
+            0 => PreloadStage::Thumbnails,
+            1 => PreloadStage::Metadata,
             _ => PreloadStage::Idle,
         }
     }
@@ -67,18 +76,99 @@ struct StageProgress {
     total: usize,
 }
 
+#[derive(Default)]
+pub(crate) struct PreloadQueues {
+    queues: HashMap<PathBuf, VecDeque<PathBuf>>,
+    order: VecDeque<PathBuf>,
+}
+
+impl PreloadQueues {
+    fn push(&mut self, root: &Path, dir: &Path) -> bool {
+        let queue = self.queues.entry(root.to_path_buf()).or_default();
+        if queue.iter().any(|d| d == dir) {
+            return false;
+        }
+        queue.push_back(dir.to_path_buf());
+        if !self.order.iter().any(|r| r == root) {
+            self.order.push_back(root.to_path_buf());
+        }
+        true
+    }
+
+    fn pop_next(&mut self) -> Option<PathBuf> {
+        while let Some(root) = self.order.pop_front() {
+            let Some(queue) = self.queues.get_mut(&root) else {
+                continue;
+            };
+            let Some(dir) = queue.pop_front() else {
+                self.queues.remove(&root);
+                continue;
+            };
+            if queue.is_empty() {
+                self.queues.remove(&root);
+            } else {
+                self.order.push_back(root);
+            }
+            return Some(dir);
+        }
+        None
+    }
+
+    pub(crate) fn remove(&mut self, dir: &Path) {
+        for queue in self.queues.values_mut() {
+            queue.retain(|d| d != dir);
+        }
+    }
+
+    fn retain_outside(&mut self, prefix: &Path) {
+        for queue in self.queues.values_mut() {
+            queue.retain(|d| !d.starts_with(prefix));
+        }
+        self.queues.retain(|_, queue| !queue.is_empty());
+        let queues = &self.queues;
+        self.order.retain(|root| queues.contains_key(root));
+    }
+
+    fn pending_for(&self, root: &Path) -> Vec<PathBuf> {
+        self.queues
+            .get(root)
+            .map(|q| q.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn len_for(&self, root: &Path) -> usize {
+        self.queues.get(root).map(|q| q.len()).unwrap_or(0)
+    }
+
+    fn len(&self) -> usize {
+        self.queues.values().map(|q| q.len()).sum()
+    }
+
+    fn clear(&mut self) {
+        self.queues.clear();
+        self.order.clear();
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct RootCounters {
+    thumbs: usize,
+    meta: usize,
+}
+
 pub(crate) static PRELOADED: Lazy<Mutex<HashSet<PathBuf>>> =
     Lazy::new(|| Mutex::new(HashSet::new()));
-pub(crate) static PRELOAD_QUEUE: Lazy<Mutex<VecDeque<PathBuf>>> =
-    Lazy::new(|| Mutex::new(VecDeque::new()));
+pub(crate) static PRELOAD_QUEUE: Lazy<Mutex<PreloadQueues>> =
+    Lazy::new(|| Mutex::new(PreloadQueues::default()));
 pub(crate) static PRELOADER_RUNNING: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
-pub(crate) static CURRENT_PRELOAD_CANCEL: Lazy<Mutex<Option<Arc<AtomicBool>>>> =
+pub(crate) static CURRENT_PRELOAD_CANCEL: Lazy<Mutex<Option<(PathBuf, Arc<AtomicBool>)>>> =
     Lazy::new(|| Mutex::new(None));
 static PRELOAD_DONE_THUMBS: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
 static PRELOAD_DONE_META: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
+static PRELOAD_DONE_ALBUMS: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
+static ROOT_DONE: Lazy<Mutex<HashMap<PathBuf, RootCounters>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 static PRELOAD_STAGE: Lazy<AtomicU8> = Lazy::new(|| AtomicU8::new(PreloadStage::Idle.as_u8()));
-static PRELOAD_CONVERSIONS_TOTAL: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
-static PRELOAD_CONVERSIONS_DONE: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
 
 static THUMB_HASH_ALBUMS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
 static THUMB_HASH_DONE_ONCE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
@@ -90,7 +180,12 @@ static ALLOW_OPEN: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
 static THUMB_WORKER_COUNTER: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
 static META_WORKER_COUNTER: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
 static HASH_WORKER_COUNTER: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
-static ACTIVE_ROOT: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+static ROOTS: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static QUEUE_STATE_PATH: OnceCell<PathBuf> = OnceCell::new();
+static PENDING_RESTORE: Lazy<Mutex<HashMap<PathBuf, PersistedRoot>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static QUEUE_PERSIST_LOCK: Mutex<()> = Mutex::new(());
+static QUEUE_STATE_DIRTY: AtomicBool = AtomicBool::new(false);
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) enum TaskPriority {
@@ -104,20 +199,153 @@ impl Default for TaskPriority {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum QueueTier {
+    Visible,
+    Album,
+    Background,
+}
+
+#[derive(Clone, Default)]
+struct ViewportFocus {
+    album: Option<PathBuf>,
+    visible: HashSet<PathBuf>,
+}
+
+impl ViewportFocus {
+    fn tier(&self, path: &Path) -> QueueTier {
+        if self.visible.contains(path) {
+            QueueTier::Visible
+        } else if self.album.is_some() && path.parent() == self.album.as_deref() {
+            QueueTier::Album
+        } else {
+            QueueTier::Background
+        }
+    }
+}
+
+static VIEWPORT_FOCUS: Lazy<Mutex<ViewportFocus>> =
+    Lazy::new(|| Mutex::new(ViewportFocus::default()));
+
+struct TierQueue<T> {
+    high: VecDeque<(PathBuf, T)>,
+    visible: VecDeque<(PathBuf, T)>,
+    album: VecDeque<(PathBuf, T)>,
+    background: VecDeque<(PathBuf, T)>,
+}
+
+impl<T> Default for TierQueue<T> {
+    fn default() -> Self {
+        Self {
+            high: VecDeque::new(),
+            visible: VecDeque::new(),
+            album: VecDeque::new(),
+            background: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> TierQueue<T> {
+    fn lanes(&self) -> [&VecDeque<(PathBuf, T)>; 4] {
+        [&self.high, &self.visible, &self.album, &self.background]
+    }
+
+    fn lanes_mut(&mut self) -> [&mut VecDeque<(PathBuf, T)>; 4] {
+        [
+            &mut self.high,
+            &mut self.visible,
+            &mut self.album,
+            &mut self.background,
+        ]
+    }
+
+    fn lane_mut(&mut self, tier: QueueTier) -> &mut VecDeque<(PathBuf, T)> {
+        match tier {
+            QueueTier::Visible => &mut self.visible,
+            QueueTier::Album => &mut self.album,
+            QueueTier::Background => &mut self.background,
+        }
+    }
+
+    fn push_high(&mut self, item: (PathBuf, T)) {
+        self.high.push_front(item);
+    }
+
+    fn push(&mut self, tier: QueueTier, item: (PathBuf, T)) {
+        self.lane_mut(tier).push_back(item);
+    }
+
+    fn pop_front(&mut self) -> Option<(PathBuf, T)> {
+        self.lanes_mut()
+            .into_iter()
+            .find_map(|lane| lane.pop_front())
+    }
+
+    fn take(&mut self, path: &Path) -> Option<(PathBuf, T)> {
+        self.lanes_mut().into_iter().find_map(|lane| {
+            let at = lane.iter().position(|(p, _)| p == path)?;
+            lane.remove(at)
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.lanes().iter().map(|lane| lane.len()).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lanes().iter().all(|lane| lane.is_empty())
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.lanes()
+            .into_iter()
+            .flat_map(|lane| lane.iter().map(|(p, _)| p))
+    }
+
+    fn retain_mut<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Path, &mut T) -> bool,
+    {
+        for lane in self.lanes_mut() {
+            lane.retain_mut(|(p, data)| keep(p, data));
+        }
+    }
+
+    fn clear(&mut self) {
+        for lane in self.lanes_mut() {
+            lane.clear();
+        }
+    }
+
+    fn rebucket(&mut self, focus: &ViewportFocus) {
+        let mut low = std::mem::take(&mut self.visible);
+        low.append(&mut self.album);
+        low.append(&mut self.background);
+        for (path, data) in low {
+            let tier = focus.tier(&path);
+            self.push(tier, (path, data));
+        }
+    }
+}
+
 struct WorkQueueState<T> {
-    queue: VecDeque<(PathBuf, T)>,
+    queue: TierQueue<T>,
     queued: HashSet<PathBuf>,
+    high: HashSet<PathBuf>,
     in_progress: HashSet<PathBuf>,
     started_workers: usize,
+    desired_workers: usize,
 }
 
 impl<T> Default for WorkQueueState<T> {
     fn default() -> Self {
         Self {
-            queue: VecDeque::new(),
+            queue: TierQueue::default(),
             queued: HashSet::new(),
+            high: HashSet::new(),
             in_progress: HashSet::new(),
             started_workers: 0,
+            desired_workers: 0,
         }
     }
 }
@@ -130,30 +358,93 @@ struct WorkQueue<T> {
 impl<T: Send + 'static> WorkQueue<T> {
     fn enqueue(&self, path: PathBuf, data: T, priority: TaskPriority) -> bool {
         let mut state = self.state.lock().unwrap();
-        if state.in_progress.contains(&path) || state.queued.contains(&path) {
+        if state.in_progress.contains(&path) {
+            return false;
+        }
+        if state.queued.contains(&path) {
+            if !matches!(priority, TaskPriority::High) || !state.high.insert(path.clone()) {
+                return false;
+            }
+            if let Some(item) = state.queue.take(&path) {
+                state.queue.push_high(item);
+            }
+            self.cv.notify_all();
             return false;
         }
         match priority {
-            TaskPriority::High => state.queue.push_front((path.clone(), data)),
-            TaskPriority::Low => state.queue.push_back((path.clone(), data)),
+            TaskPriority::High => {
+                state.high.insert(path.clone());
+                state.queue.push_high((path.clone(), data));
+            }
+            TaskPriority::Low => {
+                let tier = VIEWPORT_FOCUS.lock().unwrap().tier(&path);
+                state.queue.push(tier, (path.clone(), data));
+            }
         };
         state.queued.insert(path);
         self.cv.notify_all();
         true
     }
 
-    fn next_task_blocking(&self) -> (PathBuf, T) {
+    fn next_task_blocking(&self) -> Option<(PathBuf, T, TaskPriority)> {
         let mut guard = self.state.lock().unwrap();
         loop {
+            if guard.started_workers > guard.desired_workers {
+                guard.started_workers -= 1;
+                return None;
+            }
             if let Some((path, data)) = guard.queue.pop_front() {
                 guard.queued.remove(&path);
+                let priority = if guard.high.remove(&path) {
+                    TaskPriority::High
+                } else {
+                    TaskPriority::Low
+                };
                 guard.in_progress.insert(path.clone());
-                return (path, data);
+                return Some((path, data, priority));
             }
             guard = self.cv.wait(guard).unwrap();
         }
     }
 
+    fn resize(&self, desired: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.desired_workers = desired;
+        let spawn = desired.saturating_sub(state.started_workers);
+        state.started_workers += spawn;
+        self.cv.notify_all();
+        spawn
+    }
+
+    fn reprioritize(&self, focus: &ViewportFocus) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.queue.rebucket(focus);
+        state.queue.visible.len()
+    }
+
+    fn revalidate<F>(&self, mut keep: F) -> usize
+    where
+        F: FnMut(&Path, &mut T) -> bool,
+    {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain_mut(&mut keep);
+        let WorkQueueState {
+            queue,
+            queued,
+            high,
+            ..
+        } = &mut *state;
+        let remaining: HashSet<&PathBuf> = queue.paths().collect();
+        queued.retain(|p| remaining.contains(p));
+        high.retain(|p| queued.contains(p));
+        let removed = before - state.queue.len();
+        if removed > 0 {
+            self.cv.notify_all();
+        }
+        removed
+    }
+
     fn mark_done(&self, path: &Path) {
         let mut state = self.state.lock().unwrap();
         state.in_progress.remove(path);
@@ -175,11 +466,33 @@ impl<T: Send + 'static> WorkQueue<T> {
         state.queue.len() + state.in_progress.len()
     }
 
+    fn pending_under(&self, prefix: &Path) -> Vec<PathBuf> {
+        let state = self.state.lock().unwrap();
+        state
+            .in_progress
+            .iter()
+            .chain(state.queue.paths())
+            .filter(|p| p.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    fn outstanding_under(&self, prefix: &Path) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .queue
+            .paths()
+            .chain(state.in_progress.iter())
+            .filter(|p| p.starts_with(prefix))
+            .count()
+    }
+
     fn trim_prefix(&self, prefix: &Path) -> usize {
         let mut state = self.state.lock().unwrap();
         let before = state.queue.len();
-        state.queue.retain(|(p, _)| !p.starts_with(prefix));
+        state.queue.retain_mut(|p, _| !p.starts_with(prefix));
         state.queued.retain(|p| !p.starts_with(prefix));
+        state.high.retain(|p| !p.starts_with(prefix));
         let after = state.queue.len();
         let removed = before.saturating_sub(after);
         if removed > 0 {
@@ -188,17 +501,19 @@ impl<T: Send + 'static> WorkQueue<T> {
         removed
     }
 
-    fn clear(&self) {
+    fn is_tracked(&self, path: &Path) -> bool {
+        let state = self.state.lock().unwrap();
+        state.queued.contains(path) || state.in_progress.contains(path)
+    }
+
+    fn clear(&self) -> usize {
         let mut state = self.state.lock().unwrap();
+        let removed = state.queue.len();
         state.queue.clear();
         state.queued.clear();
-        state.in_progress.clear();
+        state.high.clear();
         self.cv.notify_all();
-    }
-
-    fn is_tracked(&self, path: &Path) -> bool {
-        let state = self.state.lock().unwrap();
-        state.queued.contains(path) || state.in_progress.contains(path)
+        removed
     }
 }
 
@@ -221,10 +536,12 @@ static META_WORK: Lazy<WorkQueue<()>> = Lazy::new(|| WorkQueue {
 struct HashQueueState {
     queue: VecDeque<PathBuf>,
     queued: HashSet<PathBuf>,
+    high: HashSet<PathBuf>,
     in_progress: HashSet<PathBuf>,
     in_progress_started: HashMap<PathBuf, std::time::Instant>,
     done: usize,
     started_workers: usize,
+    desired_workers: usize,
 }
 
 struct HashQueue {
@@ -237,6 +554,15 @@ static HASH_QUEUE: Lazy<HashQueue> = Lazy::new(|| HashQueue {
     cv: Condvar::new(),
 });
 
+#[derive(Clone, Serialize)]
+struct RootProgressPayload {
+    root_id: String,
+    root: String,
+    queued_albums: usize,
+    thumbnails: StageProgress,
+    metadata: StageProgress,
+}
+
 #[derive(Clone, Serialize)]
 struct PreloadProgressPayload {
     stage: PreloadStage,
@@ -244,40 +570,29 @@ struct PreloadProgressPayload {
     overall_completed: usize,
     overall_total: usize,
     progress: usize,
-    conversions: StageProgress,
     thumbnails: StageProgress,
     metadata: StageProgress,
     active_actions: usize,
+    roots: Vec<RootProgressPayload>,
 }
 
 fn combined_progress_totals() -> (usize, usize) {
-    let conv_total = PRELOAD_CONVERSIONS_TOTAL.load(Ordering::SeqCst);
-    let conv_done = PRELOAD_CONVERSIONS_DONE.load(Ordering::SeqCst);
-
     let thumb_done = PRELOAD_DONE_THUMBS.load(Ordering::SeqCst);
     let meta_done = PRELOAD_DONE_META.load(Ordering::SeqCst);
     let thumb_outstanding = THUMB_WORK.outstanding();
     let meta_outstanding = META_WORK.outstanding();
 
-    let completed = conv_done + thumb_done + meta_done;
-    let total = conv_total + thumb_done + thumb_outstanding + meta_done + meta_outstanding;
+    let completed = thumb_done + meta_done;
+    let total = thumb_done + thumb_outstanding + meta_done + meta_outstanding;
     (completed, total)
 }
 
 fn active_actions() -> usize {
-    let conv_total = PRELOAD_CONVERSIONS_TOTAL.load(Ordering::SeqCst);
-    let conv_done = PRELOAD_CONVERSIONS_DONE.load(Ordering::SeqCst);
-    let conv_outstanding = conv_total.saturating_sub(conv_done);
-    conv_outstanding + THUMB_WORK.outstanding() + META_WORK.outstanding()
+    THUMB_WORK.outstanding() + META_WORK.outstanding()
 }
 
 fn stage_counts(stage: PreloadStage) -> StageProgress {
     match stage {
-        PreloadStage::Conversion => {
-            let total = PRELOAD_CONVERSIONS_TOTAL.load(Ordering::SeqCst);
-            let completed = PRELOAD_CONVERSIONS_DONE.load(Ordering::SeqCst);
-            StageProgress { completed, total }
-        }
         PreloadStage::Thumbnails => {
             let completed = PRELOAD_DONE_THUMBS.load(Ordering::SeqCst);
             StageProgress {
@@ -299,6 +614,47 @@ fn stage_counts(stage: PreloadStage) -> StageProgress {
     }
 }
 
+fn root_progress() -> Vec<RootProgressPayload> {
+    let roots = ROOTS.lock().unwrap().clone();
+    let done = ROOT_DONE.lock().unwrap().clone();
+    let queues = PRELOAD_QUEUE.lock().unwrap();
+    roots
+        .into_iter()
+        .map(|root| {
+            let counters = done.get(&root).copied().unwrap_or_default();
+            RootProgressPayload {
+                root_id: root_id(&root),
+                root: root.to_string_lossy().into_owned(),
+                queued_albums: queues.len_for(&root),
+                thumbnails: StageProgress {
+                    completed: counters.thumbs,
+                    total: counters.thumbs + THUMB_WORK.outstanding_under(&root),
+                },
+                metadata: StageProgress {
+                    completed: counters.meta,
+                    total: counters.meta + META_WORK.outstanding_under(&root),
+                },
+            }
+        })
+        .collect()
+}
+
+fn record_done(path: &Path, stage: PreloadStage) {
+    match stage {
+        PreloadStage::Thumbnails => PRELOAD_DONE_THUMBS.fetch_add(1, Ordering::SeqCst),
+        _ => PRELOAD_DONE_META.fetch_add(1, Ordering::SeqCst),
+    };
+    let Some(root) = root_for(path) else {
+        return;
+    };
+    let mut done = ROOT_DONE.lock().unwrap();
+    let counters = done.entry(root).or_default();
+    match stage {
+        PreloadStage::Thumbnails => counters.thumbs += 1,
+        _ => counters.meta += 1,
+    }
+}
+
 fn build_progress_payload(stage: PreloadStage) -> PreloadProgressPayload {
     let (overall_completed, overall_total) = combined_progress_totals();
     let progress = if overall_total == 0 {
@@ -313,19 +669,15 @@ fn build_progress_payload(stage: PreloadStage) -> PreloadProgressPayload {
         overall_completed,
         overall_total,
         progress,
-        conversions: stage_counts(PreloadStage::Conversion),
         thumbnails: stage_counts(PreloadStage::Thumbnails),
         metadata: stage_counts(PreloadStage::Metadata),
         active_actions: active_actions(),
+        roots: root_progress(),
     }
 }
 
 fn current_stage() -> PreloadStage {
-    let conv_total = PRELOAD_CONVERSIONS_TOTAL.load(Ordering::SeqCst);
-    let conv_done = PRELOAD_CONVERSIONS_DONE.load(Ordering::SeqCst);
-    if conv_total.saturating_sub(conv_done) > 0 {
-        PreloadStage::Conversion
-    } else if THUMB_WORK.outstanding() > 0 {
+    if THUMB_WORK.outstanding() > 0 {
         PreloadStage::Thumbnails
     } else if META_WORK.outstanding() > 0 {
         PreloadStage::Metadata
@@ -334,7 +686,35 @@ fn current_stage() -> PreloadStage {
     }
 }
 
+fn sync_service_jobs() {
+    let albums_done = PRELOAD_DONE_ALBUMS.load(Ordering::SeqCst);
+    let albums_pending = PRELOAD_QUEUE.lock().unwrap().len()
+        + usize::from(CURRENT_PRELOAD_CANCEL.lock().unwrap().is_some());
+    update_service(
+        PRELOAD_JOB,
+        JobKind::Preload,
+        albums_done,
+        albums_done + albums_pending,
+    );
+    let thumbs_done = PRELOAD_DONE_THUMBS.load(Ordering::SeqCst);
+    update_service(
+        THUMBNAILS_JOB,
+        JobKind::Thumbnails,
+        thumbs_done,
+        thumbs_done + THUMB_WORK.outstanding(),
+    );
+    let meta_done = PRELOAD_DONE_META.load(Ordering::SeqCst);
+    update_service(
+        METADATA_JOB,
+        JobKind::Metadata,
+        meta_done,
+        meta_done + META_WORK.outstanding(),
+    );
+}
+
 fn emit_progress(stage: Option<PreloadStage>) {
+    sync_service_jobs();
+    QUEUE_STATE_DIRTY.store(true, Ordering::SeqCst);
     let prev_stage = PreloadStage::from_u8(PRELOAD_STAGE.load(Ordering::SeqCst));
     let stage = match stage {
         Some(s) => {
@@ -368,46 +748,96 @@ fn emit_progress(stage: Option<PreloadStage>) {
     }
 }
 
-pub(crate) fn artifacts_missing(dir: &Path) -> bool {
-    let thumb_dir = dir.join(".room237-thumb");
-    let meta_dir = dir.join(".room237-metadata");
-    !thumb_dir.exists() || !meta_dir.exists()
+fn deepest_root<'a>(roots: impl Iterator<Item = &'a PathBuf>, dir: &Path) -> Option<PathBuf> {
+    roots
+        .filter(|root| dir.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .cloned()
 }
 
-fn reset_preload_state() {
-    if let Some(cancel) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
-        cancel.store(true, Ordering::Relaxed);
+pub(crate) fn root_for(dir: &Path) -> Option<PathBuf> {
+    let roots = ROOTS.lock().unwrap().clone();
+    if let Some(root) = deepest_root(roots.iter(), dir) {
+        return Some(root);
     }
-    PRELOADER_RUNNING.store(false, Ordering::SeqCst);
-    *CURRENT_PRELOAD_CANCEL.lock().unwrap() = None;
+    let canonical: Vec<PathBuf> = roots.iter().filter_map(|r| r.canonicalize().ok()).collect();
+    deepest_root(canonical.iter(), dir)
+}
+
+pub(crate) fn registered_roots() -> Vec<PathBuf> {
+    ROOTS.lock().unwrap().clone()
+}
+
+pub(crate) fn register_root(root: &Path) {
+    let mut roots = ROOTS.lock().unwrap();
+    if roots.iter().any(|r| r == root) {
+        return;
+    }
+    roots.push(root.to_path_buf());
+    log::info!("registered library root {}", root.display());
+}
+
+pub(crate) fn unregister_root(root: &Path) -> bool {
     {
-        let mut q = PRELOAD_QUEUE.lock().unwrap();
-        q.clear();
+        let mut roots = ROOTS.lock().unwrap();
+        let before = roots.len();
+        roots.retain(|r| r != root);
+        if roots.len() == before {
+            return false;
+        }
     }
-    PRELOADED.lock().unwrap().clear();
-    THUMB_WORK.clear();
-    META_WORK.clear();
-    PRELOAD_DONE_THUMBS.store(0, Ordering::SeqCst);
-    PRELOAD_DONE_META.store(0, Ordering::SeqCst);
-    PRELOAD_CONVERSIONS_TOTAL.store(0, Ordering::SeqCst);
-    PRELOAD_CONVERSIONS_DONE.store(0, Ordering::SeqCst);
-    *LAST_PROGRESS_EMIT.lock().unwrap() = None;
-    THUMB_HASH_ALBUMS.lock().unwrap().clear();
-    THUMB_HASH_FAILED.lock().unwrap().clear();
-    PRELOAD_STAGE.store(PreloadStage::Idle.as_u8(), Ordering::SeqCst);
-    emit_progress(Some(PreloadStage::Idle));
+    if let Some((dir, cancel)) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
+        if dir.starts_with(root) {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }
+    drop_preload_for_path(root);
+    PENDING_RESTORE.lock().unwrap().remove(root);
+    ROOT_DONE.lock().unwrap().remove(root);
+    THUMB_HASH_FAILED
+        .lock()
+        .unwrap()
+        .retain(|p, _| !p.starts_with(root));
+    log::info!("closed library root {}", root.display());
+    emit_progress(None);
+    true
 }
 
-pub(crate) fn set_active_root(root: &Path) {
-    let mut active = ACTIVE_ROOT.lock().unwrap();
-    if active.as_ref().map(|r| r == root).unwrap_or(false) {
-        return;
+#[tauri::command]
+pub fn close_library_root(root_dir: String) -> Result<bool, String> {
+    Ok(unregister_root(Path::new(&root_dir)))
+}
+
+pub(crate) fn cancel_preload_service(id: &str) {
+    match id {
+        PRELOAD_JOB => {
+            PRELOAD_QUEUE.lock().unwrap().clear();
+            if let Some((_, cancel)) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
+                cancel.store(true, Ordering::SeqCst);
+            }
+        }
+        THUMBNAILS_JOB => {
+            THUMB_WORK.clear();
+        }
+        METADATA_JOB => {
+            META_WORK.clear();
+        }
+        HASHES_JOB => {
+            THUMB_HASH_ALBUMS.lock().unwrap().clear();
+            let (done, total) = HASH_QUEUE.clear();
+            emit_hash_event("hash-progress", done, total);
+        }
+        _ => return,
     }
-    *active = Some(root.to_path_buf());
-    reset_preload_state();
+    emit_progress(None);
+}
+
+pub(crate) fn preload_app() -> Option<AppHandle<Wry>> {
+    PRELOAD_APP.lock().unwrap().clone()
 }
 
 fn emit_hash_event(kind: &str, completed: usize, total: usize) {
+    update_service(HASHES_JOB, JobKind::Hashes, completed, total);
     if let Some(app) = PRELOAD_APP.lock().unwrap().as_ref() {
         let _ = app.emit(
             kind,
@@ -427,11 +857,22 @@ impl HashQueue {
             state.done = 0;
         }
         for path in paths {
-            if state.queued.contains(path) || state.in_progress.contains(path) {
+            if state.in_progress.contains(path) {
+                continue;
+            }
+            if state.queued.contains(path) {
+                if matches!(priority, TaskPriority::High) && state.high.insert(path.clone()) {
+                    state.queue.retain(|p| p != path);
+                    state.queue.push_front(path.clone());
+                    added = true;
+                }
                 continue;
             }
             match priority {
-                TaskPriority::High => state.queue.push_front(path.clone()),
+                TaskPriority::High => {
+                    state.high.insert(path.clone());
+                    state.queue.push_front(path.clone());
+                }
                 TaskPriority::Low => state.queue.push_back(path.clone()),
             }
             state.queued.insert(path.clone());
@@ -445,21 +886,45 @@ impl HashQueue {
         (added, done, total)
     }
 
-    fn next_task_blocking(&self) -> PathBuf {
+    fn next_task_blocking(&self) -> Option<(PathBuf, TaskPriority)> {
         let mut guard = self.state.lock().unwrap();
         loop {
+            if guard.started_workers > guard.desired_workers {
+                guard.started_workers -= 1;
+                return None;
+            }
             if let Some(path) = guard.queue.pop_front() {
                 guard.queued.remove(&path);
+                let priority = if guard.high.remove(&path) {
+                    TaskPriority::High
+                } else {
+                    TaskPriority::Low
+                };
                 guard.in_progress.insert(path.clone());
-                guard
-                    .in_progress_started
-                    .insert(path.clone(), std::time::Instant::now());
-                return path;
+                return Some((path, priority));
             }
             guard = self.cv.wait(guard).unwrap();
         }
     }
 
+    fn mark_started(&self, path: &Path) {
+        let mut state = self.state.lock().unwrap();
+        if state.in_progress.contains(path) {
+            state
+                .in_progress_started
+                .insert(path.to_path_buf(), std::time::Instant::now());
+        }
+    }
+
+    fn resize(&self, desired: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.desired_workers = desired;
+        let spawn = desired.saturating_sub(state.started_workers);
+        state.started_workers += spawn;
+        self.cv.notify_all();
+        spawn
+    }
+
     fn mark_done(&self, path: &Path) -> (usize, usize) {
         let mut state = self.state.lock().unwrap();
         state.in_progress.remove(path);
@@ -468,6 +933,7 @@ impl HashQueue {
         let queue_empty = state.queue.is_empty() && state.in_progress.is_empty();
         let total = state.done + state.queue.len() + state.in_progress.len();
         let done = state.done.min(total);
+        drop(state);
         self.cv.notify_all();
         if queue_empty {
             THUMB_HASH_DONE_ONCE.store(true, Ordering::SeqCst);
@@ -481,11 +947,23 @@ impl HashQueue {
         !state.queue.is_empty() || !state.in_progress.is_empty()
     }
 
+    fn pending_under(&self, prefix: &Path) -> Vec<PathBuf> {
+        let state = self.state.lock().unwrap();
+        state
+            .in_progress
+            .iter()
+            .chain(state.queue.iter())
+            .filter(|p| p.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
     fn trim_prefix(&self, prefix: &Path) -> Option<(usize, usize)> {
         let mut state = self.state.lock().unwrap();
         let before = state.queue.len();
         state.queue.retain(|p| !p.starts_with(prefix));
         state.queued.retain(|p| !p.starts_with(prefix));
+        state.high.retain(|p| !p.starts_with(prefix));
         let removed = before.saturating_sub(state.queue.len());
         if removed > 0 {
             if state.queue.is_empty() && state.in_progress.is_empty() {
@@ -499,6 +977,19 @@ impl HashQueue {
         None
     }
 
+    fn clear(&self) -> (usize, usize) {
+        let mut state = self.state.lock().unwrap();
+        state.queue.clear();
+        state.queued.clear();
+        state.high.clear();
+        if state.in_progress.is_empty() {
+            state.done = 0;
+        }
+        let total = state.done + state.in_progress.len();
+        self.cv.notify_all();
+        (state.done.min(total), total)
+    }
+
     fn totals(&self) -> (usize, usize) {
         let state = self.state.lock().unwrap();
         let total = state.done + state.queue.len() + state.in_progress.len();
@@ -541,30 +1032,36 @@ impl HashQueue {
     }
 }
 
-fn hash_entry_ready(entry: Option<&crate::metadata::FileMetaEntry>, cfg: &HashConfig) -> bool {
+fn entry_hashed(e: &FileMetaEntry, cfg: &HashConfig, video: bool) -> bool {
+    if video {
+        return e.video_hash_version.as_deref() == Some(&cfg.video_hash_version)
+            && e.video_hashes.len() == VIDEO_HASH_POINTS.len();
+    }
+    let version_ok = e.hash_version.as_deref() == Some(&cfg.hash_version);
+    let bits_ok = e.hash_bits.unwrap_or(cfg.bits) == cfg.bits;
+    version_ok && bits_ok && e.hash.is_some()
+}
+
+fn hash_entry_ready(
+    entry: Option<&FileMetaEntry>,
+    path: &Path,
+    cfg: &HashConfig,
+    video: bool,
+) -> bool {
     match entry {
-        Some(e) => {
-            if e.hash_failed {
-                return true;
-            }
-            let version_ok = e.hash_version.as_deref() == Some(&cfg.hash_version);
-            let bits_ok = e.hash_bits.unwrap_or(cfg.bits) == cfg.bits;
-            version_ok && bits_ok && e.hash.is_some()
-        }
+        Some(e) => e.hash_blocked(path) || entry_hashed(e, cfg, video),
         None => false,
     }
 }
 
-fn album_entry_needs_hash(entry: Option<&FileMetaEntry>, cfg: &HashConfig) -> bool {
+fn album_entry_needs_hash(
+    entry: Option<&FileMetaEntry>,
+    path: &Path,
+    cfg: &HashConfig,
+    video: bool,
+) -> bool {
     match entry {
-        Some(e) => {
-            if e.hash_failed {
-                return false;
-            }
-            let version_ok = e.hash_version.as_deref() == Some(&cfg.hash_version);
-            let bits_ok = e.hash_bits.unwrap_or(cfg.bits) == cfg.bits;
-            !(version_ok && bits_ok && e.hash.is_some())
-        }
+        Some(e) => !e.hash_blocked(path) && !entry_hashed(e, cfg, video),
         None => true,
     }
 }
@@ -615,7 +1112,12 @@ fn enqueue_hashes_internal(
                 .entry(parent.clone())
                 .or_insert_with(|| read_album_meta(&parent));
 
-            if meta.files.get(name).map(|e| e.hash_failed).unwrap_or(false) {
+            if meta
+                .files
+                .get(name)
+                .map(|e| e.hash_blocked(p))
+                .unwrap_or(false)
+            {
                 continue;
             }
 
@@ -625,10 +1127,11 @@ fn enqueue_hashes_internal(
                     .and_then(|s| s.to_str())
                     .unwrap_or("")
                     .to_ascii_lowercase();
-                if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+                let video = VIDEO_EXTENSIONS.contains(&ext.as_str());
+                if !video && !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
                     continue;
                 }
-                if !album_entry_needs_hash(meta.files.get(name), cfg) {
+                if !album_entry_needs_hash(meta.files.get(name), p, cfg, video) {
                     continue;
                 }
             }
@@ -665,6 +1168,16 @@ fn enqueue_hashes_internal(
     added
 }
 
+pub(crate) fn enqueue_hash_retries(paths: &[PathBuf]) -> bool {
+    {
+        let mut failed = THUMB_HASH_FAILED.lock().unwrap();
+        for path in paths {
+            failed.remove(path);
+        }
+    }
+    enqueue_hashes_prescreened(paths, TaskPriority::High, true)
+}
+
 pub(crate) fn enqueue_thumb_task(
     path: PathBuf,
     thumb_dir: PathBuf,
@@ -705,6 +1218,7 @@ pub(crate) fn wait_for_album_hashes(
     files: &[PathBuf],
     cfg: &HashConfig,
     priority: TaskPriority,
+    cancel: Option<&AtomicBool>,
 ) -> Result<(), String> {
     const HASH_WAIT_POLL_MS: u64 = 500;
     log::info!(
@@ -714,10 +1228,10 @@ pub(crate) fn wait_for_album_hashes(
     );
     if artifacts_missing(dir) {
         log::info!(
-            "hash-wait: artifacts missing for {} – requeuing all albums under active root",
+            "hash-wait: artifacts missing for {} – requeuing all albums under its root",
             dir.display()
         );
-        if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
+        if let Some(root) = root_for(dir) {
             if let Ok(albums) = walk_album_paths(&root) {
                 for album in albums {
                     drop_preload_for_path(&album);
@@ -738,7 +1252,12 @@ pub(crate) fn wait_for_album_hashes(
             Some(n) => n,
             None => continue,
         };
-        if album_entry_needs_hash(album_meta.files.get(name), cfg) {
+        if album_entry_needs_hash(
+            album_meta.files.get(name),
+            p,
+            cfg,
+            has_extension(p, VIDEO_EXTENSIONS),
+        ) {
             pending.insert(p.clone());
         }
     }
@@ -765,13 +1284,46 @@ pub(crate) fn wait_for_album_hashes(
     let mut last_done_total: (usize, usize) = HASH_QUEUE.totals();
     let mut last_progress_ts = std::time::Instant::now();
     loop {
+        if cancel.map(|c| c.load(Ordering::SeqCst)).unwrap_or(false) {
+            log::info!(
+                "hash-wait: cancelled dir={} pending={}",
+                dir.display(),
+                pending.len()
+            );
+            return Err("Hash wait cancelled".to_string());
+        }
+        match job_state(HASHES_JOB) {
+            Some(JobState::Cancelled) => {
+                log::info!(
+                    "hash-wait: hashing cancelled dir={} pending={}",
+                    dir.display(),
+                    pending.len()
+                );
+                return Err("Hashing was cancelled".to_string());
+            }
+            Some(JobState::Paused) => {
+                last_progress_ts = std::time::Instant::now();
+                let guard = HASH_QUEUE.state.lock().unwrap();
+                let _ = HASH_QUEUE
+                    .cv
+                    .wait_timeout(guard, std::time::Duration::from_millis(HASH_WAIT_POLL_MS))
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+            _ => {}
+        }
         let album_meta = read_album_meta(dir);
         pending.retain(|p| {
             let name = match p.file_name().and_then(|n| n.to_str()) {
                 Some(n) => n,
                 None => return false,
             };
-            !hash_entry_ready(album_meta.files.get(name), cfg)
+            !hash_entry_ready(
+                album_meta.files.get(name),
+                p,
+                cfg,
+                has_extension(p, VIDEO_EXTENSIONS),
+            )
         });
 
         if pending.is_empty() {
@@ -806,7 +1358,7 @@ pub(crate) fn wait_for_album_hashes(
         {
             if let Some((d, t, stuck)) = HASH_QUEUE.fail_in_progress(|p| {
                 log::warn!("hash-wait: marking stuck hash as failed {}", p.display());
-                let _ = mark_hash_failed(p);
+                let _ = mark_hash_failed(p, "hashing stalled");
             }) {
                 log::warn!(
                     "hash-wait: forced completion for {} stuck items dir={} hash_queue={}/{} in_progress_before={}",
@@ -876,8 +1428,11 @@ fn try_emit_end() {
     {
         PRELOAD_DONE_THUMBS.store(0, Ordering::SeqCst);
         PRELOAD_DONE_META.store(0, Ordering::SeqCst);
+        PRELOAD_DONE_ALBUMS.store(0, Ordering::SeqCst);
+        ROOT_DONE.lock().unwrap().clear();
         PRELOAD_STAGE.store(PreloadStage::Idle.as_u8(), Ordering::SeqCst);
         emit_progress(Some(PreloadStage::Idle));
+        persist_queue_state();
         if !hash_running {
             schedule_thumb_hash_scan();
         }
@@ -885,18 +1440,13 @@ fn try_emit_end() {
 }
 
 fn queue_album_for_hashing(dir: &Path) {
-    if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-        if !dir.starts_with(&root) {
-            return;
-        }
+    if root_for(dir).is_none() {
+        return;
     }
     THUMB_HASH_ALBUMS.lock().unwrap().insert(dir.to_path_buf());
 }
 
 fn schedule_thumb_hash_scan() {
-    let settings = read_settings();
-    let hash_cfg = settings.hash_config();
-    let active_root = ACTIVE_ROOT.lock().unwrap().clone();
     let albums: Vec<PathBuf> = {
         let mut pending = THUMB_HASH_ALBUMS.lock().unwrap();
         pending.drain().collect()
@@ -907,14 +1457,13 @@ fn schedule_thumb_hash_scan() {
         return;
     }
 
-    let mut to_queue: Vec<PathBuf> = Vec::new();
+    let mut to_queue: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
     for album in albums {
-        if let Some(root) = active_root.as_ref() {
-            if !album.starts_with(root) {
-                continue;
-            }
-        }
-        let thumb_dir = album.join(".room237-thumb");
+        let Some(root) = root_for(&album) else {
+            continue;
+        };
+        let to_queue = to_queue.entry(root).or_default();
+        let thumb_dir = album_thumb_dir(&album);
         if let Ok(entries) = fs::read_dir(&thumb_dir) {
             for entry in entries.flatten() {
                 let thumb_path = entry.path();
@@ -933,7 +1482,9 @@ fn schedule_thumb_hash_scan() {
                         .and_then(|s| s.to_str())
                         .unwrap_or("")
                         .to_ascii_lowercase();
-                    if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+                    if !IMAGE_EXTENSIONS.contains(&ext.as_str())
+                        && !VIDEO_EXTENSIONS.contains(&ext.as_str())
+                    {
                         continue;
                     }
                     to_queue.push(original);
@@ -941,20 +1492,25 @@ fn schedule_thumb_hash_scan() {
             }
         }
     }
-    if !to_queue.is_empty() {
-        let _ = enqueue_hashes(&to_queue, &hash_cfg, TaskPriority::Low, true);
+    for (root, paths) in to_queue {
+        let hash_cfg = settings_for(&root).hash_config();
+        let _ = enqueue_hashes(&paths, &hash_cfg, TaskPriority::Low, true);
     }
 }
 
 pub(crate) fn start_preloader_worker(app: AppHandle<Wry>) {
     *PRELOAD_APP.lock().unwrap() = Some(app.clone());
     if PRELOADER_RUNNING.swap(true, Ordering::SeqCst) {
+        restore_pending_work();
         return;
     }
     log::info!("preloader thread spawn");
     *LAST_PROGRESS_EMIT.lock().unwrap() = None;
     PRELOAD_DONE_THUMBS.store(0, Ordering::SeqCst);
     PRELOAD_DONE_META.store(0, Ordering::SeqCst);
+    PRELOAD_DONE_ALBUMS.store(0, Ordering::SeqCst);
+    ROOT_DONE.lock().unwrap().clear();
+    restore_pending_work();
     let initial_stage = current_stage();
     PRELOAD_STAGE.store(initial_stage.as_u8(), Ordering::SeqCst);
     emit_progress(Some(initial_stage));
@@ -962,17 +1518,16 @@ pub(crate) fn start_preloader_worker(app: AppHandle<Wry>) {
     thread::spawn(move || {
         set_low_priority_current_thread();
         loop {
+            wait_while_paused(PRELOAD_JOB);
             let dir_opt = {
                 let mut q = PRELOAD_QUEUE.lock().unwrap();
-                q.pop_front()
+                q.pop_next()
             };
 
             match dir_opt {
                 Some(dir) => {
-                    if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-                        if !dir.starts_with(&root) {
-                            continue;
-                        }
+                    if root_for(&dir).is_none() {
+                        continue;
                     }
                     if PRELOADED.lock().unwrap().contains(&dir) {
                         continue;
@@ -980,7 +1535,7 @@ pub(crate) fn start_preloader_worker(app: AppHandle<Wry>) {
                     queue_album_for_hashing(&dir);
                     log::info!("preload begin {}", dir.display());
                     let cancel = Arc::new(AtomicBool::new(false));
-                    *CURRENT_PRELOAD_CANCEL.lock().unwrap() = Some(cancel.clone());
+                    *CURRENT_PRELOAD_CANCEL.lock().unwrap() = Some((dir.clone(), cancel.clone()));
 
                     let _ = preload_dir(&dir, cancel.clone(), true);
                     *CURRENT_PRELOAD_CANCEL.lock().unwrap() = None;
@@ -990,6 +1545,8 @@ pub(crate) fn start_preloader_worker(app: AppHandle<Wry>) {
                         continue;
                     }
                     PRELOADED.lock().unwrap().insert(dir.clone());
+                    PRELOAD_DONE_ALBUMS.fetch_add(1, Ordering::SeqCst);
+                    emit_progress(None);
                 }
                 None => {
                     PRELOADER_RUNNING.store(false, Ordering::SeqCst);
@@ -1003,11 +1560,9 @@ pub(crate) fn start_preloader_worker(app: AppHandle<Wry>) {
 }
 
 pub fn enqueue_preload(dir: &Path) {
-    if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-        if !dir.starts_with(&root) {
-            return;
-        }
-    }
+    let Some(root) = root_for(dir) else {
+        return;
+    };
     let missing_artifacts = artifacts_missing(dir);
     {
         let mut preloaded = PRELOADED.lock().unwrap();
@@ -1023,10 +1578,9 @@ pub fn enqueue_preload(dir: &Path) {
     }
     {
         let mut q = PRELOAD_QUEUE.lock().unwrap();
-        if q.iter().any(|d| d == dir) {
+        if !q.push(&root, dir) {
             return;
         }
-        q.push_back(dir.to_path_buf());
         log::info!("queued {}", dir.display());
     }
 
@@ -1045,39 +1599,20 @@ pub fn preload_dir(
         log::warn!("skip preload for missing album {}", dir.display());
         return Ok(());
     }
-    if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-        if !dir.starts_with(&root) {
-            return Ok(());
-        }
+    if root_for(dir).is_none() {
+        return Ok(());
     }
-    let thumb_dir = dir.join(".room237-thumb");
+    let thumb_dir = album_thumb_dir(dir);
     fs::create_dir_all(&thumb_dir).map_err(|e| e.to_string())?;
+    let settings = settings_for(dir);
+    let preview_version = settings.preview_version();
+    let thumb_version = settings.thumb_version();
 
     let mut media: Vec<PathBuf> = Vec::new();
 
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if has_extension(&path, &["heic"]) {
-                if cancel.load(Ordering::Relaxed) {
-                    return Ok(());
-                }
-                let mut jpeg = path.clone();
-                jpeg.set_extension("jpeg");
-                let needs_conversion =
-                    !(jpeg.exists() && newer_than(&jpeg, &path).unwrap_or(false));
-                if needs_conversion {
-                    PRELOAD_CONVERSIONS_TOTAL.fetch_add(1, Ordering::SeqCst);
-                    emit_progress(Some(PreloadStage::Conversion));
-                    let _ = heic_to_jpeg(&path, &jpeg);
-                    PRELOAD_CONVERSIONS_DONE.fetch_add(1, Ordering::SeqCst);
-                    emit_progress(Some(PreloadStage::Conversion));
-                }
-                if jpeg.exists() {
-                    media.push(jpeg);
-                }
-                continue;
-            }
             if has_extension(&path, IMAGE_EXTENSIONS) || has_extension(&path, VIDEO_EXTENSIONS) {
                 media.push(path);
             }
@@ -1097,12 +1632,27 @@ pub fn preload_dir(
             .to_string();
         let cached_entry = album_meta.files.get(&fname);
         let meta_cached = cached_entry.and_then(|e| e.meta.as_ref()).is_some();
-        let thumb_fresh = thumb_path(p, &thumb_dir)
-            .ok()
-            .filter(|t| t.exists() && newer_than(t, p).unwrap_or(false))
-            .is_some();
-
-        if !thumb_fresh && !THUMB_WORK.is_tracked(p) {
+        let thumb_fresh = cached_entry.and_then(|e| e.thumb_version.as_deref())
+            == Some(thumb_version.as_str())
+            && thumb_path(p, &thumb_dir)
+                .ok()
+                .filter(|t| t.exists() && newer_than(t, p).unwrap_or(false))
+                .is_some();
+        let view_fresh = !needs_view_transcode(p)
+            || view_path(p, &thumb_dir)
+                .ok()
+                .filter(|v| v.exists() && newer_than(v, p).unwrap_or(false))
+                .is_some();
+        let preview_fresh = !settings.previews.enabled
+            || !supports_animated_preview(p)
+            || (cached_entry.and_then(|e| e.preview_version.as_deref())
+                == Some(preview_version.as_str())
+                && preview_path(p, &thumb_dir)
+                    .ok()
+                    .filter(|v| v.exists() && newer_than(v, p).unwrap_or(false))
+                    .is_some());
+
+        if (!thumb_fresh || !view_fresh || !preview_fresh) && !THUMB_WORK.is_tracked(p) {
             let _ = enqueue_thumb_task(
                 p.clone(),
                 thumb_dir.clone(),
@@ -1117,10 +1667,232 @@ pub fn preload_dir(
     Ok(())
 }
 
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct PersistedRoot {
+    root: PathBuf,
+    #[serde(default)]
+    albums: Vec<PathBuf>,
+    #[serde(default)]
+    preloaded: Vec<(PathBuf, u64)>,
+    #[serde(default)]
+    thumbs: Vec<PathBuf>,
+    #[serde(default)]
+    meta: Vec<PathBuf>,
+    #[serde(default)]
+    hashes: Vec<PathBuf>,
+    #[serde(default)]
+    thumbs_done: usize,
+    #[serde(default)]
+    meta_done: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PersistedQueues {
+    roots: Vec<PersistedRoot>,
+}
+
+fn dir_mtime(dir: &Path) -> Option<u64> {
+    fs::metadata(dir)
+        .and_then(|m| m.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+pub(crate) fn init_queue_store(app: &AppHandle<Wry>) -> Result<(), String> {
+    let path = app
+        .path()
+        .resolve(QUEUE_STATE_FILE, BaseDirectory::AppLocalData)
+        .map_err(|e| e.to_string())?;
+    let persisted = fs::read_to_string(&path)
+        .ok()
+        .and_then(|txt| serde_json::from_str::<PersistedQueues>(&txt).ok())
+        .unwrap_or_default();
+    let mut pending = PENDING_RESTORE.lock().unwrap();
+    for entry in persisted.roots {
+        pending.insert(entry.root.clone(), entry);
+    }
+    if !pending.is_empty() {
+        log::info!("preload queue: {} roots waiting to resume", pending.len());
+    }
+    drop(pending);
+    if QUEUE_STATE_PATH.set(path).is_ok() {
+        thread::spawn(|| loop {
+            thread::sleep(QUEUE_PERSIST_INTERVAL);
+            if QUEUE_STATE_DIRTY.swap(false, Ordering::SeqCst) {
+                persist_queue_state();
+            }
+        });
+    }
+    Ok(())
+}
+
+fn snapshot_root(root: &Path) -> PersistedRoot {
+    let mut albums = PRELOAD_QUEUE.lock().unwrap().pending_for(root);
+    if let Some((dir, _)) = CURRENT_PRELOAD_CANCEL.lock().unwrap().as_ref() {
+        if dir.starts_with(root) {
+            albums.insert(0, dir.clone());
+        }
+    }
+    let preloaded = PRELOADED
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|dir| dir.starts_with(root))
+        .cloned()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .filter_map(|dir| dir_mtime(&dir).map(|mtime| (dir, mtime)))
+        .collect();
+    let counters = ROOT_DONE
+        .lock()
+        .unwrap()
+        .get(root)
+        .copied()
+        .unwrap_or_default();
+    PersistedRoot {
+        root: root.to_path_buf(),
+        albums,
+        preloaded,
+        thumbs: THUMB_WORK.pending_under(root),
+        meta: META_WORK.pending_under(root),
+        hashes: HASH_QUEUE.pending_under(root),
+        thumbs_done: counters.thumbs,
+        meta_done: counters.meta,
+    }
+}
+
+fn persist_queue_state() {
+    let Some(path) = QUEUE_STATE_PATH.get() else {
+        return;
+    };
+    let _guard = QUEUE_PERSIST_LOCK.lock().unwrap();
+    let mut roots: Vec<PersistedRoot> = registered_roots()
+        .iter()
+        .map(|root| snapshot_root(root))
+        .collect();
+    {
+        let pending = PENDING_RESTORE.lock().unwrap();
+        roots.extend(pending.values().cloned());
+    }
+    let json = match serde_json::to_string(&PersistedQueues { roots }) {
+        Ok(json) => json,
+        Err(e) => {
+            log::warn!("preload queue: failed to serialize state: {}", e);
+            return;
+        }
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let tmp = path.with_extension("json.tmp");
+    if let Err(e) = fs::write(&tmp, json).and_then(|_| fs::rename(&tmp, path)) {
+        log::warn!("preload queue: failed to write {}: {}", path.display(), e);
+    }
+}
+
+pub(crate) fn save_queue_state() {
+    QUEUE_STATE_DIRTY.store(false, Ordering::SeqCst);
+    persist_queue_state();
+}
+
+fn restore_pending_work() {
+    let restored: Vec<PersistedRoot> = {
+        let mut pending = PENDING_RESTORE.lock().unwrap();
+        registered_roots()
+            .iter()
+            .filter_map(|root| pending.remove(root))
+            .collect()
+    };
+    for entry in restored {
+        restore_root(entry);
+    }
+}
+
+fn restore_root(entry: PersistedRoot) {
+    let mut dropped = 0_usize;
+    {
+        let mut preloaded = PRELOADED.lock().unwrap();
+        for (dir, mtime) in &entry.preloaded {
+            if dir_mtime(dir) == Some(*mtime) && !artifacts_missing(dir) {
+                preloaded.insert(dir.clone());
+            }
+        }
+    }
+    {
+        let mut queue = PRELOAD_QUEUE.lock().unwrap();
+        for dir in &entry.albums {
+            if dir.is_dir() {
+                queue.push(&entry.root, dir);
+            } else {
+                dropped += 1;
+            }
+        }
+    }
+
+    let mut thumbs = 0_usize;
+    for path in entry.thumbs {
+        let Some(parent) = path.parent().filter(|_| path.exists()) else {
+            dropped += 1;
+            continue;
+        };
+        let task = ThumbTask {
+            thumb_dir: album_thumb_dir(parent),
+        };
+        if THUMB_WORK.enqueue(path, task, TaskPriority::Low) {
+            thumbs += 1;
+        }
+    }
+    let mut meta = 0_usize;
+    for path in entry.meta {
+        if !path.exists() {
+            dropped += 1;
+            continue;
+        }
+        if META_WORK.enqueue(path, (), TaskPriority::Low) {
+            meta += 1;
+        }
+    }
+    let hashes: Vec<PathBuf> = entry.hashes.into_iter().filter(|p| p.exists()).collect();
+
+    PRELOAD_DONE_THUMBS.fetch_add(entry.thumbs_done, Ordering::SeqCst);
+    PRELOAD_DONE_META.fetch_add(entry.meta_done, Ordering::SeqCst);
+    {
+        let mut done = ROOT_DONE.lock().unwrap();
+        let counters = done.entry(entry.root.clone()).or_default();
+        counters.thumbs += entry.thumbs_done;
+        counters.meta += entry.meta_done;
+    }
+
+    log::info!(
+        "preload queue: resumed {} thumbs, {} meta, {} hashes for {} (dropped {} stale)",
+        thumbs,
+        meta,
+        hashes.len(),
+        entry.root.display(),
+        dropped
+    );
+    if thumbs > 0 {
+        start_thumb_worker();
+    }
+    if meta > 0 {
+        start_meta_worker();
+    }
+    if !hashes.is_empty() {
+        let (added, done, total) = HASH_QUEUE.enqueue_many(&hashes, TaskPriority::Low);
+        if added {
+            emit_hash_event("hash-progress", done, total);
+            start_thumb_hash_worker();
+        }
+    }
+    emit_progress(None);
+}
+
 pub fn drop_preload_for_path(prefix: &Path) {
     {
         let mut q = PRELOAD_QUEUE.lock().unwrap();
-        q.retain(|p| !p.starts_with(prefix));
+        q.retain_outside(prefix);
     }
     {
         let mut preloaded = PRELOADED.lock().unwrap();
@@ -1150,29 +1922,23 @@ pub fn drop_preload_for_path(prefix: &Path) {
 }
 
 fn start_thumb_worker() {
-    let settings = read_settings();
-    let desired = settings.preload.thumb_workers.max(1) as usize;
-    loop {
-        let worker_id = {
-            let mut state = THUMB_WORK.state.lock().unwrap();
-            if state.started_workers >= desired {
-                return;
-            }
-            state.started_workers += 1;
-            THUMB_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
-        };
-        let settings_clone = settings.clone();
+    let desired = read_settings().preload.thumb_workers.max(1) as usize;
+    for _ in 0..THUMB_WORK.resize(desired) {
+        let worker_id = THUMB_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
         thread::spawn(move || {
             set_low_priority_current_thread();
             log::info!("thumb worker #{worker_id} start");
             loop {
-                let (p, task) = THUMB_WORK.next_task_blocking();
-                if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-                    if !p.starts_with(&root) {
-                        THUMB_WORK.mark_done(&p);
-                        emit_progress(None);
-                        continue;
-                    }
+                wait_while_paused(THUMBNAILS_JOB);
+                let Some((p, task, priority)) = THUMB_WORK.next_task_blocking() else {
+                    log::info!("thumb worker #{worker_id} retired");
+                    return;
+                };
+                let slot = scheduler::acquire(priority);
+                if root_for(&p).is_none() {
+                    THUMB_WORK.mark_done(&p);
+                    emit_progress(None);
+                    continue;
                 }
                 if is_thumb_failed(p.as_path()) {
                     THUMB_WORK.mark_done(&p);
@@ -1180,46 +1946,66 @@ fn start_thumb_worker() {
                     continue;
                 }
                 log::debug!("thumb worker #{worker_id} processing {}", p.display());
-                match ensure_thumb_with_settings(p.as_path(), &task.thumb_dir, &settings_clone) {
+                let settings = settings_for(&p);
+                match ensure_thumb_with_settings(p.as_path(), &task.thumb_dir, &settings) {
                     Ok(_) => {
+                        if needs_view_transcode(p.as_path()) {
+                            if let Err(e) =
+                                ensure_view_transcode(p.as_path(), &task.thumb_dir, &settings)
+                            {
+                                log::warn!(
+                                    "Failed to transcode {} for viewing: {}",
+                                    p.display(),
+                                    e
+                                );
+                            }
+                        }
+                        if settings.previews.enabled && supports_animated_preview(p.as_path()) {
+                            if let Err(e) =
+                                ensure_animated_preview(p.as_path(), &task.thumb_dir, &settings)
+                            {
+                                log::warn!(
+                                    "Failed to generate animated preview {}: {}",
+                                    p.display(),
+                                    e
+                                );
+                            }
+                        }
                         let _ = clear_thumb_failed(p.as_path());
-                        PRELOAD_DONE_THUMBS.fetch_add(1, Ordering::SeqCst);
+                        record_done(&p, PreloadStage::Thumbnails);
                     }
                     Err(e) => {
                         log::error!("Failed to generate thumbnail {}: {}", p.display(), e);
-                        let _ = mark_thumb_failed(p.as_path());
+                        service_error(THUMBNAILS_JOB, format!("{}: {}", p.display(), e));
+                        let _ = mark_thumb_failed(p.as_path(), &e);
                     }
                 }
                 THUMB_WORK.mark_done(&p);
                 emit_progress(Some(PreloadStage::Thumbnails));
+                slot.finish();
             }
         });
     }
 }
 
 fn start_meta_worker() {
-    let settings = read_settings();
-    let desired = settings.preload.meta_workers.max(1) as usize;
-    loop {
-        let worker_id = {
-            let mut state = META_WORK.state.lock().unwrap();
-            if state.started_workers >= desired {
-                return;
-            }
-            state.started_workers += 1;
-            META_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
-        };
+    let desired = read_settings().preload.meta_workers.max(1) as usize;
+    for _ in 0..META_WORK.resize(desired) {
+        let worker_id = META_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
         thread::spawn(move || {
             set_low_priority_current_thread();
             log::debug!("meta worker #{worker_id} start");
             loop {
-                let (p, _) = META_WORK.next_task_blocking();
-                if let Some(root) = ACTIVE_ROOT.lock().unwrap().clone() {
-                    if !p.starts_with(&root) {
-                        META_WORK.mark_done(&p);
-                        emit_progress(None);
-                        continue;
-                    }
+                wait_while_paused(METADATA_JOB);
+                let Some((p, _, priority)) = META_WORK.next_task_blocking() else {
+                    log::debug!("meta worker #{worker_id} retired");
+                    return;
+                };
+                let slot = scheduler::acquire(priority);
+                if root_for(&p).is_none() {
+                    META_WORK.mark_done(&p);
+                    emit_progress(None);
+                    continue;
                 }
                 if is_meta_failed(p.as_path()) {
                     META_WORK.mark_done(&p);
@@ -1229,43 +2015,38 @@ fn start_meta_worker() {
                 log::debug!("meta worker #{worker_id} processing {}", p.display());
                 match get_file_metadata_cached(p.as_path()) {
                     Ok(_) => {
-                        PRELOAD_DONE_META.fetch_add(1, Ordering::SeqCst);
+                        record_done(&p, PreloadStage::Metadata);
                     }
                     Err(e) => {
                         log::error!("Failed to extract metadata {}: {}", p.display(), e);
-                        let _ = mark_meta_failed(p.as_path());
+                        service_error(METADATA_JOB, format!("{}: {}", p.display(), e));
+                        let _ = mark_meta_failed(p.as_path(), &e);
                     }
                 }
                 META_WORK.mark_done(&p);
                 emit_progress(Some(PreloadStage::Metadata));
+                slot.finish();
             }
         });
     }
 }
 
 fn start_thumb_hash_worker() {
-    let settings = read_settings();
-    let hash_cfg = settings.hash_config();
-    let desired = settings.preload.hash_workers.max(1) as usize;
-    loop {
-        let worker_id = {
-            let mut state = HASH_QUEUE.state.lock().unwrap();
-            if state.started_workers >= desired {
-                return;
-            }
-            state.started_workers += 1;
-            HASH_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
-        };
-        let settings_clone = settings.clone();
-        let hash_cfg_clone = hash_cfg.clone();
+    let desired = read_settings().preload.hash_workers.max(1) as usize;
+    for _ in 0..HASH_QUEUE.resize(desired) {
+        let worker_id = HASH_WORKER_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
         std::thread::spawn(move || {
             set_low_priority_current_thread();
             log::debug!("hash worker #{} start", worker_id);
-            let retry_on_thumb_change = settings_clone.preload.thumb_hash_retry_on_thumb_change;
-            let delay_ms = settings_clone.preload.thumb_hash_queue_delay_ms;
             let mut processed: u64 = 0;
             loop {
-                let original = HASH_QUEUE.next_task_blocking();
+                wait_while_paused(HASHES_JOB);
+                let Some((original, priority)) = HASH_QUEUE.next_task_blocking() else {
+                    log::debug!("hash worker #{} retired", worker_id);
+                    return;
+                };
+                let slot = scheduler::acquire(priority);
+                HASH_QUEUE.mark_started(&original);
                 processed = processed.saturating_add(1);
                 let log_this = processed <= 20 || processed % 100 == 0;
                 if log_this {
@@ -1297,13 +2078,33 @@ fn start_thumb_hash_worker() {
                         original.display()
                     );
                 }
+                let task_settings = settings_for(&original);
+                let retry_on_thumb_change = task_settings.preload.thumb_hash_retry_on_thumb_change;
+                let delay_ms = task_settings.preload.thumb_hash_queue_delay_ms;
+                let hash_cfg = task_settings.hash_config();
+                if has_extension(&original, VIDEO_EXTENSIONS) {
+                    if let Some(parent) = original.parent() {
+                        let album_meta = read_album_meta(parent);
+                        if compute_video_hash_for_path(&album_meta, &original, &hash_cfg).is_some()
+                            && log_this
+                        {
+                            log::info!("hash worker #{} ok {}", worker_id, original.display());
+                        }
+                    }
+                    let (done, total) = HASH_QUEUE.mark_done(&original);
+                    emit_hash_event("hash-progress", done, total);
+                    if delay_ms > 0 {
+                        std::thread::sleep(Duration::from_millis(delay_ms));
+                    }
+                    continue;
+                }
                 if let Some(cached) = load_album_file_hash(&original) {
                     if cached
                         .version
                         .as_deref()
-                        .map(|v| v == hash_cfg_clone.hash_version)
+                        .map(|v| v == hash_cfg.hash_version)
                         .unwrap_or(false)
-                        && cached.bits.unwrap_or(hash_cfg_clone.bits) == hash_cfg_clone.bits
+                        && cached.bits.unwrap_or(hash_cfg.bits) == hash_cfg.bits
                     {
                         let (done, total) = HASH_QUEUE.mark_done(&original);
                         emit_hash_event("hash-progress", done, total);
@@ -1318,7 +2119,7 @@ fn start_thumb_hash_worker() {
                         continue;
                     }
                 };
-                let thumb_dir = parent.join(".room237-thumb");
+                let thumb_dir = album_thumb_dir(&parent);
                 let thumb = match thumb_path(&original, &thumb_dir) {
                     Ok(t) => t,
                     Err(_) => {
@@ -1344,14 +2145,23 @@ fn start_thumb_hash_worker() {
                     }
                 }
                 let album_meta = read_album_meta(&parent);
-                let hashed_bits =
-                    compute_hash_for_path(&album_meta, &original, &hash_cfg_clone, &settings_clone);
-                if hashed_bits.is_some() {
+                let hashed = compute_hash_signals_for_path(
+                    &album_meta,
+                    &original,
+                    &hash_cfg,
+                    &task_settings,
+                );
+                if let Some((bits, signals)) = &hashed {
+                    note_album_hash(&original, bits, signals, &hash_cfg);
                     THUMB_HASH_FAILED.lock().unwrap().remove(&original);
                     if log_this {
                         log::info!("hash worker #{} ok {}", worker_id, original.display());
                     }
                 } else if let Some(mtime) = thumb_mtime {
+                    service_error(
+                        HASHES_JOB,
+                        format!("{}: hashing failed", original.display()),
+                    );
                     let mut failed = THUMB_HASH_FAILED.lock().unwrap();
                     if retry_on_thumb_change {
                         failed.insert(original.clone(), mtime);
@@ -1367,6 +2177,7 @@ fn start_thumb_hash_worker() {
                 }
                 let (done, total) = HASH_QUEUE.mark_done(&original);
                 emit_hash_event("hash-progress", done, total);
+                slot.finish();
                 if delay_ms > 0 {
                     std::thread::sleep(Duration::from_millis(delay_ms));
                 }
@@ -1375,6 +2186,115 @@ fn start_thumb_hash_worker() {
     }
 }
 
+pub(crate) fn apply_preload_settings(previous: &AdvancedSettings, next: &AdvancedSettings) {
+    scheduler::policy_changed();
+    revalidate_queued_work(previous, next);
+    if THUMB_WORK.state.lock().unwrap().started_workers > 0 {
+        start_thumb_worker();
+    }
+    if META_WORK.state.lock().unwrap().started_workers > 0 {
+        start_meta_worker();
+    }
+    if HASH_QUEUE.state.lock().unwrap().started_workers > 0 {
+        start_thumb_hash_worker();
+    }
+}
+
+fn revalidate_queued_work(previous: &AdvancedSettings, next: &AdvancedSettings) {
+    let dropped = THUMB_WORK.revalidate(|p, task| {
+        if root_for(p).is_none() || !p.exists() {
+            return false;
+        }
+        if let Some(parent) = p.parent() {
+            task.thumb_dir = album_thumb_dir(parent);
+        }
+        true
+    });
+    let dropped = dropped + META_WORK.revalidate(|p, _| root_for(p).is_some() && p.exists());
+    if dropped > 0 {
+        log::info!("settings: dropped {} stale queued tasks", dropped);
+        emit_progress(None);
+    }
+
+    let thumbs_changed = previous.thumb_version() != next.thumb_version()
+        || previous.preview_version() != next.preview_version()
+        || previous.previews.enabled != next.previews.enabled;
+    let (old_hash, new_hash) = (previous.hash_config(), next.hash_config());
+    let hashes_changed = old_hash.hash_version != new_hash.hash_version
+        || old_hash.video_hash_version != new_hash.video_hash_version;
+    if !thumbs_changed && !hashes_changed {
+        return;
+    }
+
+    let albums: Vec<PathBuf> = PRELOADED.lock().unwrap().iter().cloned().collect();
+    log::info!(
+        "settings: revalidating {} albums thumbs={} hashes={}",
+        albums.len(),
+        thumbs_changed,
+        hashes_changed
+    );
+    if hashes_changed {
+        THUMB_HASH_FAILED.lock().unwrap().clear();
+        THUMB_HASH_ALBUMS
+            .lock()
+            .unwrap()
+            .extend(albums.iter().cloned());
+        schedule_thumb_hash_scan();
+    }
+    if thumbs_changed {
+        {
+            let mut preloaded = PRELOADED.lock().unwrap();
+            for album in &albums {
+                preloaded.remove(album);
+            }
+        }
+        for album in &albums {
+            enqueue_preload(album);
+        }
+        if let Some(app) = preload_app() {
+            start_preloader_worker(app);
+        }
+    }
+}
+
+pub(crate) fn focus_album(dir: &Path) {
+    let focus = {
+        let mut focus = VIEWPORT_FOCUS.lock().unwrap();
+        if focus.album.as_deref() == Some(dir) {
+            return;
+        }
+        focus.album = Some(dir.to_path_buf());
+        focus.visible.clear();
+        focus.clone()
+    };
+    THUMB_WORK.reprioritize(&focus);
+    META_WORK.reprioritize(&focus);
+}
+
+#[tauri::command]
+pub fn prioritize_media(paths: Vec<String>) -> Result<usize, String> {
+    let visible: HashSet<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    let mut parents = visible.iter().filter_map(|p| p.parent());
+    let album = parents
+        .next()
+        .filter(|first| parents.all(|p| p == *first))
+        .map(Path::to_path_buf);
+    let focus = {
+        let mut focus = VIEWPORT_FOCUS.lock().unwrap();
+        if album.is_some() {
+            focus.album = album;
+        }
+        focus.visible = visible;
+        focus.clone()
+    };
+    let promoted = THUMB_WORK.reprioritize(&focus) + META_WORK.reprioritize(&focus);
+    if promoted > 0 {
+        log::debug!("prioritized {} visible tasks", promoted);
+        emit_progress(None);
+    }
+    Ok(promoted)
+}
+
 #[tauri::command]
 pub fn is_preloading() -> bool {
     PRELOADER_RUNNING.load(Ordering::SeqCst)
@@ -0,0 +1,133 @@
+use std::{
+    fs,
+    sync::{Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    preload::TaskPriority,
+    settings::{read_settings, PreloadSettings},
+};
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const POLICY_RECHECK: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct SchedulerState {
+    running: usize,
+    last_activity: Option<Instant>,
+    on_battery: bool,
+    power_checked: Option<Instant>,
+}
+
+static SCHEDULER: Lazy<Mutex<SchedulerState>> = Lazy::new(|| Mutex::new(SchedulerState::default()));
+static SCHEDULER_CV: Condvar = Condvar::new();
+
+pub(crate) struct Slot {
+    started: Instant,
+    cpu_limit: u8,
+}
+
+impl Slot {
+    pub(crate) fn finish(self) {
+        let elapsed = self.started.elapsed();
+        let limit = self.cpu_limit.clamp(1, 100) as u32;
+        drop(self);
+        if limit < 100 {
+            thread::sleep(elapsed * (100 - limit) / limit);
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut state = SCHEDULER.lock().unwrap();
+        state.running = state.running.saturating_sub(1);
+        SCHEDULER_CV.notify_all();
+    }
+}
+
+fn read_on_battery() -> bool {
+    let Ok(entries) = fs::read_dir(POWER_SUPPLY_DIR) else {
+        return false;
+    };
+    let mut mains_online = false;
+    let mut discharging = false;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let dir = entry.path();
+        let read = |name: &str| {
+            fs::read_to_string(dir.join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" | "USB" => mains_online |= read("online") == "1",
+            "Battery" => discharging |= read("status") == "Discharging",
+            _ => {}
+        }
+    }
+    discharging && !mains_online
+}
+
+fn on_battery(state: &mut SchedulerState) -> bool {
+    let stale = state
+        .power_checked
+        .map(|t| t.elapsed() >= POWER_CHECK_INTERVAL)
+        .unwrap_or(true);
+    if stale {
+        let on_battery = read_on_battery();
+        if on_battery != state.on_battery {
+            log::info!(
+                "scheduler: power source changed, on battery = {}",
+                on_battery
+            );
+        }
+        state.on_battery = on_battery;
+        state.power_checked = Some(Instant::now());
+    }
+    state.on_battery
+}
+
+fn user_active(state: &SchedulerState, preload: &PreloadSettings) -> bool {
+    state
+        .last_activity
+        .map(|t| t.elapsed() < Duration::from_millis(preload.active_idle_ms))
+        .unwrap_or(false)
+}
+
+fn blocked(state: &mut SchedulerState, preload: &PreloadSettings, priority: TaskPriority) -> bool {
+    if matches!(priority, TaskPriority::Low) && preload.pause_on_battery && on_battery(state) {
+        return true;
+    }
+    user_active(state, preload) && state.running >= preload.active_max_workers as usize
+}
+
+pub(crate) fn acquire(priority: TaskPriority) -> Slot {
+    let mut state = SCHEDULER.lock().unwrap();
+    loop {
+        let preload = read_settings().preload;
+        if !blocked(&mut state, &preload, priority) {
+            state.running += 1;
+            return Slot {
+                started: Instant::now(),
+                cpu_limit: preload.cpu_limit_percent,
+            };
+        }
+        state = SCHEDULER_CV.wait_timeout(state, POLICY_RECHECK).unwrap().0;
+    }
+}
+
+pub(crate) fn policy_changed() {
+    let mut state = SCHEDULER.lock().unwrap();
+    state.power_checked = None;
+    SCHEDULER_CV.notify_all();
+}
+
+#[tauri::command]
+pub fn notify_user_activity() {
+    SCHEDULER.lock().unwrap().last_activity = Some(Instant::now());
+}
@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tauri::{path::BaseDirectory, AppHandle, Manager, Wry};
 
+use crate::{constants::VIDEO_HASH_POINTS, preload::apply_preload_settings};
+
 const SETTINGS_FILE: &str = "settings.json";
 const HASH_SCHEMA_VERSION: &str = "1";
 
@@ -48,12 +50,14 @@ impl HashSize {
     }
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum HashAlgorithm {
     Blockhash,
     Phash,
     Dhash,
+    Wavelet,
+    Combined,
 }
 
 impl Default for HashAlgorithm {
@@ -66,10 +70,26 @@ impl HashAlgorithm {
     pub fn to_img_hash_alg(self) -> HashAlg {
         match self {
             HashAlgorithm::Blockhash => HashAlg::Blockhash,
-            HashAlgorithm::Phash => HashAlg::DoubleGradient,
+            HashAlgorithm::Phash | HashAlgorithm::Wavelet | HashAlgorithm::Combined => {
+                HashAlg::Mean
+            }
             HashAlgorithm::Dhash => HashAlg::Gradient,
         }
     }
+
+    pub fn uses_dct(self) -> bool {
+        matches!(self, HashAlgorithm::Phash | HashAlgorithm::Combined)
+    }
+
+    fn version_tag(self) -> &'static str {
+        match self {
+            HashAlgorithm::Blockhash => "Blockhash",
+            HashAlgorithm::Phash => "PhashDct",
+            HashAlgorithm::Dhash => "Dhash",
+            HashAlgorithm::Wavelet => "Wavelet",
+            HashAlgorithm::Combined => "Combined",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -146,6 +166,20 @@ pub struct DuplicatesSettings {
     pub resize_filter: ResizeFilter,
     pub use_thumbnails_first: bool,
     pub max_files_per_album: u32,
+    #[serde(default = "default_scan_chunk_size")]
+    pub scan_chunk_size: u32,
+    #[serde(default)]
+    pub color_hash: bool,
+    #[serde(default = "default_color_threshold")]
+    pub color_threshold: u32,
+}
+
+fn default_scan_chunk_size() -> u32 {
+    500
+}
+
+fn default_color_threshold() -> u32 {
+    36
 }
 
 impl Default for DuplicatesSettings {
@@ -157,12 +191,15 @@ impl Default for DuplicatesSettings {
             resize_filter: ResizeFilter::default(),
             use_thumbnails_first: true,
             max_files_per_album: 0,
+            scan_chunk_size: default_scan_chunk_size(),
+            color_hash: false,
+            color_threshold: default_color_threshold(),
         }
     }
 }
 
 impl DuplicatesSettings {
-    fn clamp(self) -> Self {
+    pub(crate) fn clamp(self) -> Self {
         Self {
             threshold: self.threshold.min(128),
             hash_size: self.hash_size,
@@ -170,6 +207,9 @@ impl DuplicatesSettings {
             resize_filter: self.resize_filter,
             use_thumbnails_first: self.use_thumbnails_first,
             max_files_per_album: self.max_files_per_album.min(20_000),
+            scan_chunk_size: self.scan_chunk_size.min(20_000),
+            color_hash: self.color_hash,
+            color_threshold: self.color_threshold.min(512),
         }
     }
 
@@ -184,34 +224,117 @@ impl DuplicatesSettings {
 #[serde(rename_all = "camelCase")]
 pub struct ThumbnailSettings {
     pub max_dim: u32,
+    #[serde(default = "default_extra_sizes")]
+    pub extra_sizes: Vec<u32>,
     pub image_webp_quality: u8,
     pub image_webp_compression_level: u8,
     pub video_seek_seconds: f32,
+    #[serde(default = "default_poster_samples")]
+    pub poster_samples: u32,
+    #[serde(default = "default_poster_window_secs")]
+    pub poster_window_secs: f32,
+    #[serde(default = "default_poster_min_luma")]
+    pub poster_min_luma: u8,
     pub lock_poll_ms: u64,
 }
 
+fn default_extra_sizes() -> Vec<u32> {
+    vec![1024, 2048]
+}
+
+fn default_poster_samples() -> u32 {
+    12
+}
+
+fn default_poster_window_secs() -> f32 {
+    5.0
+}
+
+fn default_poster_min_luma() -> u8 {
+    24
+}
+
 impl Default for ThumbnailSettings {
     fn default() -> Self {
         Self {
             max_dim: 450,
+            extra_sizes: default_extra_sizes(),
             image_webp_quality: 75,
             image_webp_compression_level: 3,
             video_seek_seconds: 1.0,
+            poster_samples: default_poster_samples(),
+            poster_window_secs: default_poster_window_secs(),
+            poster_min_luma: default_poster_min_luma(),
             lock_poll_ms: 50,
         }
     }
 }
 
 impl ThumbnailSettings {
-    fn clamp(self) -> Self {
+    pub(crate) fn clamp(self) -> Self {
+        let max_dim = self.max_dim.clamp(128, 2048);
+        let mut extra_sizes: Vec<u32> = self
+            .extra_sizes
+            .into_iter()
+            .map(|d| d.min(4096))
+            .filter(|d| *d > max_dim)
+            .collect();
+        extra_sizes.sort_unstable();
+        extra_sizes.dedup();
+        extra_sizes.truncate(2);
         Self {
-            max_dim: self.max_dim.clamp(128, 2048),
+            max_dim,
+            extra_sizes,
             image_webp_quality: self.image_webp_quality.clamp(30, 95),
             image_webp_compression_level: self.image_webp_compression_level.clamp(0, 9),
             video_seek_seconds: self.video_seek_seconds.clamp(0.0, 30.0),
+            poster_samples: self.poster_samples.clamp(1, 60),
+            poster_window_secs: self.poster_window_secs.clamp(0.5, 60.0),
+            poster_min_luma: self.poster_min_luma.min(128),
             lock_poll_ms: self.lock_poll_ms.clamp(5, 250),
         }
     }
+
+    pub fn size_for(&self, min_dim: u32) -> u32 {
+        std::iter::once(self.max_dim)
+            .chain(self.extra_sizes.iter().copied())
+            .find(|d| *d >= min_dim)
+            .unwrap_or_else(|| self.extra_sizes.last().copied().unwrap_or(self.max_dim))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimatedPreviewSettings {
+    pub enabled: bool,
+    pub duration_secs: f32,
+    pub fps: u8,
+    pub max_dim: u32,
+    pub webp_quality: u8,
+}
+
+impl Default for AnimatedPreviewSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_secs: 3.0,
+            fps: 8,
+            max_dim: 320,
+            webp_quality: 60,
+        }
+    }
+}
+
+impl AnimatedPreviewSettings {
+    fn clamp(self) -> Self {
+        Self {
+            enabled: self.enabled,
+            duration_secs: self.duration_secs.clamp(1.0, 10.0),
+            fps: self.fps.clamp(1, 24),
+            max_dim: self.max_dim.clamp(128, 720),
+            webp_quality: self.webp_quality.clamp(20, 90),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -259,6 +382,30 @@ pub struct PreloadSettings {
     pub thumb_hash_queue_delay_ms: u64,
     pub thumb_hash_only_after_idle: bool,
     pub thumb_hash_retry_on_thumb_change: bool,
+    #[serde(default = "default_pause_on_battery")]
+    pub pause_on_battery: bool,
+    #[serde(default = "default_active_max_workers")]
+    pub active_max_workers: u8,
+    #[serde(default = "default_active_idle_ms")]
+    pub active_idle_ms: u64,
+    #[serde(default = "default_cpu_limit_percent")]
+    pub cpu_limit_percent: u8,
+}
+
+fn default_pause_on_battery() -> bool {
+    false
+}
+
+fn default_active_max_workers() -> u8 {
+    1
+}
+
+fn default_active_idle_ms() -> u64 {
+    1500
+}
+
+fn default_cpu_limit_percent() -> u8 {
+    100
 }
 
 impl Default for PreloadSettings {
@@ -271,6 +418,10 @@ impl Default for PreloadSettings {
             thumb_hash_queue_delay_ms: 10,
             thumb_hash_only_after_idle: true,
             thumb_hash_retry_on_thumb_change: true,
+            pause_on_battery: default_pause_on_battery(),
+            active_max_workers: default_active_max_workers(),
+            active_idle_ms: default_active_idle_ms(),
+            cpu_limit_percent: default_cpu_limit_percent(),
         }
     }
 }
@@ -285,6 +436,10 @@ impl PreloadSettings {
             thumb_hash_queue_delay_ms: self.thumb_hash_queue_delay_ms.clamp(0, 100),
             thumb_hash_only_after_idle: self.thumb_hash_only_after_idle,
             thumb_hash_retry_on_thumb_change: self.thumb_hash_retry_on_thumb_change,
+            pause_on_battery: self.pause_on_battery,
+            active_max_workers: self.active_max_workers.min(32),
+            active_idle_ms: self.active_idle_ms.clamp(250, 10_000),
+            cpu_limit_percent: self.cpu_limit_percent.clamp(10, 100),
         }
     }
 }
@@ -376,6 +531,12 @@ impl PrivacySettings {
     }
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSettings {
+    pub external_cache: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdvancedSettings {
@@ -384,6 +545,8 @@ pub struct AdvancedSettings {
     #[serde(default)]
     pub thumbnails: ThumbnailSettings,
     #[serde(default)]
+    pub previews: AnimatedPreviewSettings,
+    #[serde(default)]
     pub ffmpeg: FfmpegSettings,
     #[serde(default)]
     pub preload: PreloadSettings,
@@ -393,6 +556,8 @@ pub struct AdvancedSettings {
     pub album: AlbumSettings,
     #[serde(default)]
     pub privacy: PrivacySettings,
+    #[serde(default)]
+    pub storage: StorageSettings,
 }
 
 impl Default for AdvancedSettings {
@@ -400,11 +565,13 @@ impl Default for AdvancedSettings {
         Self {
             duplicates: DuplicatesSettings::default(),
             thumbnails: ThumbnailSettings::default(),
+            previews: AnimatedPreviewSettings::default(),
             ffmpeg: FfmpegSettings::default(),
             preload: PreloadSettings::default(),
             metadata: MetadataSettings::default(),
             album: AlbumSettings::default(),
             privacy: PrivacySettings::default(),
+            storage: StorageSettings::default(),
         }
     }
 }
@@ -415,22 +582,44 @@ impl AdvancedSettings {
         Self {
             duplicates: self.duplicates.clamp(),
             thumbnails: self.thumbnails.clamp(),
+            previews: self.previews.clamp(),
             preload: self.preload.clamp(),
             metadata: self.metadata.clamp(ffmpeg.timeout_secs),
             album: self.album.clamp(),
             privacy: self.privacy.clamp(),
+            storage: self.storage,
             ffmpeg,
         }
     }
 
     pub fn thumb_version(&self) -> String {
+        self.sized_thumb_version(self.thumbnails.max_dim)
+    }
+
+    pub fn sized_thumb_version(&self, dim: u32) -> String {
         let mut hasher = Sha256::new();
         hasher.update(format!(
-            "dim:{}|q:{}|c:{}|seek:{:.3}",
-            self.thumbnails.max_dim,
+            "dim:{}|q:{}|c:{}|seek:{:.3}|samples:{}|window:{:.3}|luma:{}",
+            dim,
             self.thumbnails.image_webp_quality,
             self.thumbnails.image_webp_compression_level,
-            self.thumbnails.video_seek_seconds
+            self.thumbnails.video_seek_seconds,
+            self.thumbnails.poster_samples,
+            self.thumbnails.poster_window_secs,
+            self.thumbnails.poster_min_luma
+        ));
+        let digest = hasher.finalize();
+        hex::encode(digest)[0..16].to_string()
+    }
+
+    pub fn preview_version(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!(
+            "anim|dur:{:.2}|fps:{}|dim:{}|q:{}",
+            self.previews.duration_secs,
+            self.previews.fps,
+            self.previews.max_dim,
+            self.previews.webp_quality
         ));
         let digest = hasher.finalize();
         hex::encode(digest)[0..16].to_string()
@@ -448,26 +637,49 @@ impl AdvancedSettings {
         hasher.update(HASH_SCHEMA_VERSION.as_bytes());
         hasher.update(
             format!(
-                "alg:{:?}|size:{}x{}|filter:{:?}|thumb_first:{}|thumb_version:{}",
-                self.duplicates.hash_alg,
+                "alg:{}|size:{}x{}|filter:{:?}|thumb_first:{}|thumb_version:{}{}",
+                self.duplicates.hash_alg.version_tag(),
                 size.0,
                 size.1,
                 self.duplicates.resize_filter,
                 self.duplicates.use_thumbnails_first,
-                thumb_version
+                thumb_version,
+                if self.duplicates.color_hash {
+                    "|color"
+                } else {
+                    ""
+                }
             )
             .as_bytes(),
         );
         let hash_version = hex::encode(hasher.finalize());
 
+        let mut hasher = Sha256::new();
+        hasher.update(HASH_SCHEMA_VERSION.as_bytes());
+        hasher.update(
+            format!(
+                "video|alg:{}|size:{}x{}|filter:{:?}|points:{:?}",
+                self.duplicates.hash_alg.version_tag(),
+                size.0,
+                size.1,
+                self.duplicates.resize_filter,
+                VIDEO_HASH_POINTS
+            )
+            .as_bytes(),
+        );
+        let video_hash_version = hex::encode(hasher.finalize());
+
         HashConfig {
             size,
             bits,
             alg: self.duplicates.hash_alg,
+            color_hash: self.duplicates.color_hash,
+            color_threshold: self.duplicates.color_threshold,
             resize_filter: self.duplicates.resize_filter,
             use_thumbnails_first: self.duplicates.use_thumbnails_first,
             thumb_version,
             hash_version,
+            video_hash_version,
             user_threshold: self.duplicates.threshold,
             effective_threshold: self.duplicates.effective_threshold(),
             thumb_settings: self.thumbnails.clone(),
@@ -480,10 +692,13 @@ pub struct HashConfig {
     pub size: (u32, u32),
     pub bits: u32,
     pub alg: HashAlgorithm,
+    pub color_hash: bool,
+    pub color_threshold: u32,
     pub resize_filter: ResizeFilter,
     pub use_thumbnails_first: bool,
     pub thumb_version: String,
     pub hash_version: String,
+    pub video_hash_version: String,
     pub user_threshold: u32,
     pub effective_threshold: u32,
     pub thumb_settings: ThumbnailSettings,
@@ -563,10 +778,16 @@ pub fn update_settings(
     state: tauri::State<SettingsState>,
     settings: AdvancedSettings,
 ) -> Result<AdvancedSettings, String> {
-    state.update(settings)
+    let previous = state.get();
+    let updated = state.update(settings)?;
+    apply_preload_settings(&previous, &updated);
+    Ok(updated)
 }
 
 #[tauri::command]
 pub fn reset_settings(state: tauri::State<SettingsState>) -> Result<AdvancedSettings, String> {
-    state.reset()
+    let previous = state.get();
+    let updated = state.reset()?;
+    apply_preload_settings(&previous, &updated);
+    Ok(updated)
 }
@@ -1,19 +1,65 @@
 use std::{
+    collections::HashSet,
     fs,
-    fs::OpenOptions,
-    io,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Condvar, Mutex,
+    },
     thread,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
-    constants::{IMAGE_EXTENSIONS, VIDEO_EXTENSIONS},
-    metadata::{load_thumb_version, write_thumb_version},
+    artifacts::{ensure_writable, media_thumb_dir},
+    constants::{
+        ANIMATED_PREVIEW_EXTENSIONS, IMAGE_EXTENSIONS, VIDEO_EXTENSIONS, VIEW_TRANSCODE_EXTENSIONS,
+    },
+    library::settings_for,
+    metadata::{
+        load_preview_version, load_sized_thumb_version, load_thumb_version, write_preview_version,
+        write_sized_thumb_version, write_thumb_version,
+    },
+    preload::root_for,
     settings::{read_settings, AdvancedSettings},
-    util::{apply_ffmpeg_tuning, ffmpeg_timeout, newer_than, wait_with_timeout},
+    util::{
+        apply_ffmpeg_tuning, ffmpeg_timeout, has_extension, move_file, newer_than, process_alive,
+        wait_with_timeout,
+    },
 };
 use ffmpeg_sidecar::command::FfmpegCommand;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime;
+
+const LOCK_EXPIRY_GRACE: Duration = Duration::from_secs(30);
+
+static HELD_LOCKS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static HELD_LOCKS_CV: Condvar = Condvar::new();
+static LOCK_TOMBSTONES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize)]
+struct LockOwner {
+    pid: u32,
+    at: u64,
+}
+
+struct ThumbLock {
+    path: PathBuf,
+    created: bool,
+}
+
+impl Drop for ThumbLock {
+    fn drop(&mut self) {
+        if self.created {
+            let _ = fs::remove_file(&self.path);
+        }
+        HELD_LOCKS.lock().unwrap().remove(&self.path);
+        HELD_LOCKS_CV.notify_all();
+    }
+}
 
 pub fn thumb_path(original: &Path, thumb_dir: &Path) -> Result<PathBuf, String> {
     Ok(thumb_dir.join(format!(
@@ -25,13 +71,205 @@ pub fn thumb_path(original: &Path, thumb_dir: &Path) -> Result<PathBuf, String>
     )))
 }
 
+pub fn sized_thumb_path(original: &Path, thumb_dir: &Path, dim: u32) -> Result<PathBuf, String> {
+    Ok(thumb_dir.join(format!(
+        "{}.{}.webp",
+        original
+            .file_name()
+            .ok_or("missing filename")?
+            .to_string_lossy(),
+        dim
+    )))
+}
+
+pub fn view_path(original: &Path, thumb_dir: &Path) -> Result<PathBuf, String> {
+    Ok(thumb_dir.join(format!(
+        "{}.view.jpg",
+        original
+            .file_name()
+            .ok_or("missing filename")?
+            .to_string_lossy()
+    )))
+}
+
+pub fn media_thumb_path(path: &Path) -> Option<String> {
+    let thumb_dir = media_thumb_dir(path)?;
+    thumb_path(path, &thumb_dir)
+        .ok()
+        .map(|t| t.to_string_lossy().into_owned())
+}
+
+pub fn needs_view_transcode(path: &Path) -> bool {
+    has_extension(path, VIEW_TRANSCODE_EXTENSIONS)
+}
+
+pub fn media_view_path(path: &Path) -> Option<String> {
+    if !needs_view_transcode(path) {
+        return None;
+    }
+    let thumb_dir = media_thumb_dir(path)?;
+    view_path(path, &thumb_dir)
+        .ok()
+        .map(|v| v.to_string_lossy().into_owned())
+}
+
+pub fn preview_path(original: &Path, thumb_dir: &Path) -> Result<PathBuf, String> {
+    Ok(thumb_dir.join(format!(
+        "{}.anim.webp",
+        original
+            .file_name()
+            .ok_or("missing filename")?
+            .to_string_lossy()
+    )))
+}
+
+fn is_animated_webp(path: &Path) -> bool {
+    let mut header = [0_u8; 21];
+    let read = File::open(path).and_then(|mut f| f.read_exact(&mut header));
+    read.is_ok()
+        && &header[..4] == b"RIFF"
+        && &header[8..16] == b"WEBPVP8X"
+        && header[20] & 0x02 != 0
+}
+
+pub fn supports_animated_preview(path: &Path) -> bool {
+    if has_extension(path, &["webp"]) {
+        return is_animated_webp(path);
+    }
+    has_extension(path, ANIMATED_PREVIEW_EXTENSIONS) || has_extension(path, VIDEO_EXTENSIONS)
+}
+
+pub fn media_preview_path(path: &Path) -> Option<String> {
+    if !read_settings().previews.enabled || !supports_animated_preview(path) {
+        return None;
+    }
+    let thumb_dir = media_thumb_dir(path)?;
+    let preview = preview_path(path, &thumb_dir).ok()?;
+    if !preview.exists() {
+        return None;
+    }
+    Some(preview.to_string_lossy().into_owned())
+}
+
 fn thumb_lock_path(thumb: &Path) -> PathBuf {
     thumb.with_extension("lock")
 }
 
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn lock_expired(lock_file: &Path, contents: &str) -> bool {
+    let expiry = ffmpeg_timeout() + LOCK_EXPIRY_GRACE;
+    match serde_json::from_str::<LockOwner>(contents) {
+        Ok(owner) => {
+            owner.pid == std::process::id()
+                || !process_alive(owner.pid)
+                || now_ms().saturating_sub(owner.at) > expiry.as_millis() as u64
+        }
+        Err(_) => fs::metadata(lock_file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .map(|age| age > expiry)
+            .unwrap_or(false),
+    }
+}
+
+fn remove_if_expired(lock_file: &Path) -> bool {
+    let Ok(contents) = fs::read_to_string(lock_file) else {
+        return false;
+    };
+    if !lock_expired(lock_file, &contents) {
+        return false;
+    }
+    let tombstone = lock_file.with_extension(format!(
+        "{}-{}.lock",
+        std::process::id(),
+        LOCK_TOMBSTONES.fetch_add(1, Ordering::Relaxed)
+    ));
+    if fs::rename(lock_file, &tombstone).is_err() {
+        return false;
+    }
+    if fs::read_to_string(&tombstone).ok().as_deref() != Some(contents.as_str()) {
+        if let Err(e) = fs::hard_link(&tombstone, lock_file) {
+            log::warn!("failed to restore lock {}: {}", lock_file.display(), e);
+        }
+        let _ = fs::remove_file(&tombstone);
+        return false;
+    }
+    log::warn!("removing stale lock {} ({})", lock_file.display(), contents);
+    let _ = fs::remove_file(&tombstone);
+    true
+}
+
+pub(crate) fn lock_is_stale(lock_file: &Path) -> bool {
+    if HELD_LOCKS.lock().unwrap().contains(lock_file) {
+        return false;
+    }
+    fs::read_to_string(lock_file)
+        .map(|contents| lock_expired(lock_file, &contents))
+        .unwrap_or(false)
+}
+
+fn acquire_lock<F>(lock_file: &Path, poll_ms: u64, ready: F) -> Result<Option<ThumbLock>, String>
+where
+    F: Fn() -> bool,
+{
+    let poll = Duration::from_millis(poll_ms);
+    {
+        let mut held = HELD_LOCKS.lock().unwrap();
+        while held.contains(lock_file) {
+            if ready() {
+                return Ok(None);
+            }
+            held = HELD_LOCKS_CV.wait_timeout(held, poll).unwrap().0;
+        }
+        held.insert(lock_file.to_path_buf());
+    }
+    let mut lock = ThumbLock {
+        path: lock_file.to_path_buf(),
+        created: false,
+    };
+
+    loop {
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(lock_file)
+        {
+            Ok(mut f) => {
+                lock.created = true;
+                let owner = LockOwner {
+                    pid: std::process::id(),
+                    at: now_ms(),
+                };
+                let contents = serde_json::to_string(&owner).map_err(|e| e.to_string())?;
+                f.write_all(contents.as_bytes())
+                    .map_err(|e| e.to_string())?;
+                return Ok(Some(lock));
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if ready() {
+                    return Ok(None);
+                }
+                if remove_if_expired(lock_file) {
+                    continue;
+                }
+                thread::sleep(poll);
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
 fn generate_image_thumbnail(
     input: &Path,
     output: &Path,
+    dim: u32,
     settings: &AdvancedSettings,
 ) -> Result<bool, String> {
     let mut cmd = FfmpegCommand::new();
@@ -42,7 +280,7 @@ fn generate_image_thumbnail(
         .arg("-vf")
         .arg(format!(
             "scale=min(iw\\,{d}):min(ih\\,{d}):force_original_aspect_ratio=decrease",
-            d = settings.thumbnails.max_dim
+            d = dim
         ))
         .arg("-c:v")
         .arg("libwebp")
@@ -76,27 +314,25 @@ fn generate_image_thumbnail(
     }
 }
 
-fn generate_video_thumbnail(
+fn extract_video_frame(
     input: &Path,
     output: &Path,
-    settings: &AdvancedSettings,
+    dim: u32,
+    seconds: f32,
 ) -> Result<bool, String> {
     let mut cmd = FfmpegCommand::new();
     apply_ffmpeg_tuning(&mut cmd, true);
     let mut child = cmd
         .input(input.to_string_lossy())
         .arg("-ss")
-        .arg(format!(
-            "{:.3}",
-            settings.thumbnails.video_seek_seconds.max(0.0)
-        ))
+        .arg(format!("{:.3}", seconds.max(0.0)))
         .arg("-y")
         .arg("-frames:v")
         .arg("1")
         .arg("-vf")
         .arg(format!(
             "scale=min(iw\\,{d}):min(ih\\,{d}):force_original_aspect_ratio=decrease",
-            d = settings.thumbnails.max_dim
+            d = dim
         ))
         .output(output.to_string_lossy())
         .spawn()
@@ -124,8 +360,93 @@ fn generate_video_thumbnail(
     }
 }
 
+fn sample_video_poster(
+    input: &Path,
+    output: &Path,
+    dim: u32,
+    start: f32,
+    window: f32,
+    settings: &AdvancedSettings,
+) -> Result<bool, String> {
+    let samples = settings.thumbnails.poster_samples;
+    let mut cmd = FfmpegCommand::new();
+    apply_ffmpeg_tuning(&mut cmd, true);
+    cmd.arg("-hide_banner").arg("-loglevel").arg("error");
+    cmd.arg("-ss")
+        .arg(format!("{:.3}", start))
+        .arg("-t")
+        .arg(format!("{:.3}", window));
+    let mut child = cmd
+        .input(input.to_string_lossy())
+        .arg("-y")
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "fps={rate:.3},signalstats,\
+             metadata=select:key=lavfi.signalstats.YAVG:value={luma}:function=greater,\
+             thumbnail={samples},\
+             scale=min(iw\\,{d}):min(ih\\,{d}):force_original_aspect_ratio=decrease",
+            rate = samples as f32 / window,
+            luma = settings.thumbnails.poster_min_luma,
+            samples = samples,
+            d = dim
+        ))
+        .arg("-frames:v")
+        .arg("1")
+        .output(output.to_string_lossy())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let status = wait_with_timeout(&mut child, ffmpeg_timeout())?;
+    let written = fs::metadata(output).map(|m| m.len() > 0).unwrap_or(false);
+    Ok(status.success() && written)
+}
+
+fn poster_window(seek: f32, window: f32, duration: Option<f64>) -> (f32, f32) {
+    match duration {
+        Some(d) if d > 0.0 => {
+            let d = d as f32;
+            let start = if seek < d * 0.5 { seek } else { 0.0 };
+            (start, window.min(d - start))
+        }
+        _ => (seek, window),
+    }
+}
+
+fn generate_video_thumbnail(
+    input: &Path,
+    output: &Path,
+    dim: u32,
+    settings: &AdvancedSettings,
+) -> Result<bool, String> {
+    if let Some(seconds) = load_poster_frame(input) {
+        return extract_video_frame(input, output, dim, seconds);
+    }
+
+    let (start, window) = poster_window(
+        settings.thumbnails.video_seek_seconds.max(0.0),
+        settings.thumbnails.poster_window_secs,
+        probe_duration(input),
+    );
+    if settings.thumbnails.poster_samples > 1 && window > 0.0 {
+        let _ = fs::remove_file(output);
+        match sample_video_poster(input, output, dim, start, window, settings) {
+            Ok(true) => return Ok(true),
+            Ok(false) => log::debug!("no poster candidate in {}", input.display()),
+            Err(e) => log::warn!("poster sampling failed {}: {}", input.display(), e),
+        }
+    }
+
+    if extract_video_frame(input, output, dim, start)? {
+        return Ok(true);
+    }
+    if start > 0.0 {
+        return extract_video_frame(input, output, dim, 0.0);
+    }
+    Ok(false)
+}
+
 pub fn ensure_thumb(path: &Path, thumb_dir: &Path) -> Result<PathBuf, String> {
-    let settings = read_settings();
+    let settings = settings_for(path);
     ensure_thumb_with_settings(path, thumb_dir, &settings)
 }
 
@@ -149,43 +470,406 @@ pub fn ensure_thumb_with_settings(
     let _ = fs::create_dir_all(thumb_dir);
 
     let lock_file = thumb_lock_path(&thumb);
-    loop {
-        match OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&lock_file)
-        {
-            Ok(f) => {
-                drop(f);
-                break;
-            }
-            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
-                if thumb.exists()
-                    && newer_than(&thumb, path).unwrap_or(false)
-                    && existing_version.as_deref() == Some(&thumb_version)
-                {
-                    return Ok(thumb);
-                }
-                thread::sleep(Duration::from_millis(settings.thumbnails.lock_poll_ms));
-            }
-            Err(e) => return Err(e.to_string()),
-        }
+    let Some(lock) = acquire_lock(&lock_file, settings.thumbnails.lock_poll_ms, || {
+        thumb.exists()
+            && newer_than(&thumb, path).unwrap_or(false)
+            && existing_version.as_deref() == Some(&thumb_version)
+    })?
+    else {
+        return Ok(thumb);
+    };
+
+    let res = render_thumb(path, &thumb, settings.thumbnails.max_dim, settings);
+
+    drop(lock);
+    res?;
+    let _ = write_thumb_version(path, &thumb_version);
+    Ok(thumb)
+}
+
+pub fn ensure_sized_thumb(
+    path: &Path,
+    thumb_dir: &Path,
+    dim: u32,
+    settings: &AdvancedSettings,
+) -> Result<PathBuf, String> {
+    if dim <= settings.thumbnails.max_dim {
+        return ensure_thumb_with_settings(path, thumb_dir, settings);
     }
+    let thumb = sized_thumb_path(path, thumb_dir, dim)?;
+    let thumb_version = settings.sized_thumb_version(dim);
+    let existing_version = load_sized_thumb_version(path, dim);
+    let is_fresh = || {
+        thumb.exists()
+            && newer_than(&thumb, path).unwrap_or(false)
+            && existing_version.as_deref() == Some(&thumb_version)
+    };
+    if is_fresh() {
+        return Ok(thumb);
+    }
+
+    log::info!(
+        "generating {}px thumb {}→{}",
+        dim,
+        path.display(),
+        thumb.display()
+    );
+    let _ = fs::create_dir_all(thumb_dir);
+    let lock_file = thumb_lock_path(&thumb);
+    let Some(lock) = acquire_lock(&lock_file, settings.thumbnails.lock_poll_ms, is_fresh)? else {
+        return Ok(thumb);
+    };
+
+    let res = render_thumb(path, &thumb, dim, settings);
+    drop(lock);
+    if !matches!(res, Ok(true)) {
+        let _ = fs::remove_file(&thumb);
+    }
+    if !res? {
+        return Err(format!("failed to generate {}px thumb", dim));
+    }
+    let _ = write_sized_thumb_version(path, dim, &thumb_version);
+    Ok(thumb)
+}
 
-    let res = match path
+fn render_thumb(
+    path: &Path,
+    thumb: &Path,
+    dim: u32,
+    settings: &AdvancedSettings,
+) -> Result<bool, String> {
+    match path
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("")
         .to_ascii_lowercase()
         .as_str()
     {
-        ext if IMAGE_EXTENSIONS.contains(&ext) => generate_image_thumbnail(path, &thumb, settings),
-        ext if VIDEO_EXTENSIONS.contains(&ext) => generate_video_thumbnail(path, &thumb, settings),
+        ext if IMAGE_EXTENSIONS.contains(&ext) => {
+            generate_image_thumbnail(path, thumb, dim, settings)
+        }
+        ext if VIDEO_EXTENSIONS.contains(&ext) => {
+            generate_video_thumbnail(path, thumb, dim, settings)
+        }
         _ => Ok(false),
+    }
+}
+
+fn is_thumb_artifact_suffix(suffix: &str) -> bool {
+    if suffix == "webp" || suffix == "view.jpg" || suffix == "anim.webp" {
+        return true;
+    }
+    suffix
+        .strip_suffix(".webp")
+        .map(|dim| !dim.is_empty() && dim.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
+pub(crate) fn thumb_artifact_sources(file_name: &str) -> Vec<&str> {
+    file_name
+        .match_indices('.')
+        .filter(|(i, _)| *i > 0 && is_thumb_artifact_suffix(&file_name[i + 1..]))
+        .map(|(i, _)| &file_name[..i])
+        .collect()
+}
+
+pub(crate) fn is_truncated_artifact(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
     };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut head = [0_u8; 12];
+    if len < head.len() as u64 || file.read_exact(&mut head).is_err() {
+        return true;
+    }
+    if path.extension().and_then(|e| e.to_str()) == Some("jpg") {
+        let mut tail = [0_u8; 2];
+        let tail_ok = file.seek(SeekFrom::End(-2)).is_ok() && file.read_exact(&mut tail).is_ok();
+        return !(head.starts_with(&[0xFF, 0xD8]) && tail_ok && tail == [0xFF, 0xD9]);
+    }
+    if &head[..4] != b"RIFF" || &head[8..12] != b"WEBP" {
+        return true;
+    }
+    let riff_len = u32::from_le_bytes([head[4], head[5], head[6], head[7]]) as u64;
+    len < riff_len + 8
+}
 
-    let _ = fs::remove_file(&lock_file);
-    res?;
-    let _ = write_thumb_version(path, &thumb_version);
-    Ok(thumb)
+pub fn rename_thumb_artifacts(
+    src_thumb_dir: &Path,
+    tgt_thumb_dir: &Path,
+    source_name: &str,
+    target_name: &str,
+) {
+    let Ok(entries) = fs::read_dir(src_thumb_dir) else {
+        return;
+    };
+    let prefix = format!("{}.", source_name);
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(suffix) = file_name
+            .to_str()
+            .and_then(|n| n.strip_prefix(prefix.as_str()))
+        else {
+            continue;
+        };
+        if !is_thumb_artifact_suffix(suffix) {
+            continue;
+        }
+        let _ = fs::create_dir_all(tgt_thumb_dir);
+        let target = tgt_thumb_dir.join(format!("{}.{}", target_name, suffix));
+        let _ = fs::remove_file(&target);
+        let _ = move_file(&entry.path(), &target);
+    }
+}
+
+pub fn remove_thumb_artifacts(thumb_dir: &Path, name: &str) {
+    let Ok(entries) = fs::read_dir(thumb_dir) else {
+        return;
+    };
+    let prefix = format!("{}.", name);
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let is_artifact = file_name
+            .to_str()
+            .and_then(|n| n.strip_prefix(prefix.as_str()))
+            .map(is_thumb_artifact_suffix)
+            .unwrap_or(false);
+        if is_artifact {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_thumb(path: String, min_dim: u32) -> Result<String, String> {
+    async_runtime::spawn_blocking(move || {
+        let p = PathBuf::from(&path);
+        if !p.is_file() {
+            return Err(format!("{} does not exist", path));
+        }
+        let thumb_dir = media_thumb_dir(&p).ok_or("Invalid path")?;
+        let settings = settings_for(&p);
+        let dim = settings.thumbnails.size_for(min_dim);
+        ensure_sized_thumb(&p, &thumb_dir, dim, &settings).map(|t| t.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn set_poster_frame(path: String, seconds: Option<f32>) -> Result<String, String> {
+    async_runtime::spawn_blocking(move || {
+        let p = PathBuf::from(&path)
+            .canonicalize()
+            .map_err(|_| format!("{} does not exist", path))?;
+        if !p.is_file() {
+            return Err(format!("{} does not exist", path));
+        }
+        if root_for(&p).is_none() {
+            return Err(format!("{} is not inside an open library", path));
+        }
+        ensure_writable(&p)?;
+        if !has_extension(&p, VIDEO_EXTENSIONS) {
+            return Err(format!("{} is not a video", path));
+        }
+        let seconds = seconds.map(|s| s.max(0.0));
+        write_poster_frame(&p, seconds)?;
+
+        let thumb_dir = media_thumb_dir(&p).ok_or("Invalid path")?;
+        let settings = settings_for(&p);
+        let _ = fs::remove_file(thumb_path(&p, &thumb_dir)?);
+        for dim in &settings.thumbnails.extra_sizes {
+            let _ = fs::remove_file(sized_thumb_path(&p, &thumb_dir, *dim)?);
+        }
+        ensure_thumb_with_settings(&p, &thumb_dir, &settings)
+            .map(|t| t.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn generate_view_transcode(input: &Path, output: &Path) -> Result<bool, String> {
+    log::info!("view transcode {}→{}", input.display(), output.display());
+    let mut cmd = FfmpegCommand::new();
+    apply_ffmpeg_tuning(&mut cmd, false);
+    cmd.arg("-hide_banner").arg("-loglevel").arg("error");
+    let mut child = cmd
+        .input(input.to_string_lossy())
+        .arg("-y")
+        .arg("-map_metadata")
+        .arg("0")
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-q:v")
+        .arg("2")
+        .output(output.to_string_lossy())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    match wait_with_timeout(&mut child, ffmpeg_timeout()) {
+        Ok(status) => {
+            if !status.success() {
+                log::error!(
+                    "view transcode failed {}→{}",
+                    input.display(),
+                    output.display()
+                );
+            }
+            Ok(status.success())
+        }
+        Err(e) => {
+            log::error!(
+                "view transcode timeout {}→{}: {}",
+                input.display(),
+                output.display(),
+                e
+            );
+            Err(e)
+        }
+    }
+}
+
+pub fn ensure_view_transcode(
+    path: &Path,
+    thumb_dir: &Path,
+    settings: &AdvancedSettings,
+) -> Result<PathBuf, String> {
+    let view = view_path(path, thumb_dir)?;
+    if view.exists() && newer_than(&view, path).unwrap_or(false) {
+        return Ok(view);
+    }
+
+    let _ = fs::create_dir_all(thumb_dir);
+    let lock_file = view.with_extension("lock");
+    let Some(lock) = acquire_lock(&lock_file, settings.thumbnails.lock_poll_ms, || {
+        view.exists() && newer_than(&view, path).unwrap_or(false)
+    })?
+    else {
+        return Ok(view);
+    };
+
+    let res = generate_view_transcode(path, &view);
+    drop(lock);
+    if !res? {
+        let _ = fs::remove_file(&view);
+        return Err(format!("failed to transcode {}", path.display()));
+    }
+    Ok(view)
+}
+
+fn generate_animated_preview(
+    input: &Path,
+    output: &Path,
+    settings: &AdvancedSettings,
+) -> Result<bool, String> {
+    let previews = &settings.previews;
+    let is_video = has_extension(input, VIDEO_EXTENSIONS);
+    let mut cmd = FfmpegCommand::new();
+    apply_ffmpeg_tuning(&mut cmd, is_video);
+    cmd.arg("-hide_banner").arg("-loglevel").arg("error");
+    if is_video {
+        cmd.arg("-ss").arg(format!(
+            "{:.3}",
+            settings.thumbnails.video_seek_seconds.max(0.0)
+        ));
+    }
+    let mut child = cmd
+        .input(input.to_string_lossy())
+        .arg("-y")
+        .arg("-t")
+        .arg(format!("{:.2}", previews.duration_secs))
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "fps={fps},scale=min(iw\\,{d}):min(ih\\,{d}):force_original_aspect_ratio=decrease",
+            fps = previews.fps,
+            d = previews.max_dim
+        ))
+        .arg("-c:v")
+        .arg("libwebp")
+        .arg("-loop")
+        .arg("0")
+        .arg("-q:v")
+        .arg(previews.webp_quality.to_string())
+        .output(output.to_string_lossy())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let timeout = ffmpeg_timeout() + Duration::from_secs_f32(previews.duration_secs * 2.0);
+    match wait_with_timeout(&mut child, timeout) {
+        Ok(status) => {
+            if !status.success() {
+                log::error!(
+                    "animated preview failed {}→{}",
+                    input.display(),
+                    output.display()
+                );
+            }
+            Ok(status.success())
+        }
+        Err(e) => {
+            log::error!(
+                "animated preview timeout {}→{}: {}",
+                input.display(),
+                output.display(),
+                e
+            );
+            Err(e)
+        }
+    }
+}
+
+pub fn ensure_animated_preview(
+    path: &Path,
+    thumb_dir: &Path,
+    settings: &AdvancedSettings,
+) -> Result<PathBuf, String> {
+    let preview = preview_path(path, thumb_dir)?;
+    let preview_version = settings.preview_version();
+    let existing_version = load_preview_version(path);
+    let is_fresh = || {
+        preview.exists()
+            && newer_than(&preview, path).unwrap_or(false)
+            && existing_version.as_deref() == Some(&preview_version)
+    };
+    if is_fresh() {
+        return Ok(preview);
+    }
+
+    let _ = fs::create_dir_all(thumb_dir);
+    let lock_file = thumb_dir.join(format!(
+        "{}.anim.lock",
+        path.file_name()
+            .ok_or("missing filename")?
+            .to_string_lossy()
+    ));
+    let Some(lock) = acquire_lock(&lock_file, settings.thumbnails.lock_poll_ms, is_fresh)? else {
+        return Ok(preview);
+    };
+
+    let res = generate_animated_preview(path, &preview, settings);
+    drop(lock);
+    if !res? {
+        let _ = fs::remove_file(&preview);
+        return Err(format!("failed to generate preview for {}", path.display()));
+    }
+    let _ = write_preview_version(path, &preview_version);
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn get_animated_preview(path: String) -> Result<Option<String>, String> {
+    async_runtime::spawn_blocking(move || {
+        let p = PathBuf::from(&path);
+        let settings = settings_for(&p);
+        if !settings.previews.enabled || !supports_animated_preview(&p) {
+            return Ok(None);
+        }
+        if !p.is_file() {
+            return Err(format!("{} does not exist", path));
+        }
+        let thumb_dir = media_thumb_dir(&p).ok_or("Invalid path")?;
+        ensure_animated_preview(&p, &thumb_dir, &settings)
+            .map(|t| Some(t.to_string_lossy().into_owned()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
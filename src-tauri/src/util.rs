@@ -1,5 +1,5 @@
 use std::{
-    io,
+    fs, io,
     path::Path,
     process::{Child, Command, ExitStatus, Output, Stdio},
     sync::Mutex,
@@ -13,16 +13,68 @@ use std::os::windows::process::CommandExt;
 use ffmpeg_sidecar::{child::FfmpegChild, command::FfmpegCommand};
 #[cfg(target_family = "unix")]
 use libc;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 
 use crate::settings::read_settings;
 
 pub static STORE_WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static FFMPEG_VERSION: OnceCell<Option<String>> = OnceCell::new();
+
+pub fn ffmpeg_version() -> Option<String> {
+    FFMPEG_VERSION
+        .get_or_init(|| ffmpeg_sidecar::version::ffmpeg_version().ok())
+        .clone()
+}
 
 pub fn newer_than(a: &Path, b: &Path) -> io::Result<bool> {
     Ok(a.metadata()?.modified()? >= b.metadata()?.modified()?)
 }
 
+fn is_cross_device(e: &io::Error) -> bool {
+    #[cfg(target_family = "unix")]
+    {
+        e.raw_os_error() == Some(libc::EXDEV)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        e.raw_os_error() == Some(windows::Win32::Foundation::ERROR_NOT_SAME_DEVICE.0 as i32)
+    }
+    #[cfg(not(any(target_family = "unix", target_os = "windows")))]
+    {
+        let _ = e;
+        false
+    }
+}
+
+pub fn move_file(src: &Path, dst: &Path) -> io::Result<()> {
+    if dst.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dst.display()),
+        ));
+    }
+    match fs::rename(src, dst) {
+        Ok(()) => return Ok(()),
+        Err(e) if !is_cross_device(&e) => return Err(e),
+        Err(_) => {}
+    }
+
+    let metadata = src.metadata()?;
+    let mut input = fs::File::open(src)?;
+    let mut output = fs::File::options().write(true).create_new(true).open(dst)?;
+    if let Err(e) = io::copy(&mut input, &mut output) {
+        drop(output);
+        let _ = fs::remove_file(dst);
+        return Err(e);
+    }
+    let _ = output.set_permissions(metadata.permissions());
+    if let Ok(modified) = metadata.modified() {
+        let _ = output.set_modified(modified);
+    }
+    drop(output);
+    fs::remove_file(src)
+}
+
 pub fn has_extension(path: &Path, exts: &[&str]) -> bool {
     path.extension()
         .and_then(|s| s.to_str())
@@ -88,6 +140,20 @@ pub fn set_low_priority_current_thread() {
     }
 }
 
+pub fn process_alive(pid: u32) -> bool {
+    #[cfg(target_family = "unix")]
+    unsafe {
+        libc::kill(pid as libc::pid_t, 0) == 0
+            || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        // ? No cheap liveness probe; rely on the lock age instead.
+        let _ = pid;
+        true
+    }
+}
+
 pub trait WaitableChild {
     fn try_wait(&mut self) -> io::Result<Option<ExitStatus>>;
     fn kill(&mut self) -> io::Result<()>;
@@ -221,38 +287,3 @@ pub fn get_file_manager_name() -> Result<String, String> {
         return Ok(detect_linux_file_manager());
     }
 }
-
-pub fn heic_to_jpeg(src: &Path, dst: &Path) -> Result<(), String> {
-    if dst.exists() && newer_than(dst, src).unwrap_or(false) {
-        return Ok(());
-    }
-    log::info!("heic→jpeg {}→{}", src.display(), dst.display());
-    let mut cmd = FfmpegCommand::new();
-    apply_ffmpeg_tuning(&mut cmd, false);
-    cmd.arg("-hide_banner").arg("-loglevel").arg("error");
-    let mut child = cmd
-        .input(src.to_string_lossy())
-        .arg("-y")
-        .arg("-map_metadata")
-        .arg("0")
-        .output(dst.to_string_lossy())
-        .spawn()
-        .map_err(|e| e.to_string())?;
-    match wait_with_timeout(&mut child, ffmpeg_timeout()) {
-        Ok(status) => {
-            if !status.success() {
-                log::error!("heic→jpeg failed {}→{}", src.display(), dst.display());
-            }
-            Ok(())
-        }
-        Err(e) => {
-            log::error!(
-                "heic→jpeg timeout {}→{}: {}",
-                src.display(),
-                dst.display(),
-                e
-            );
-            Err(e)
-        }
-    }
-}
@@ -276,8 +276,10 @@ export function AdvancedSettingsPopover({
                 {
                   {
                     blockhash: "Blockhash",
-                    phash: "Perceptual",
+                    phash: "Perceptual (DCT)",
                     dhash: "dHash",
+                    wavelet: "Wavelet",
+                    combined: "Combined",
                   }[settings.duplicates.hashAlg]
                 }
               </SelectTrigger>
@@ -298,10 +300,10 @@ export function AdvancedSettingsPopover({
                 </SelectItem>
                 <SelectItem value="phash">
                   <div className="flex max-w-64 flex-col items-start gap-1">
-                    <div>Perceptual</div>
+                    <div>Perceptual (DCT)</div>
                     <div className="text-muted-foreground text-xs">
-                      Slower. Focuses on overall image structure. Often more
-                      accurate for general images.
+                      Slower. Compares low-frequency DCT coefficients. Robust to
+                      recompression and resizing.
                     </div>
                   </div>
                 </SelectItem>
@@ -314,6 +316,24 @@ export function AdvancedSettingsPopover({
                     </div>
                   </div>
                 </SelectItem>
+                <SelectItem value="wavelet">
+                  <div className="flex max-w-64 flex-col items-start gap-1">
+                    <div>Wavelet</div>
+                    <div className="text-muted-foreground text-xs">
+                      Moderate speed. Haar wavelet hash. Tolerant of blur and
+                      small crops.
+                    </div>
+                  </div>
+                </SelectItem>
+                <SelectItem value="combined">
+                  <div className="flex max-w-64 flex-col items-start gap-1">
+                    <div>Combined</div>
+                    <div className="text-muted-foreground text-xs">
+                      Slowest. Requires both the DCT and wavelet hashes to
+                      agree. Fewest false positives.
+                    </div>
+                  </div>
+                </SelectItem>
               </SelectContent>
             </Select>
           </Field>
@@ -404,6 +424,43 @@ export function AdvancedSettingsPopover({
                 : t("common.disabled")}
             </Button>
           </Field>
+          <Field
+            label={t("advanced.field.duplicates.colorHash")}
+            helper={t("advanced.field.duplicates.colorHash.helper")}
+            default={t("common.disabled")}
+          >
+            <Button
+              size="sm"
+              variant={settings.duplicates.colorHash ? "default" : "outline"}
+              onClick={() =>
+                updateField(
+                  ["duplicates", "colorHash"],
+                  !settings.duplicates.colorHash,
+                )
+              }
+              className="w-fit"
+            >
+              {settings.duplicates.colorHash
+                ? t("common.enabled")
+                : t("common.disabled")}
+            </Button>
+          </Field>
+          <Field
+            label={t("advanced.field.duplicates.colorThreshold")}
+            helper={t("advanced.field.duplicates.colorThreshold.helper")}
+            default="36"
+          >
+            <Input
+              type="number"
+              value={settings.duplicates.colorThreshold}
+              onChange={(e) =>
+                updateField(
+                  ["duplicates", "colorThreshold"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
           <Field
             label={t("advanced.field.duplicates.maxPerAlbum")}
             helper={t("advanced.field.duplicates.maxPerAlbum.helper")}
@@ -420,6 +477,22 @@ export function AdvancedSettingsPopover({
               }
             />
           </Field>
+          <Field
+            label={t("advanced.field.duplicates.scanChunkSize")}
+            helper={t("advanced.field.duplicates.scanChunkSize.helper")}
+            default="500"
+          >
+            <Input
+              type="number"
+              value={settings.duplicates.scanChunkSize}
+              onChange={(e) =>
+                updateField(
+                  ["duplicates", "scanChunkSize"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
         </Section>
 
         <Section
@@ -490,6 +563,39 @@ export function AdvancedSettingsPopover({
               }
             />
           </Field>
+          <Field
+            label={t("advanced.field.thumbs.posterSamples")}
+            helper={t("advanced.field.thumbs.posterSamples.helper")}
+            default="12"
+          >
+            <Input
+              type="number"
+              value={settings.thumbnails.posterSamples}
+              onChange={(e) =>
+                updateField(
+                  ["thumbnails", "posterSamples"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.thumbs.posterWindow")}
+            helper={t("advanced.field.thumbs.posterWindow.helper")}
+            default="5"
+          >
+            <Input
+              type="number"
+              step="0.5"
+              value={settings.thumbnails.posterWindowSecs}
+              onChange={(e) =>
+                updateField(
+                  ["thumbnails", "posterWindowSecs"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
           <Field
             label={t("advanced.field.thumbs.lockPoll")}
             helper={t("advanced.field.thumbs.lockPoll.helper")}
@@ -508,6 +614,91 @@ export function AdvancedSettingsPopover({
           </Field>
         </Section>
 
+        <Section
+          title={t("advanced.section.previews")}
+          onReset={() => resetSection("previews")}
+          resetLabel={t("advanced.resetSection")}
+        >
+          <Field
+            label={t("advanced.field.previews.enabled")}
+            helper={t("advanced.field.previews.enabled.helper")}
+            default={t("common.disabled")}
+          >
+            <Button
+              size="sm"
+              variant={settings.previews.enabled ? "default" : "outline"}
+              onClick={() =>
+                updateField(["previews", "enabled"], !settings.previews.enabled)
+              }
+              className="w-fit"
+            >
+              {settings.previews.enabled
+                ? t("common.enabled")
+                : t("common.disabled")}
+            </Button>
+          </Field>
+          <Field
+            label={t("advanced.field.previews.duration")}
+            helper={t("advanced.field.previews.duration.helper")}
+            default="3"
+          >
+            <Input
+              type="number"
+              step="0.5"
+              value={settings.previews.durationSecs}
+              onChange={(e) =>
+                updateField(
+                  ["previews", "durationSecs"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.previews.fps")}
+            helper={t("advanced.field.previews.fps.helper")}
+            default="8"
+          >
+            <Slider
+              min={1}
+              max={24}
+              step={1}
+              value={[settings.previews.fps]}
+              onValueChange={(v) =>
+                updateField(["previews", "fps"], v[0] ?? 8)
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.previews.maxDim")}
+            helper={t("advanced.field.previews.maxDim.helper")}
+            default="320"
+          >
+            <Input
+              type="number"
+              value={settings.previews.maxDim}
+              onChange={(e) =>
+                updateField(["previews", "maxDim"], Number(e.target.value))
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.previews.webpQuality")}
+            helper={t("advanced.field.previews.webpQuality.helper")}
+            default="60"
+          >
+            <Slider
+              min={20}
+              max={90}
+              step={1}
+              value={[settings.previews.webpQuality]}
+              onValueChange={(v) =>
+                updateField(["previews", "webpQuality"], v[0] ?? 60)
+              }
+            />
+          </Field>
+        </Section>
+
         <Section
           title={t("advanced.section.ffmpeg")}
           onReset={() => resetSection("ffmpeg")}
@@ -735,6 +926,72 @@ export function AdvancedSettingsPopover({
                 : t("common.off")}
             </Button>
           </Field>
+          <Field
+            label={t("advanced.field.preload.pauseOnBattery")}
+            helper={t("advanced.field.preload.pauseOnBattery.helper")}
+            default={t("common.off")}
+          >
+            <Button
+              size="sm"
+              variant={settings.preload.pauseOnBattery ? "default" : "outline"}
+              onClick={() =>
+                updateField(
+                  ["preload", "pauseOnBattery"],
+                  !settings.preload.pauseOnBattery,
+                )
+              }
+              className="w-fit"
+            >
+              {settings.preload.pauseOnBattery
+                ? t("common.enabled")
+                : t("common.off")}
+            </Button>
+          </Field>
+          <Field
+            label={t("advanced.field.preload.activeMaxWorkers")}
+            helper={t("advanced.field.preload.activeMaxWorkers.helper")}
+            default="1"
+          >
+            <Input
+              type="number"
+              value={settings.preload.activeMaxWorkers}
+              onChange={(e) =>
+                updateField(
+                  ["preload", "activeMaxWorkers"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.preload.activeIdle")}
+            helper={t("advanced.field.preload.activeIdle.helper")}
+            default="1500"
+          >
+            <Input
+              type="number"
+              value={settings.preload.activeIdleMs}
+              onChange={(e) =>
+                updateField(["preload", "activeIdleMs"], Number(e.target.value))
+              }
+            />
+          </Field>
+          <Field
+            label={t("advanced.field.preload.cpuLimit")}
+            helper={t("advanced.field.preload.cpuLimit.helper")}
+            default="100"
+          >
+            <Input
+              type="number"
+              value={settings.preload.cpuLimitPercent}
+              onChange={(e) =>
+                updateField(
+                  ["preload", "cpuLimitPercent"],
+                  Number(e.target.value),
+                )
+              }
+            />
+          </Field>
         </Section>
 
         <Section
@@ -3,21 +3,48 @@
 import { invoke } from "@tauri-apps/api/core";
 import { AnimatePresence, motion } from "framer-motion";
 import {
+  IconAlertTriangle,
   IconBook,
+  IconChecklist,
   IconLayoutGrid,
   IconLoader2,
+  IconLock,
+  IconLockOpen,
   IconFileText,
+  IconPhotoX,
+  IconFolders,
   IconRefresh,
+  IconRotateClockwise,
   IconTrash,
 } from "@tabler/icons-react";
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { Button } from "./ui/button";
 
 import { relaunch } from "@tauri-apps/plugin-process";
 import { LottiePlayer } from "@/lib/lottie";
-import { clearRoom237Artifacts, resetDuplicates } from "@/lib/fs/albumService";
+import {
+  checkLibrary,
+  clearRoom237Artifacts,
+  listFailures,
+  migrateArtifacts,
+  removeHeicSidecars,
+  resetDuplicates,
+  retryFailures,
+} from "@/lib/fs/albumService";
+import {
+  addLibrary,
+  listLibraries,
+  setLibraryOverrides,
+  type LibraryEntry,
+} from "@/lib/fs/libraryService";
 import { getStore } from "@/lib/fs/state";
 import { useRoom237 } from "@/lib/stores";
+import { useAdvancedSettings } from "@/lib/settings/store";
+import type {
+  FailureEntry,
+  HeicSidecarReport,
+  LibraryCheckReport,
+} from "@/lib/types";
 
 export function Debugger() {
   const [thumbnailsRebuilding, setThumbnailsRebuilding] = useState(false);
@@ -25,6 +52,23 @@ export function Debugger() {
   const [resettingDupes, setResettingDupes] = useState(false);
   const [clearingArtifacts, setClearingArtifacts] = useState(false);
   const [confirmClear, setConfirmClear] = useState(false);
+  const [migratingArtifacts, setMigratingArtifacts] = useState(false);
+  const [failures, setFailures] = useState<FailureEntry[] | null>(null);
+  const [loadingFailures, setLoadingFailures] = useState(false);
+  const [checkReport, setCheckReport] = useState<LibraryCheckReport | null>(
+    null,
+  );
+  const [checkingLibrary, setCheckingLibrary] = useState(false);
+  const [sidecarReport, setSidecarReport] = useState<HeicSidecarReport | null>(
+    null,
+  );
+  const [removingSidecars, setRemovingSidecars] = useState(false);
+  const [library, setLibrary] = useState<LibraryEntry | null>(null);
+  const [togglingReadOnly, setTogglingReadOnly] = useState(false);
+  const externalCache = useAdvancedSettings(
+    (state) => state.settings.storage.externalCache,
+  );
+  const refreshSettings = useAdvancedSettings((state) => state.refresh);
   const isDebug = useRoom237((state) => state.isDebug);
   const rootDir = useRoom237((state) => state.rootDir);
   const isLogger = useRoom237((state) => state.isLogger);
@@ -36,6 +80,80 @@ export function Debugger() {
   const hotRefresh = useRoom237((state) => state.hotRefresh);
   const setActiveAlbumId = useRoom237((state) => state.setActiveAlbumId);
 
+  useEffect(() => {
+    if (!isDebug || !rootDir) return;
+    void listLibraries().then((libraries) =>
+      setLibrary(libraries.find((entry) => entry.path === rootDir) ?? null),
+    );
+  }, [isDebug, rootDir]);
+
+  const toggleReadOnly = async () => {
+    if (!rootDir) return;
+    setTogglingReadOnly(true);
+    try {
+      const entry = library ?? (await addLibrary(rootDir));
+      setLibrary(
+        await setLibraryOverrides(
+          entry.id,
+          entry.duplicates,
+          entry.thumbnails,
+          !entry.readOnly,
+        ),
+      );
+    } finally {
+      setTogglingReadOnly(false);
+    }
+  };
+
+  const refreshFailures = async () => {
+    if (!rootDir) return;
+    setLoadingFailures(true);
+    try {
+      setFailures(await listFailures(rootDir));
+    } finally {
+      setLoadingFailures(false);
+    }
+  };
+
+  const runCheck = async (fix: boolean) => {
+    if (!rootDir) return;
+    setCheckingLibrary(true);
+    try {
+      setCheckReport(await checkLibrary(rootDir, fix));
+    } finally {
+      setCheckingLibrary(false);
+    }
+  };
+
+  const checkIssues = checkReport
+    ? [
+        ["Orphan thumbnails", checkReport.orphanThumbs],
+        ["Entries for missing files", checkReport.missingEntries],
+        ["Leftover locks", checkReport.staleLocks],
+        ["Stale legacy metadata", checkReport.legacyMeta],
+        ["Truncated thumbnails", checkReport.truncatedThumbs],
+      ] as const
+    : [];
+  const unfixedIssues = checkIssues.reduce(
+    (sum, [, category]) => sum + category.found - category.fixed,
+    0,
+  );
+
+  const retry = async (entry?: FailureEntry) => {
+    if (!rootDir) return;
+    setLoadingFailures(true);
+    try {
+      await retryFailures(
+        rootDir,
+        entry ? [entry.path] : undefined,
+        entry ? [entry.kind] : undefined,
+      );
+      setFailures(await listFailures(rootDir));
+    } finally {
+      setLoadingFailures(false);
+    }
+  };
+
   return (
     <AnimatePresence>
       {isDebug && (
@@ -147,6 +265,192 @@ export function Debugger() {
               )}
               Reset Duplicates
             </Button>
+            <Button
+              className="mt-2"
+              variant="secondary"
+              disabled={checkingLibrary}
+              onClick={() => runCheck(false)}
+            >
+              {checkingLibrary ? (
+                <IconLoader2 className="animate-spin" />
+              ) : (
+                <IconChecklist />
+              )}
+              Check library
+            </Button>
+            {checkReport && (
+              <div className="mt-2 flex w-full max-w-md flex-col gap-1 text-sm">
+                <span className="text-secondary-foreground/50">
+                  Checked {checkReport.albums} albums
+                </span>
+                {checkIssues.map(([label, category]) => (
+                  <div key={label} className="flex justify-between">
+                    <span>{label}</span>
+                    <span className="text-secondary-foreground/50">
+                      {category.fixed > 0
+                        ? `${category.fixed}/${category.found} fixed`
+                        : category.found}
+                    </span>
+                  </div>
+                ))}
+                {unfixedIssues > 0 && (
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={checkingLibrary}
+                    onClick={() => runCheck(true)}
+                  >
+                    <IconRefresh />
+                    Repair {unfixedIssues} issues
+                  </Button>
+                )}
+              </div>
+            )}
+            <Button
+              className="mt-2"
+              variant="secondary"
+              disabled={loadingFailures}
+              onClick={async () => {
+                if (failures) {
+                  setFailures(null);
+                  return;
+                }
+                await refreshFailures();
+              }}
+            >
+              {loadingFailures ? (
+                <IconLoader2 className="animate-spin" />
+              ) : (
+                <IconAlertTriangle />
+              )}
+              {failures ? "Hide failures" : "Show failures"}
+            </Button>
+            {failures && (
+              <div className="mt-2 flex w-full max-w-md flex-col gap-1">
+                <div className="flex items-center justify-between text-sm">
+                  <span className="text-secondary-foreground/50">
+                    {failures.length} failed tasks
+                  </span>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={loadingFailures || failures.length === 0}
+                    onClick={() => retry()}
+                  >
+                    <IconRotateClockwise />
+                    Retry all
+                  </Button>
+                </div>
+                <div className="max-h-48 overflow-y-auto rounded-md border text-xs">
+                  {failures.map((failure) => (
+                    <div
+                      key={`${failure.kind}:${failure.path}`}
+                      className="flex items-center gap-2 border-b px-2 py-1 last:border-b-0"
+                    >
+                      <div className="flex min-w-0 flex-1 flex-col">
+                        <span className="truncate font-medium">
+                          {failure.name}
+                        </span>
+                        <span className="text-secondary-foreground/50 truncate">
+                          {failure.kind} · {failure.attempts}×
+                          {failure.reason ? ` · ${failure.reason}` : ""}
+                        </span>
+                      </div>
+                      <Button
+                        size="icon"
+                        variant="ghost"
+                        disabled={loadingFailures}
+                        onClick={() => retry(failure)}
+                      >
+                        <IconRotateClockwise />
+                      </Button>
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+            <Button
+              className="mt-2"
+              variant="secondary"
+              disabled={removingSidecars}
+              onClick={async () => {
+                if (!rootDir) return;
+                setRemovingSidecars(true);
+                try {
+                  const pending =
+                    sidecarReport !== null &&
+                    sidecarReport.removed === 0 &&
+                    sidecarReport.sidecars.length > 0;
+                  setSidecarReport(await removeHeicSidecars(rootDir, !pending));
+                } finally {
+                  setRemovingSidecars(false);
+                }
+              }}
+            >
+              {removingSidecars ? (
+                <IconLoader2 className="animate-spin" />
+              ) : (
+                <IconPhotoX />
+              )}
+              {sidecarReport &&
+              sidecarReport.removed === 0 &&
+              sidecarReport.sidecars.length > 0
+                ? `Trash ${sidecarReport.sidecars.length} HEIC sidecars`
+                : "Find HEIC sidecars"}
+            </Button>
+            {sidecarReport && (
+              <span className="text-secondary-foreground/50 mt-1 text-sm">
+                {sidecarReport.removed > 0
+                  ? `Trashed ${sidecarReport.removed} sidecars`
+                  : `${sidecarReport.sidecars.length} sidecars found`}
+                {sidecarReport.skipped.length > 0 &&
+                  `, ${sidecarReport.skipped.length} kept (not generated)`}
+              </span>
+            )}
+            <Button
+              className="mt-2"
+              variant="secondary"
+              disabled={migratingArtifacts}
+              onClick={async () => {
+                setMigratingArtifacts(true);
+                try {
+                  if (!rootDir) return;
+                  await migrateArtifacts(rootDir, !externalCache);
+                  await refreshSettings();
+                } catch {
+                  return;
+                } finally {
+                  setMigratingArtifacts(false);
+                }
+                await relaunch();
+              }}
+            >
+              {migratingArtifacts ? (
+                <IconLoader2 className="animate-spin" />
+              ) : (
+                <IconFolders />
+              )}
+              {externalCache
+                ? "Move artifacts into albums"
+                : "Move artifacts to app cache"}
+            </Button>
+            <Button
+              className="mt-2"
+              variant="secondary"
+              disabled={togglingReadOnly}
+              onClick={toggleReadOnly}
+            >
+              {togglingReadOnly ? (
+                <IconLoader2 className="animate-spin" />
+              ) : library?.readOnly ? (
+                <IconLockOpen />
+              ) : (
+                <IconLock />
+              )}
+              {library?.readOnly
+                ? "Allow writes to this library"
+                : "Open this library read-only"}
+            </Button>
             <Button
               className="mt-2"
               variant="destructive"
@@ -2,10 +2,21 @@
 "use client";
 
 import { FAVORITES_ALBUM_ID } from "@/lib/consts";
-import { markNonDuplicates } from "@/lib/fs/albumService";
+import { subscribeDuplicateEvents } from "@/lib/duplicate-events";
+import {
+  listIgnoredDuplicates,
+  markNonDuplicates,
+  resolveDuplicateGroup,
+  unignoreDuplicates,
+} from "@/lib/fs/albumService";
 import { useUpload } from "@/lib/hooks/use-upload";
 import { useRoom237 } from "@/lib/stores";
 import type { MediaEntry } from "@/lib/types";
+import type {
+  DuplicateGroup,
+  IgnoredDuplicateGroup,
+} from "@/lib/types/album";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import { cn } from "@/lib/utils";
 import { AnimatePresence, motion } from "framer-motion";
 import { IconTrash, IconX } from "@tabler/icons-react";
@@ -84,25 +95,40 @@ function ScrollRightFade({
   );
 }
 
-const duplicateEqual = (
-  a: { image: MediaEntry; onDelete: () => void },
-  b: { image: MediaEntry; onDelete: () => void },
-) => a.image === b.image && a.onDelete === b.onDelete;
+type DuplicateProps = {
+  image: MediaEntry;
+  suggested: boolean;
+  onDelete: () => void;
+};
+
+const duplicateEqual = (a: DuplicateProps, b: DuplicateProps) =>
+  a.image === b.image &&
+  a.suggested === b.suggested &&
+  a.onDelete === b.onDelete;
 
 const Duplicate = memo(function Duplicate({
   image,
+  suggested,
   onDelete,
-}: {
-  image: MediaEntry;
-  onDelete: () => void;
-}) {
+}: DuplicateProps) {
   const [confirm, setConfirm] = useState(false);
+  const { t } = useI18n();
 
   return (
-    <div className="border-border group relative aspect-square overflow-hidden rounded-xl border">
+    <div
+      className={cn(
+        "border-border group relative aspect-square overflow-hidden rounded-xl border",
+        suggested && "border-primary",
+      )}
+    >
       <div className="text-foreground bg-background/70 absolute top-2 left-2 rounded-xl px-2 py-0.5 text-xs opacity-0 backdrop-blur-lg transition-all duration-150 group-hover:opacity-100">
         {image.name}
       </div>
+      {suggested && (
+        <div className="text-foreground bg-background/70 absolute bottom-2 left-2 rounded-xl px-2 py-0.5 text-xs backdrop-blur-lg">
+          {t("duplicates.suggested")}
+        </div>
+      )}
       <AnimatePresence>
         {confirm && (
           <motion.div
@@ -152,9 +178,92 @@ const Duplicate = memo(function Duplicate({
   );
 }, duplicateEqual);
 
+function IgnoredDuplicates({
+  dir,
+  version,
+  onRestore,
+}: {
+  dir: string;
+  version: number;
+  onRestore: () => void;
+}) {
+  const { t } = useI18n();
+  const [open, setOpen] = useState(false);
+  const [groups, setGroups] = useState<IgnoredDuplicateGroup[]>([]);
+
+  useEffect(() => {
+    let cancelled = false;
+    void listIgnoredDuplicates(dir).then((next) => {
+      if (!cancelled) setGroups(next);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [dir, version]);
+
+  if (groups.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="flex flex-col gap-2 px-4 pb-4">
+      <Button
+        variant="ghost"
+        size="sm"
+        className="w-fit"
+        onClick={() => setOpen((v) => !v)}
+      >
+        {open
+          ? t("duplicates.ignored.hide")
+          : t("duplicates.ignored.show", { count: groups.length })}
+      </Button>
+      {open &&
+        groups.map((group) => (
+          <div
+            key={group.id}
+            className="border-border/60 bg-background/60 flex items-center gap-3 rounded-2xl border p-3"
+          >
+            <div className="flex flex-1 flex-wrap gap-2">
+              {group.files.map((file) => (
+                <div
+                  key={file.name}
+                  title={file.name}
+                  className={cn(
+                    "border-border aspect-square w-16 overflow-hidden rounded-lg border",
+                    !file.exists && "opacity-40",
+                  )}
+                >
+                  {file.thumb && (
+                    <img
+                      src={convertFileSrc(file.thumb)}
+                      alt={file.name}
+                      className="h-full w-full object-cover"
+                    />
+                  )}
+                </div>
+              ))}
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={async () => {
+                await unignoreDuplicates(dir, group.id);
+                setGroups((prev) => prev.filter((g) => g.id !== group.id));
+                onRestore();
+              }}
+            >
+              {t("duplicates.ignored.restore")}
+            </Button>
+          </div>
+        ))}
+    </div>
+  );
+}
+
 export function DuplicatesView() {
   const [ready, setReady] = useState(false);
-  const [duplicates, setDuplicates] = useState<string[][]>([]);
+  const [duplicates, setDuplicates] = useState<DuplicateGroup[]>([]);
+  const [ignoredVersion, setIgnoredVersion] = useState(0);
   const { t } = useI18n();
   const albumSlice = useStoreWithEqualityFn(
     useRoom237,
@@ -196,24 +305,25 @@ export function DuplicatesView() {
 
   const duplicatesToDelete = useMemo(() => {
     return duplicates.flatMap((group) => {
-      const resolved = group
+      const resolved = group.files
         .map((item) => mediaByName.get(item))
         .filter((item): item is MediaEntry => Boolean(item));
       if (resolved.length <= 1) return [] as MediaEntry[];
-      return resolved.slice(1);
+      const keeper = group.keeper ?? resolved[0]!.name;
+      return resolved.filter((item) => item.name !== keeper);
     });
   }, [duplicates, mediaByName]);
 
   const normalize = useCallback(
-    (groups: string[][]) =>
+    (groups: DuplicateGroup[]) =>
       (groups ?? [])
-        .map((group) => group.filter(Boolean))
-        .filter((group) => group.length > 1),
+        .map((group) => ({ ...group, files: group.files.filter(Boolean) }))
+        .filter((group) => group.files.length > 1),
     [],
   );
 
   useEffect(() => {
-    const hasAny = duplicates.some((group) => group.length > 1);
+    const hasAny = duplicates.some((group) => group.files.length > 1);
     if (!hasAny) setShowDuplicates(false);
     setDuplicatesAvailable(hasAny);
   }, [duplicates, setDuplicatesAvailable, setShowDuplicates]);
@@ -237,8 +347,12 @@ export function DuplicatesView() {
         const normalized = normalize(latest);
         startTransition(() =>
           setDuplicates((prev) => {
-            const prevKey = prev.map((g) => g.join("|")).join("||");
-            const nextKey = normalized.map((g) => g.join("|")).join("||");
+            const prevKey = prev
+              .map((g) => `${g.kind}:${g.files.join("|")}`)
+              .join("||");
+            const nextKey = normalized
+              .map((g) => `${g.kind}:${g.files.join("|")}`)
+              .join("||");
             if (prevKey === nextKey) return prev;
             return normalized;
           }),
@@ -266,6 +380,20 @@ export function DuplicatesView() {
     setDuplicates(normalize(latest));
   }, [albumSlice, normalize]);
 
+  useEffect(() => {
+    if (!albumSlice) return;
+    return subscribeDuplicateEvents(({ dir, groups }) => {
+      useRoom237.setState((state) => ({
+        albumDuplicatesByPath: {
+          ...state.albumDuplicatesByPath,
+          [dir]: groups,
+        },
+      }));
+      if (dir !== albumSlice.path) return;
+      startTransition(() => setDuplicates(normalize(groups)));
+    });
+  }, [albumSlice, normalize]);
+
   useEffect(() => {
     let cancelled = false;
     void (async () => {
@@ -352,7 +480,9 @@ export function DuplicatesView() {
               duplicates.length <= 0 ||
               !duplicates.some(
                 (group) =>
-                  group.map((item) => mediaByName.get(item)).filter(Boolean)
+                  group.files
+                    .map((item) => mediaByName.get(item))
+                    .filter(Boolean)
                     .length > 1,
               )) ? (
               <div className="flex h-full flex-col gap-4 p-4">
@@ -376,37 +506,72 @@ export function DuplicatesView() {
               duplicates.length > 0 &&
               duplicates.some(
                 (group) =>
-                  group.map((item) => mediaByName.get(item)).filter(Boolean)
+                  group.files
+                    .map((item) => mediaByName.get(item))
+                    .filter(Boolean)
                     .length > 1,
               ) ? (
               <div className="flex flex-col gap-2 p-4">
                 {duplicates.map(
                   (group, index) =>
-                    group.length > 1 && (
+                    group.files.length > 1 && (
                       <div
                         key={index}
                         className="border-border/60 bg-background/60 relative overflow-hidden rounded-2xl border p-3 shadow-sm"
                       >
-                        <div className="mb-2">
+                        <div className="mb-2 flex items-center gap-2">
                           <Button
                             variant="outline"
                             size="sm"
                             onClick={async () => {
                               if (!albumSlice?.path) return;
-                              await markNonDuplicates(albumSlice.path, group);
+                              await markNonDuplicates(
+                                albumSlice.path,
+                                group.files,
+                              );
                               setDuplicates((prev) =>
                                 prev.filter((_, i) => i !== index),
                               );
+                              setIgnoredVersion((v) => v + 1);
                             }}
                           >
                             {t("duplicates.markNonDuplicates")}
                           </Button>
+                          {group.keeper && (
+                            <Button
+                              variant="outline"
+                              size="sm"
+                              disabled={batchOperationInProgress}
+                              onClick={async () => {
+                                if (!albumSlice?.path || !group.keeper) return;
+                                await resolveDuplicateGroup(
+                                  albumSlice.path,
+                                  group.keeper,
+                                  group.files,
+                                );
+                                const state = useRoom237.getState();
+                                await state.loadAlbumMedia(albumSlice.id, {
+                                  force: true,
+                                });
+                                await state.refreshFavoritesMap();
+                                await refreshDuplicates({
+                                  initial: false,
+                                  force: true,
+                                });
+                              }}
+                            >
+                              {t("duplicates.keepSuggested")}
+                            </Button>
+                          )}
+                          <span className="text-muted-foreground text-xs">
+                            {t(`duplicates.kind.${group.kind}`)}
+                          </span>
                         </div>
                         <div className="relative">
-                          {group.length > 5 ? (
+                          {group.files.length > 5 ? (
                             <ScrollRightFade>
                               <div className="flex gap-3">
-                                {group.map((item) => {
+                                {group.files.map((item) => {
                                   const image = mediaByName.get(item);
                                   if (!image) return null;
                                   return (
@@ -416,6 +581,7 @@ export function DuplicatesView() {
                                     >
                                       <Duplicate
                                         image={image}
+                                        suggested={group.keeper === item}
                                         onDelete={() => {
                                           void (async () => {
                                             await deleteMedia(image);
@@ -429,7 +595,7 @@ export function DuplicatesView() {
                             </ScrollRightFade>
                           ) : (
                             <div className="flex flex-wrap gap-3">
-                              {group.map((item) => {
+                              {group.files.map((item) => {
                                 const image = mediaByName.get(item);
                                 if (!image) return null;
                                 return (
@@ -439,6 +605,7 @@ export function DuplicatesView() {
                                   >
                                     <Duplicate
                                       image={image}
+                                      suggested={group.keeper === item}
                                       onDelete={() => {
                                         void (async () => {
                                           await deleteMedia(image);
@@ -501,6 +668,13 @@ export function DuplicatesView() {
                 </span>
               </div>
             )}
+            <IgnoredDuplicates
+              dir={albumSlice.path}
+              version={ignoredVersion}
+              onRestore={() =>
+                void refreshDuplicates({ initial: false, force: true })
+              }
+            />
           </ScrollArea>
         </motion.div>
       )}
@@ -7,23 +7,40 @@ import {
   PopoverTrigger,
 } from "@/components/ui/popover";
 import { FAVORITES_ALBUM_ID } from "@/lib/consts";
+import {
+  subscribeDuplicateScanProgress,
+  type DuplicateScanProgressPayload,
+} from "@/lib/duplicate-events";
+import { cancelDuplicateScan } from "@/lib/fs/albumService";
 import { useI18n } from "@/lib/i18n";
 import { useRoom237 } from "@/lib/stores";
 import { cn } from "@/lib/utils";
-import { IconStack2, IconTrash } from "@tabler/icons-react";
+import { IconStack2, IconTrash, IconX } from "@tabler/icons-react";
 import { AnimatePresence, motion } from "framer-motion";
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { useStoreWithEqualityFn } from "zustand/traditional";
 
 export function AlbumActions() {
   const duplicatesAvailable = useRoom237((state) => state.duplicatesAvailable);
   const duplicatesLoading = useRoom237((state) => state.duplicatesLoading);
+  const duplicatesScanId = useRoom237((state) => state.duplicatesScanId);
+  const [scanProgress, setScanProgress] =
+    useState<DuplicateScanProgressPayload | null>(null);
   const showDuplicates = useRoom237((state) => state.showDuplicates);
   const setShowDuplicates = useRoom237((state) => state.setShowDuplicates);
   const deleteAlbum = useRoom237((state) => state.deleteAlbum);
   const [deleteOpen, setDeleteOpen] = useState(false);
   const { t } = useI18n();
 
+  useEffect(() => {
+    setScanProgress(null);
+    if (!duplicatesScanId) return;
+    return subscribeDuplicateScanProgress((progress) => {
+      if (progress.scan_id !== duplicatesScanId) return;
+      setScanProgress(progress);
+    });
+  }, [duplicatesScanId]);
+
   const activeAlbum = useStoreWithEqualityFn(
     useRoom237,
     (state) => {
@@ -101,7 +118,24 @@ export function AlbumActions() {
                   <circle cx="17" cy="17" r="3" />
                 </motion.g>
               </svg>
-              {t("duplicates.loading")}
+              {scanProgress && scanProgress.total > 0
+                ? t("duplicates.loadingProgress", {
+                    values: {
+                      completed: scanProgress.completed,
+                      total: scanProgress.total,
+                    },
+                  })
+                : t("duplicates.loading")}
+              {duplicatesScanId && (
+                <button
+                  type="button"
+                  title={t("duplicates.cancelScan")}
+                  onClick={() => void cancelDuplicateScan(duplicatesScanId)}
+                  className="hover:text-foreground -mr-1.5 ml-0.5 rounded-full p-0.5"
+                >
+                  <IconX className="size-3" />
+                </button>
+              )}
             </motion.div>
           )}
           {!duplicatesLoading && duplicatesAvailable && (
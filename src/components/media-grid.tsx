@@ -22,6 +22,9 @@ import { useStoreWithEqualityFn } from "zustand/traditional";
 import { FavoritesAlbum } from "./favorites-album";
 import { useDragDrop } from "@/lib/hooks/use-drag-drop";
 import { useI18n } from "@/lib/i18n";
+import { prioritizeMedia } from "@/lib/fs/albumService";
+
+const PRIORITIZE_DEBOUNCE_MS = 200;
 
 const nameForClipboard = (file: File, idx: number) => {
   const trimmed = file.name?.trim();
@@ -203,6 +206,28 @@ export default function MediaGrid({
     measureElement: measureWithFallback,
   });
 
+  const visibleIndexes = (
+    layout === "masonry"
+      ? rowVirtualizerMasonry.getVirtualItems()
+      : rowVirtualizerGrid.getVirtualItems()
+  ).map((item) => item.index);
+  const firstVisible = visibleIndexes.length
+    ? Math.min(...visibleIndexes)
+    : -1;
+  const lastVisible = visibleIndexes.length ? Math.max(...visibleIndexes) : -1;
+
+  useEffect(() => {
+    if (firstVisible < 0) return;
+    const paths =
+      layout === "masonry"
+        ? mediaPaths.slice(firstVisible, lastVisible + 1)
+        : mediaPaths.slice(firstVisible * columns, (lastVisible + 1) * columns);
+    const timer = setTimeout(() => {
+      prioritizeMedia(paths).catch(() => {});
+    }, PRIORITIZE_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [firstVisible, lastVisible, layout, columns, mediaPaths]);
+
   const scrollerHeight = scrollSize.height ?? scrollTarget?.clientHeight ?? 0;
   const virtualContentHeight =
     layout === "masonry"
@@ -34,6 +34,7 @@ export const MediaItemInner = memo(function MediaItemInner({
       return {
         name: item?.name,
         thumb: item?.thumb,
+        preview: item?.preview,
         favorite: item?.favorite,
       };
     },
@@ -66,6 +67,10 @@ export const MediaItemInner = memo(function MediaItemInner({
 
   const showExtras = useMemo(() => columns <= 10, [columns]);
 
+  const [hovered, setHovered] = useState(false);
+  const previewing = hovered && !!itemData.preview;
+  const src = previewing ? itemData.preview : itemData.thumb;
+
   if (!itemData.name) return null;
 
   return (
@@ -87,6 +92,8 @@ export const MediaItemInner = memo(function MediaItemInner({
         if (item) onDragStart(e, item);
       }}
       onDragEnd={clear}
+      onMouseEnter={() => setHovered(true)}
+      onMouseLeave={() => setHovered(false)}
       style={itemStyle}
     >
       {deferredExtrasMounted && showExtras && (
@@ -96,18 +103,20 @@ export const MediaItemInner = memo(function MediaItemInner({
       {isVideo(itemData.name) ? (
         <>
           <img
-            src={itemData.thumb}
+            src={src}
             alt={itemData.name}
             className={cn(
               "block w-full cursor-pointer select-none",
               imgClassName,
             )}
           />
-          <IconPlayerPlay className="pointer-events-none absolute top-1/2 left-1/2 h-8 w-8 -translate-x-1/2 -translate-y-1/2 text-white/80" />
+          {!previewing && (
+            <IconPlayerPlay className="pointer-events-none absolute top-1/2 left-1/2 h-8 w-8 -translate-x-1/2 -translate-y-1/2 text-white/80" />
+          )}
         </>
       ) : (
         <img
-          src={itemData.thumb}
+          src={src}
           alt={itemData.name}
           className={cn(
             "block w-full cursor-pointer select-none",
@@ -3,9 +3,12 @@
 import MediaGrid from "@/components/media-grid";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { useSortedMedia } from "@/lib/hooks/use-sorted-media";
+import { notifyUserActivity } from "@/lib/fs/albumService";
 import { AnimatePresence, motion } from "framer-motion";
 import { useCallback, useEffect, useRef, useState } from "react";
 
+const ACTIVITY_NOTIFY_MS = 250;
+
 export function MediaScroller() {
   const scrollerRef = useRef<HTMLDivElement>(null);
   const [showTopFade, setShowTopFade] = useState(false);
@@ -26,7 +29,15 @@ export function MediaScroller() {
     const el = scrollerRef.current;
     if (!el) return;
     updateFade();
-    const onScroll = () => updateFade();
+    let lastNotify = 0;
+    const onScroll = () => {
+      updateFade();
+      const now = Date.now();
+      if (now - lastNotify > ACTIVITY_NOTIFY_MS) {
+        lastNotify = now;
+        notifyUserActivity().catch(() => {});
+      }
+    };
     el.addEventListener("scroll", onScroll, { passive: true });
     const ro = new ResizeObserver(() => updateFade());
     ro.observe(el);
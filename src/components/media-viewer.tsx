@@ -8,6 +8,7 @@ import {
 } from "@/components/ui/popover";
 import { useUpload } from "@/lib/hooks/use-upload";
 import { useViewer } from "@/lib/hooks/use-viewer";
+import { getThumb } from "@/lib/fs/albumService";
 import { useRoom237 } from "@/lib/stores";
 import { cn, copyFiles, extractItemFromState, isVideo } from "@/lib/utils";
 import {
@@ -17,6 +18,7 @@ import {
   IconTrash,
   IconX,
 } from "@tabler/icons-react";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import { AnimatePresence, motion } from "framer-motion";
 import { isEqual } from "lodash";
 import {
@@ -115,6 +117,35 @@ export default function MediaViewer() {
     isEqual,
   );
 
+  const [sizedThumb, setSizedThumb] = useState<{
+    path: string;
+    src: string;
+  } | null>(null);
+
+  useEffect(() => {
+    if (!item || isVideo(item.name)) return;
+    const itemPath = item.path;
+    const minDim = Math.ceil(
+      Math.max(window.innerWidth, window.innerHeight) *
+        0.9 *
+        window.devicePixelRatio,
+    );
+    let cancelled = false;
+    getThumb(itemPath, minDim)
+      .then((thumb) => convertFileSrc(thumb))
+      .catch(() => item.url)
+      .then((src) => {
+        if (!cancelled) setSizedThumb({ path: itemPath, src });
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [item]);
+
+  const sizedSrc =
+    item && sizedThumb?.path === item.path ? sizedThumb.src : null;
+  const imageSrc = !item ? undefined : scale > 1 ? item.url : sizedSrc;
+
   const controlsRef = useRef<HTMLDivElement>(null);
 
   const controlsOpacity = useMemo(() => {
@@ -786,8 +817,11 @@ export default function MediaViewer() {
                   ref={(el) => {
                     mediaRef.current = el;
                   }}
-                  src={item.url}
-                  className="max-h-[90vh] max-w-[90vw] object-contain select-none"
+                  src={imageSrc ?? item.thumb}
+                  className={cn(
+                    "max-h-[90vh] max-w-[90vw] object-contain select-none",
+                    !imageSrc && "h-[90vh] w-[90vw]",
+                  )}
                   alt="media"
                   draggable={false}
                   onLoad={(e) => {
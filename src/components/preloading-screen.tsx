@@ -9,23 +9,31 @@ import { LottiePlayer } from "@/lib/lottie";
 import { CircularProgress } from "./ui/circular-progress";
 import { useI18n } from "@/lib/i18n";
 
-type StageKey = "conversion" | "thumbnails" | "metadata" | "idle";
+type StageKey = "thumbnails" | "metadata" | "idle";
 
 type StageProgress = {
   completed: number;
   total: number;
 };
 
+type RootProgress = {
+  root_id: string;
+  root: string;
+  queued_albums: number;
+  thumbnails: StageProgress;
+  metadata: StageProgress;
+};
+
 type PreloadProgressPayload = {
   stage: StageKey;
   stage_progress: StageProgress;
   overall_completed: number;
   overall_total: number;
   progress: number;
-  conversions: StageProgress;
   thumbnails: StageProgress;
   metadata: StageProgress;
   active_actions: number;
+  roots?: RootProgress[];
 };
 
 const emptyStage: StageProgress = { completed: 0, total: 0 };
@@ -39,10 +47,10 @@ const normalizePayload = (
   overall_completed: payload?.overall_completed ?? 0,
   overall_total: payload?.overall_total ?? 0,
   progress: payload?.progress ?? 0,
-  conversions: payload?.conversions ?? emptyStage,
   thumbnails: payload?.thumbnails ?? emptyStage,
   metadata: payload?.metadata ?? emptyStage,
   active_actions: payload?.active_actions ?? 0,
+  roots: payload?.roots ?? [],
 });
 
 export function PreloadingScreen() {
@@ -55,7 +63,6 @@ export function PreloadingScreen() {
   const [recentActivity, setRecentActivity] = useState<
     Record<StageKey, number>
   >({
-    conversion: 0,
     thumbnails: 0,
     metadata: 0,
     idle: 0,
@@ -103,9 +110,8 @@ export function PreloadingScreen() {
         const now = Date.now();
         setRecentActivity((curr) => {
           const next = { ...curr };
-          const stages = ["conversion", "thumbnails", "metadata"] as const;
+          const stages = ["thumbnails", "metadata"] as const;
           const propMap = {
-            conversion: "conversions",
             thumbnails: "thumbnails",
             metadata: "metadata",
           } as const;
@@ -166,11 +172,6 @@ export function PreloadingScreen() {
 
   const steps = useMemo(
     () => [
-      {
-        key: "conversion" as const,
-        label: t("preload.conversion"),
-        progress: status?.conversions ?? emptyStage,
-      },
       {
         key: "thumbnails" as const,
         label: t("preload.thumbnails"),
@@ -182,7 +183,7 @@ export function PreloadingScreen() {
         progress: status?.metadata ?? emptyStage,
       },
     ],
-    [status?.conversions, status?.thumbnails, status?.metadata, t],
+    [status?.thumbnails, status?.metadata, t],
   );
 
   if (!isVisible || !status) {
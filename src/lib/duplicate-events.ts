@@ -0,0 +1,59 @@
+"use client";
+
+import { listen } from "@tauri-apps/api/event";
+import type { DuplicateGroup } from "@/lib/types/album";
+
+export type DuplicatesUpdatedPayload = {
+  dir: string;
+  groups: DuplicateGroup[];
+};
+
+export type DuplicateScanProgressPayload = {
+  scan_id: string;
+  dir: string;
+  phase: "hashing" | "comparing" | "done";
+  completed: number;
+  total: number;
+};
+
+export function subscribeDuplicateScanProgress(
+  onProgress: (p: DuplicateScanProgressPayload) => void,
+) {
+  let unlistenProgress: (() => void) | null = null;
+
+  const setup = async () => {
+    unlistenProgress = await listen<DuplicateScanProgressPayload>(
+      "duplicate-scan-progress",
+      (event) => {
+        onProgress(event.payload);
+      },
+    );
+  };
+
+  setup().catch(console.error);
+
+  return () => {
+    unlistenProgress?.();
+  };
+}
+
+export function subscribeDuplicateEvents(
+  onUpdate: (p: DuplicatesUpdatedPayload) => void,
+) {
+  let unlistenUpdated: (() => void) | null = null;
+
+  const setup = async () => {
+    unlistenUpdated = await listen<DuplicatesUpdatedPayload>(
+      "duplicates-updated",
+      (event) => {
+        onUpdate(event.payload);
+      },
+    );
+  };
+
+  setup().catch(console.error);
+
+  return () => {
+    unlistenUpdated?.();
+  };
+}
@@ -1,4 +1,12 @@
-import type { FavoriteDetachedMediaEntry, MediaEntry } from "@/lib/types";
+import type {
+  FailureEntry,
+  FailureKind,
+  FavoriteDetachedMediaEntry,
+  HeicSidecarReport,
+  JobInfo,
+  LibraryCheckReport,
+  MediaEntry,
+} from "@/lib/types";
 import { invoke } from "@tauri-apps/api/core";
 import path from "path";
 import { exists, mkdir, remove } from "@tauri-apps/plugin-fs";
@@ -7,6 +15,9 @@ import {
   type Album,
   type AlbumNode,
   type DetachedAlbum,
+  type DuplicateGroup,
+  type IgnoredDuplicateGroup,
+  type SimilarMatch,
 } from "../types/album";
 import { type DetachedMediaEntry } from "../types";
 import { attachMediaEntry } from "../utils";
@@ -143,6 +154,23 @@ export async function listAlbums(
   return { albumsById, albumTree };
 }
 
+export async function listLibraryTree(
+  rootDirs: string[],
+): Promise<Record<string, DetachedAlbum[]>> {
+  const rawAlbums = (await invoke("get_library_tree", {
+    rootDirs,
+  })) satisfies DetachedAlbum[];
+  const byRoot: Record<string, DetachedAlbum[]> = {};
+  for (const raw of rawAlbums) {
+    (byRoot[raw.root_id] ??= []).push(raw);
+  }
+  return byRoot;
+}
+
+export async function closeLibraryRoot(rootDir: string): Promise<boolean> {
+  return await invoke("close_library_root", { rootDir });
+}
+
 export async function markNonDuplicates(
   dir: string,
   files: string[],
@@ -150,6 +178,127 @@ export async function markNonDuplicates(
   await invoke("mark_non_duplicates", { dir, files });
 }
 
+export async function listIgnoredDuplicates(
+  dir: string,
+): Promise<IgnoredDuplicateGroup[]> {
+  return await invoke("list_ignored_duplicates", { dir });
+}
+
+export async function unignoreDuplicates(
+  dir: string,
+  groupId: string,
+  files?: string[],
+): Promise<boolean> {
+  return await invoke("unignore_duplicates", {
+    dir,
+    groupId,
+    files: files ?? null,
+  });
+}
+
+export async function resolveDuplicateGroup(
+  dir: string,
+  keep: string,
+  files: string[],
+): Promise<string[]> {
+  return await invoke("resolve_duplicate_group", { dir, keep, files });
+}
+
+export async function findSimilar(
+  path: string,
+  options?: {
+    maxDistance?: number;
+    limit?: number;
+    scope?: "album" | "root";
+  },
+): Promise<SimilarMatch[]> {
+  return await invoke("find_similar", {
+    path,
+    maxDistance: options?.maxDistance ?? null,
+    limit: options?.limit ?? null,
+    scope: options?.scope ?? null,
+  });
+}
+
+export async function findLibraryDuplicates(
+  rootDir: string,
+  scanId?: string,
+): Promise<DuplicateGroup[]> {
+  return await invoke("find_library_duplicates", {
+    rootDir,
+    scanId: scanId ?? null,
+  });
+}
+
+export async function cancelDuplicateScan(scanId: string): Promise<boolean> {
+  return await cancelJob(scanId);
+}
+
+export async function getThumb(path: string, minDim: number): Promise<string> {
+  return await invoke("get_thumb", { path, minDim });
+}
+
+export async function prioritizeMedia(paths: string[]): Promise<number> {
+  return await invoke("prioritize_media", { paths });
+}
+
+export async function notifyUserActivity(): Promise<void> {
+  await invoke("notify_user_activity");
+}
+
+export async function listJobs(): Promise<JobInfo[]> {
+  return await invoke("list_jobs");
+}
+
+export async function pauseJob(jobId: string): Promise<boolean> {
+  return await invoke("pause_job", { jobId });
+}
+
+export async function resumeJob(jobId: string): Promise<boolean> {
+  return await invoke("resume_job", { jobId });
+}
+
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return await invoke("cancel_job", { jobId });
+}
+
+export async function listFailures(rootDir: string): Promise<FailureEntry[]> {
+  return await invoke("list_failures", { rootDir });
+}
+
+export async function retryFailures(
+  rootDir: string,
+  paths?: string[],
+  kinds?: FailureKind[],
+): Promise<number> {
+  return await invoke("retry_failures", {
+    rootDir,
+    paths: paths ?? null,
+    kinds: kinds ?? null,
+  });
+}
+
+export async function checkLibrary(
+  rootDir: string,
+  fix: boolean,
+): Promise<LibraryCheckReport> {
+  return await invoke("check_library", { rootDir, fix });
+}
+
+export async function removeHeicSidecars(
+  rootDir: string,
+  dryRun: boolean,
+): Promise<HeicSidecarReport> {
+  return await invoke("remove_heic_sidecars", { rootDir, dryRun });
+}
+
+export async function markLibraryNonDuplicates(
+  rootDir: string,
+  files: string[],
+): Promise<void> {
+  await invoke("mark_library_non_duplicates", { rootDir, files });
+}
+
 export async function resetDuplicates(rootDir: string): Promise<void> {
   await invoke("reset_duplicates", { rootDir });
 }
@@ -158,10 +307,24 @@ export async function clearRoom237Artifacts(rootDir: string): Promise<void> {
   await invoke("clear_room237_artifacts", { rootDir });
 }
 
+export async function migrateArtifacts(
+  rootDir: string,
+  toExternal: boolean,
+): Promise<number> {
+  return await invoke("migrate_artifacts", { rootDir, toExternal });
+}
+
+export async function assertWritable(dir: string): Promise<void> {
+  if (await invoke<boolean>("is_library_read_only", { rootDir: dir })) {
+    throw new Error("Library is opened read-only.");
+  }
+}
+
 export async function createAlbum(
   rootDir: string,
   name: string,
 ): Promise<void> {
+  await assertWritable(rootDir);
   const safe = name.trim().replace(/[\/\\:]/g, "_");
   const dir = path.join(rootDir, safe);
   if (await exists(dir)) {
@@ -171,6 +334,7 @@ export async function createAlbum(
 }
 
 export async function deleteAlbum(album: Album): Promise<void> {
+  await assertWritable(album.path);
   albumCache.delete(album.path);
   await remove(album.path, { recursive: true });
 }
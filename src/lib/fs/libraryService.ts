@@ -0,0 +1,51 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  DuplicatesSettings,
+  ThumbnailSettings,
+} from "@/lib/settings/schema";
+
+export type LibraryEntry = {
+  id: string;
+  path: string;
+  name: string;
+  lastOpened: number | null;
+  duplicates: DuplicatesSettings | null;
+  thumbnails: ThumbnailSettings | null;
+  readOnly: boolean;
+};
+
+export async function listLibraries(): Promise<LibraryEntry[]> {
+  return await invoke("list_libraries");
+}
+
+export async function addLibrary(
+  rootDir: string,
+  name?: string,
+): Promise<LibraryEntry> {
+  return await invoke("add_library", { rootDir, name: name ?? null });
+}
+
+export async function removeLibrary(id: string): Promise<boolean> {
+  return await invoke("remove_library", { id });
+}
+
+export async function relocateLibrary(
+  id: string,
+  newPath: string,
+): Promise<LibraryEntry> {
+  return await invoke("relocate_library", { id, newPath });
+}
+
+export async function setLibraryOverrides(
+  id: string,
+  duplicates: DuplicatesSettings | null,
+  thumbnails: ThumbnailSettings | null,
+  readOnly = false,
+): Promise<LibraryEntry> {
+  return await invoke("set_library_overrides", {
+    id,
+    duplicates,
+    thumbnails,
+    readOnly,
+  });
+}
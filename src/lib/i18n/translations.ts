@@ -66,17 +66,26 @@ export const translations: Record<Language, TranslationDictionary> = {
     "media.empty.title": "This album is empty",
     "media.empty.subtitle": "Drop a photo here to upload",
     "preload.title": "Indexing your library",
-    "preload.conversion": "HEIC to JPEG Conversion",
     "preload.thumbnails": "Thumbnails",
     "preload.metadata": "Metadata",
     "layout.grid": "Grid",
     "layout.masonry": "Masonry",
     "layout.apple": "Apple-Style",
     "duplicates.loading": "Looking for duplicates...",
+    "duplicates.loadingProgress":
+      "Looking for duplicates... {{completed}}/{{total}}",
+    "duplicates.cancelScan": "Stop and show what was found",
     "duplicates.show": "Show Duplicates",
     "duplicates.hide": "Hide Duplicates",
     "duplicates.markNonDuplicates": "Mark as non-duplicates",
+    "duplicates.kind.exact": "Exact copies",
+    "duplicates.kind.perceptual": "Similar",
+    "duplicates.suggested": "Best shot",
+    "duplicates.keepSuggested": "Keep best shot",
     "duplicates.none": "No duplicates found.",
+    "duplicates.ignored.show": "Show ignored ({{count}})",
+    "duplicates.ignored.hide": "Hide ignored",
+    "duplicates.ignored.restore": "Un-ignore",
     "duplicates.deleteAll": "Delete all duplicates",
     "duplicates.deleteAllConfirm":
       "Delete all duplicates? This keeps one file from each group and deletes the rest. Please review duplicates manually before continuing.",
@@ -198,6 +207,7 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.section.privacy": "Privacy",
     "advanced.section.duplicates": "Duplicates & Hashing",
     "advanced.section.thumbnails": "Thumbnails",
+    "advanced.section.previews": "Animated previews",
     "advanced.section.ffmpeg": "FFmpeg",
     "advanced.section.preload": "Preload",
     "advanced.section.metadata": "Metadata",
@@ -226,9 +236,18 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.duplicates.useThumbsFirst": "Use thumbnails first",
     "advanced.field.duplicates.useThumbsFirst.helper":
       "Hash thumbnails first to speed up duplicate detection.",
+    "advanced.field.duplicates.colorHash": "Compare colours",
+    "advanced.field.duplicates.colorHash.helper":
+      "Also compare colour moments so recoloured or filtered copies are not grouped.",
+    "advanced.field.duplicates.colorThreshold": "Colour tolerance",
+    "advanced.field.duplicates.colorThreshold.helper":
+      "Maximum colour-moment difference for two images to count as duplicates. Lower values separate recoloured copies more strictly.",
     "advanced.field.duplicates.maxPerAlbum": "Max files per album",
     "advanced.field.duplicates.maxPerAlbum.helper":
-      "Max number of duplicate files to list per album.",
+      "Scan at most this many files per album (0 = no limit). Larger albums are scanned partially.",
+    "advanced.field.duplicates.scanChunkSize": "Scan chunk size",
+    "advanced.field.duplicates.scanChunkSize.helper":
+      "Files hashed per chunk during a duplicate scan. 0 hashes the whole album at once.",
     "advanced.field.thumbs.maxDim": "Max dimension (px)",
     "advanced.field.thumbs.maxDim.helper":
       "Maximum width or height of generated thumbnails.",
@@ -241,9 +260,30 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.thumbs.videoSeek": "Video seek (s)",
     "advanced.field.thumbs.videoSeek.helper":
       "Time position for video thumbnails.",
+    "advanced.field.thumbs.posterSamples": "Poster samples",
+    "advanced.field.thumbs.posterSamples.helper":
+      "Frames compared when picking a video poster (1 = use the seek position).",
+    "advanced.field.thumbs.posterWindow": "Poster window (s)",
+    "advanced.field.thumbs.posterWindow.helper":
+      "Length of the clip scanned for a poster frame.",
     "advanced.field.thumbs.lockPoll": "Lock poll (ms)",
     "advanced.field.thumbs.lockPoll.helper":
       "Interval to check for thumbnail lock release.",
+    "advanced.field.previews.enabled": "Animated previews",
+    "advanced.field.previews.enabled.helper":
+      "Generate short looping previews for videos, GIFs and animated WebP, shown on hover.",
+    "advanced.field.previews.duration": "Duration (s)",
+    "advanced.field.previews.duration.helper":
+      "Length of the animated preview clip.",
+    "advanced.field.previews.fps": "Frame rate",
+    "advanced.field.previews.fps.helper":
+      "Frames per second in animated previews.",
+    "advanced.field.previews.maxDim": "Max dimension (px)",
+    "advanced.field.previews.maxDim.helper":
+      "Maximum width or height of animated previews.",
+    "advanced.field.previews.webpQuality": "WebP quality",
+    "advanced.field.previews.webpQuality.helper":
+      "Quality of animated WebP previews.",
     "advanced.field.ffmpeg.threads": "Threads",
     "advanced.field.ffmpeg.threads.helper": "Number of threads FFmpeg can use.",
     "advanced.field.ffmpeg.timeout": "Timeout (s)",
@@ -276,6 +316,18 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.preload.hashRetry": "Retry on thumb change",
     "advanced.field.preload.hashRetry.helper":
       "Retry computing hash if thumbnail changes during preload.",
+    "advanced.field.preload.pauseOnBattery": "Pause on battery",
+    "advanced.field.preload.pauseOnBattery.helper":
+      "Stop background processing while the laptop runs on battery.",
+    "advanced.field.preload.activeMaxWorkers": "Workers while scrolling",
+    "advanced.field.preload.activeMaxWorkers.helper":
+      "Maximum background tasks running while you scroll. 0 pauses them.",
+    "advanced.field.preload.activeIdle": "Scroll idle (ms)",
+    "advanced.field.preload.activeIdle.helper":
+      "How long after the last scroll the full worker count resumes.",
+    "advanced.field.preload.cpuLimit": "CPU limit (%)",
+    "advanced.field.preload.cpuLimit.helper":
+      "Share of time each worker may stay busy; workers rest for the rest.",
     "advanced.field.metadata.probeTimeout": "Probe timeout (s)",
     "advanced.field.metadata.probeTimeout.helper":
       "Timeout for probing media metadata.",
@@ -339,7 +391,6 @@ export const translations: Record<Language, TranslationDictionary> = {
     "media.empty.title": "Альбом пуст",
     "media.empty.subtitle": "Перетащи фото сюда, чтобы загрузить",
     "preload.title": "Индексируем библиотеку",
-    "preload.conversion": "Конвертация HEIC в JPEG",
     "preload.thumbnails": "Превью",
     "preload.metadata": "Метаданные",
     "sort.shoot": "Дата EXIF",
@@ -401,10 +452,19 @@ export const translations: Record<Language, TranslationDictionary> = {
     "layout.masonry": "Пинтерест",
     "layout.apple": "Стиль Apple",
     "duplicates.loading": "Ищем дубликаты...",
+    "duplicates.loadingProgress": "Ищем дубликаты... {{completed}}/{{total}}",
+    "duplicates.cancelScan": "Остановить и показать найденное",
     "duplicates.show": "Показать дубликаты",
     "duplicates.hide": "Скрыть дубликаты",
     "duplicates.markNonDuplicates": "Отметить как разные",
+    "duplicates.kind.exact": "Точные копии",
+    "duplicates.kind.perceptual": "Похожие",
+    "duplicates.suggested": "Лучший кадр",
+    "duplicates.keepSuggested": "Оставить лучший кадр",
     "duplicates.none": "Дубликатов не найдено.",
+    "duplicates.ignored.show": "Показать отмеченные как разные ({{count}})",
+    "duplicates.ignored.hide": "Скрыть отмеченные как разные",
+    "duplicates.ignored.restore": "Вернуть",
     "duplicates.deleteAll": "Удалить все дубликаты",
     "duplicates.deleteAllConfirm":
       "Удалить все дубликаты? В каждой группе останется только один файл, остальные будут удалены. Перед продолжением просмотрите дубликаты вручную.",
@@ -468,6 +528,7 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.section.privacy": "Приватность",
     "advanced.section.duplicates": "Дубликаты и хэши",
     "advanced.section.thumbnails": "Превью",
+    "advanced.section.previews": "Анимированные превью",
     "advanced.section.ffmpeg": "FFmpeg",
     "advanced.section.preload": "Предзагрузка",
     "advanced.section.metadata": "Метаданные",
@@ -495,9 +556,18 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.duplicates.useThumbsFirst": "Сначала превью",
     "advanced.field.duplicates.useThumbsFirst.helper":
       "Сначала хэшировать превью, чтобы ускорить поиск дубликатов.",
+    "advanced.field.duplicates.colorHash": "Сравнивать цвета",
+    "advanced.field.duplicates.colorHash.helper":
+      "Дополнительно сравнивать цветовые моменты, чтобы не группировать перекрашенные или отфильтрованные копии.",
+    "advanced.field.duplicates.colorThreshold": "Допуск по цвету",
+    "advanced.field.duplicates.colorThreshold.helper":
+      "Максимальная разница цветовых моментов, при которой изображения считаются дубликатами. Меньшие значения строже разделяют перекрашенные копии.",
     "advanced.field.duplicates.maxPerAlbum": "Максимум файлов на альбом",
     "advanced.field.duplicates.maxPerAlbum.helper":
-      "Максимальное число найденных дубликатов на один альбом.",
+      "Сканировать не больше стольких файлов в альбоме (0 — без ограничения). Большие альбомы сканируются частично.",
+    "advanced.field.duplicates.scanChunkSize": "Размер порции сканирования",
+    "advanced.field.duplicates.scanChunkSize.helper":
+      "Сколько файлов хэшируется за одну порцию при поиске дубликатов. 0 — весь альбом сразу.",
     "advanced.field.thumbs.maxDim": "Максимальная сторона (px)",
     "advanced.field.thumbs.maxDim.helper":
       "Максимальная ширина или высота создаваемых превью.",
@@ -510,9 +580,29 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.thumbs.videoSeek": "Позиция видео (с)",
     "advanced.field.thumbs.videoSeek.helper":
       "Момент времени для превью видео.",
+    "advanced.field.thumbs.posterSamples": "Кадров для обложки",
+    "advanced.field.thumbs.posterSamples.helper":
+      "Сколько кадров сравнивать при выборе обложки видео (1 - брать кадр по позиции).",
+    "advanced.field.thumbs.posterWindow": "Окно поиска обложки (с)",
+    "advanced.field.thumbs.posterWindow.helper":
+      "Длина фрагмента, в котором ищется кадр для обложки.",
     "advanced.field.thumbs.lockPoll": "Период блокировки (мс)",
     "advanced.field.thumbs.lockPoll.helper":
       "Интервал проверки снятия блокировки превью.",
+    "advanced.field.previews.enabled": "Анимированные превью",
+    "advanced.field.previews.enabled.helper":
+      "Создавать короткие зацикленные превью для видео, GIF и анимированных WebP, показываются при наведении.",
+    "advanced.field.previews.duration": "Длительность (с)",
+    "advanced.field.previews.duration.helper": "Длина анимированного превью.",
+    "advanced.field.previews.fps": "Частота кадров",
+    "advanced.field.previews.fps.helper":
+      "Количество кадров в секунду в анимированных превью.",
+    "advanced.field.previews.maxDim": "Максимальная сторона (px)",
+    "advanced.field.previews.maxDim.helper":
+      "Максимальная ширина или высота анимированных превью.",
+    "advanced.field.previews.webpQuality": "Качество WebP",
+    "advanced.field.previews.webpQuality.helper":
+      "Качество анимированных превью в WebP.",
     "advanced.field.ffmpeg.threads": "Потоки",
     "advanced.field.ffmpeg.threads.helper":
       "Число потоков, которое может использовать FFmpeg.",
@@ -546,6 +636,18 @@ export const translations: Record<Language, TranslationDictionary> = {
     "advanced.field.preload.hashRetry": "Повтор при изменении превью",
     "advanced.field.preload.hashRetry.helper":
       "Повторять подсчёт хэша, если превью изменилось во время предзагрузки.",
+    "advanced.field.preload.pauseOnBattery": "Пауза от батареи",
+    "advanced.field.preload.pauseOnBattery.helper":
+      "Останавливать фоновую обработку, пока ноутбук работает от батареи.",
+    "advanced.field.preload.activeMaxWorkers": "Потоки при прокрутке",
+    "advanced.field.preload.activeMaxWorkers.helper":
+      "Сколько фоновых задач может работать во время прокрутки. 0 — пауза.",
+    "advanced.field.preload.activeIdle": "Простой после прокрутки (мс)",
+    "advanced.field.preload.activeIdle.helper":
+      "Через сколько после последней прокрутки возвращаются все потоки.",
+    "advanced.field.preload.cpuLimit": "Лимит CPU (%)",
+    "advanced.field.preload.cpuLimit.helper":
+      "Доля времени, которую каждый поток может быть занят; остальное — отдых.",
     "advanced.field.metadata.probeTimeout": "Таймаут опроса (с)",
     "advanced.field.metadata.probeTimeout.helper":
       "Таймаут извлечения метаданных файла.",
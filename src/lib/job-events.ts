@@ -0,0 +1,20 @@
+"use client";
+
+import { listen } from "@tauri-apps/api/event";
+import type { JobInfo } from "@/lib/types";
+
+export function subscribeJobEvents(onUpdate: (job: JobInfo) => void) {
+  let unlisten: (() => void) | null = null;
+
+  const setup = async () => {
+    unlisten = await listen<JobInfo>("job-updated", (event) => {
+      onUpdate(event.payload);
+    });
+  };
+
+  setup().catch(console.error);
+
+  return () => {
+    unlisten?.();
+  };
+}
@@ -3,7 +3,12 @@ import { clamp } from "@/lib/utils";
 export const HASH_SCHEMA_VERSION = "1";
 
 export type HashSize = "8x8" | "16x16" | "32x32";
-export type HashAlgorithm = "blockhash" | "phash" | "dhash";
+export type HashAlgorithm =
+  | "blockhash"
+  | "phash"
+  | "dhash"
+  | "wavelet"
+  | "combined";
 export type ResizeFilter = "nearest" | "triangle" | "catmullrom" | "lanczos3";
 export type ThreadSetting = number | "auto";
 
@@ -14,16 +19,31 @@ export type DuplicatesSettings = {
   resizeFilter: ResizeFilter;
   useThumbnailsFirst: boolean;
   maxFilesPerAlbum: number;
+  scanChunkSize: number;
+  colorHash: boolean;
+  colorThreshold: number;
 };
 
 export type ThumbnailSettings = {
   maxDim: number;
+  extraSizes: number[];
   imageWebpQuality: number;
   imageWebpCompressionLevel: number;
   videoSeekSeconds: number;
+  posterSamples: number;
+  posterWindowSecs: number;
+  posterMinLuma: number;
   lockPollMs: number;
 };
 
+export type AnimatedPreviewSettings = {
+  enabled: boolean;
+  durationSecs: number;
+  fps: number;
+  maxDim: number;
+  webpQuality: number;
+};
+
 export type FfmpegSettings = {
   threads: ThreadSetting;
   timeoutSecs: number;
@@ -39,6 +59,10 @@ export type PreloadSettings = {
   thumbHashQueueDelayMs: number;
   thumbHashOnlyAfterIdle: boolean;
   thumbHashRetryOnThumbChange: boolean;
+  pauseOnBattery: boolean;
+  activeMaxWorkers: number;
+  activeIdleMs: number;
+  cpuLimitPercent: number;
 };
 
 export type MetadataSettings = {
@@ -57,14 +81,20 @@ export type PrivacySettings = {
   confirmOpenEnabled: boolean;
 };
 
+export type StorageSettings = {
+  externalCache: boolean;
+};
+
 export type AdvancedSettings = {
   duplicates: DuplicatesSettings;
   thumbnails: ThumbnailSettings;
+  previews: AnimatedPreviewSettings;
   ffmpeg: FfmpegSettings;
   preload: PreloadSettings;
   metadata: MetadataSettings;
   album: AlbumSettings;
   privacy: PrivacySettings;
+  storage: StorageSettings;
 };
 
 export const defaultAdvancedSettings: AdvancedSettings = {
@@ -75,14 +105,28 @@ export const defaultAdvancedSettings: AdvancedSettings = {
     resizeFilter: "nearest",
     useThumbnailsFirst: true,
     maxFilesPerAlbum: 0,
+    scanChunkSize: 500,
+    colorHash: false,
+    colorThreshold: 36,
   },
   thumbnails: {
     maxDim: 450,
+    extraSizes: [1024, 2048],
     imageWebpQuality: 75,
     imageWebpCompressionLevel: 3,
     videoSeekSeconds: 1,
+    posterSamples: 12,
+    posterWindowSecs: 5,
+    posterMinLuma: 24,
     lockPollMs: 50,
   },
+  previews: {
+    enabled: false,
+    durationSecs: 3,
+    fps: 8,
+    maxDim: 320,
+    webpQuality: 60,
+  },
   ffmpeg: {
     threads: 4,
     timeoutSecs: 5,
@@ -97,6 +141,10 @@ export const defaultAdvancedSettings: AdvancedSettings = {
     thumbHashQueueDelayMs: 10,
     thumbHashOnlyAfterIdle: true,
     thumbHashRetryOnThumbChange: true,
+    pauseOnBattery: false,
+    activeMaxWorkers: 1,
+    activeIdleMs: 1500,
+    cpuLimitPercent: 100,
   },
   metadata: {
     ffmpegProbeTimeoutSecs: 5,
@@ -111,6 +159,9 @@ export const defaultAdvancedSettings: AdvancedSettings = {
     lockscreenEnabled: false,
     confirmOpenEnabled: false,
   },
+  storage: {
+    externalCache: false,
+  },
 };
 
 const hashSizeToDimensions: Record<
@@ -147,11 +198,13 @@ export function clampAdvancedSettings(
       ...defaultAdvancedSettings.thumbnails,
       ...incoming?.thumbnails,
     },
+    previews: { ...defaultAdvancedSettings.previews, ...incoming?.previews },
     ffmpeg: { ...defaultAdvancedSettings.ffmpeg, ...incoming?.ffmpeg },
     preload: { ...defaultAdvancedSettings.preload, ...incoming?.preload },
     metadata: { ...defaultAdvancedSettings.metadata, ...incoming?.metadata },
     album: { ...defaultAdvancedSettings.album, ...incoming?.album },
     privacy: { ...defaultAdvancedSettings.privacy, ...incoming?.privacy },
+    storage: { ...defaultAdvancedSettings.storage, ...incoming?.storage },
   };
 
   merged.duplicates.threshold = clamp(
@@ -170,10 +223,30 @@ export function clampAdvancedSettings(
     0,
     20_000,
   );
+  merged.duplicates.scanChunkSize = clamp(
+    coerceNumber(
+      merged.duplicates.scanChunkSize,
+      defaultAdvancedSettings.duplicates.scanChunkSize,
+    ),
+    0,
+    20_000,
+  );
+  merged.duplicates.colorThreshold = clamp(
+    coerceNumber(
+      merged.duplicates.colorThreshold,
+      defaultAdvancedSettings.duplicates.colorThreshold,
+    ),
+    0,
+    512,
+  );
   if (!hashSizeToDimensions[merged.duplicates.hashSize]) {
     merged.duplicates.hashSize = defaultAdvancedSettings.duplicates.hashSize;
   }
-  if (!["blockhash", "phash", "dhash"].includes(merged.duplicates.hashAlg)) {
+  if (
+    !["blockhash", "phash", "dhash", "wavelet", "combined"].includes(
+      merged.duplicates.hashAlg,
+    )
+  ) {
     merged.duplicates.hashAlg = defaultAdvancedSettings.duplicates.hashAlg;
   }
   if (
@@ -193,6 +266,18 @@ export function clampAdvancedSettings(
     128,
     2048,
   );
+  merged.thumbnails.extraSizes = Array.from(
+    new Set(
+      (Array.isArray(merged.thumbnails.extraSizes)
+        ? merged.thumbnails.extraSizes
+        : defaultAdvancedSettings.thumbnails.extraSizes
+      )
+        .map((size) => Math.min(coerceNumber(size, 0), 4096))
+        .filter((size) => size > merged.thumbnails.maxDim),
+    ),
+  )
+    .sort((a, b) => a - b)
+    .slice(0, 2);
   merged.thumbnails.imageWebpQuality = clamp(
     coerceNumber(
       merged.thumbnails.imageWebpQuality,
@@ -217,6 +302,34 @@ export function clampAdvancedSettings(
     0,
     30,
   );
+  merged.thumbnails.posterSamples = clamp(
+    Math.round(
+      coerceNumber(
+        merged.thumbnails.posterSamples,
+        defaultAdvancedSettings.thumbnails.posterSamples,
+      ),
+    ),
+    1,
+    60,
+  );
+  merged.thumbnails.posterWindowSecs = clamp(
+    coerceNumber(
+      merged.thumbnails.posterWindowSecs,
+      defaultAdvancedSettings.thumbnails.posterWindowSecs,
+    ),
+    0.5,
+    60,
+  );
+  merged.thumbnails.posterMinLuma = clamp(
+    Math.round(
+      coerceNumber(
+        merged.thumbnails.posterMinLuma,
+        defaultAdvancedSettings.thumbnails.posterMinLuma,
+      ),
+    ),
+    0,
+    128,
+  );
   merged.thumbnails.lockPollMs = clamp(
     coerceNumber(
       merged.thumbnails.lockPollMs,
@@ -226,6 +339,39 @@ export function clampAdvancedSettings(
     250,
   );
 
+  merged.previews.enabled = Boolean(merged.previews.enabled);
+  merged.previews.durationSecs = clamp(
+    coerceNumber(
+      merged.previews.durationSecs,
+      defaultAdvancedSettings.previews.durationSecs,
+    ),
+    1,
+    10,
+  );
+  merged.previews.fps = clamp(
+    Math.round(
+      coerceNumber(merged.previews.fps, defaultAdvancedSettings.previews.fps),
+    ),
+    1,
+    24,
+  );
+  merged.previews.maxDim = clamp(
+    coerceNumber(
+      merged.previews.maxDim,
+      defaultAdvancedSettings.previews.maxDim,
+    ),
+    128,
+    720,
+  );
+  merged.previews.webpQuality = clamp(
+    coerceNumber(
+      merged.previews.webpQuality,
+      defaultAdvancedSettings.previews.webpQuality,
+    ),
+    20,
+    90,
+  );
+
   merged.ffmpeg.threads = clampThreads(merged.ffmpeg.threads);
   merged.ffmpeg.timeoutSecs = clamp(
     coerceNumber(
@@ -286,6 +432,30 @@ export function clampAdvancedSettings(
     0,
     100,
   );
+  merged.preload.activeMaxWorkers = clamp(
+    coerceNumber(
+      merged.preload.activeMaxWorkers,
+      defaultAdvancedSettings.preload.activeMaxWorkers,
+    ),
+    0,
+    32,
+  );
+  merged.preload.activeIdleMs = clamp(
+    coerceNumber(
+      merged.preload.activeIdleMs,
+      defaultAdvancedSettings.preload.activeIdleMs,
+    ),
+    250,
+    10000,
+  );
+  merged.preload.cpuLimitPercent = clamp(
+    coerceNumber(
+      merged.preload.cpuLimitPercent,
+      defaultAdvancedSettings.preload.cpuLimitPercent,
+    ),
+    10,
+    100,
+  );
 
   merged.metadata.ffmpegProbeTimeoutSecs = clamp(
     coerceNumber(
@@ -318,6 +488,8 @@ export function clampAdvancedSettings(
     );
   }
 
+  merged.storage.externalCache = Boolean(merged.storage.externalCache);
+
   return merged;
 }
 
@@ -351,6 +523,7 @@ export function deriveHashVersionSeed(settings: AdvancedSettings): string {
     `${dims.width}x${dims.height}`,
     settings.duplicates.resizeFilter,
     settings.duplicates.useThumbnailsFirst ? "thumb-first" : "original-first",
+    settings.duplicates.colorHash ? "color" : "gray",
     thumbSeed,
   ].join("|");
 }
@@ -5,11 +5,13 @@ import { create } from "zustand";
 import {
   type AdvancedSettings,
   type AlbumSettings,
+  type AnimatedPreviewSettings,
   type DuplicatesSettings,
   type FfmpegSettings,
   type MetadataSettings,
   type PrivacySettings,
   type PreloadSettings,
+  type StorageSettings,
   type ThumbnailSettings,
   clampAdvancedSettings,
   defaultAdvancedSettings,
@@ -51,6 +53,8 @@ const applyPathUpdate = (
         return { ...settings, duplicates: value as DuplicatesSettings };
       case "thumbnails":
         return { ...settings, thumbnails: value as ThumbnailSettings };
+      case "previews":
+        return { ...settings, previews: value as AnimatedPreviewSettings };
       case "ffmpeg":
         return { ...settings, ffmpeg: value as FfmpegSettings };
       case "preload":
@@ -61,6 +65,8 @@ const applyPathUpdate = (
         return { ...settings, album: value as AlbumSettings };
       case "privacy":
         return { ...settings, privacy: value as PrivacySettings };
+      case "storage":
+        return { ...settings, storage: value as StorageSettings };
       default:
         return settings;
     }
@@ -87,6 +93,15 @@ const applyPathUpdate = (
           value,
         ),
       };
+    case "previews":
+      return {
+        ...settings,
+        previews: updateObjectSection<AnimatedPreviewSettings>(
+          settings.previews,
+          fieldKey,
+          value,
+        ),
+      };
     case "ffmpeg":
       return {
         ...settings,
@@ -132,6 +147,15 @@ const applyPathUpdate = (
           value,
         ),
       };
+    case "storage":
+      return {
+        ...settings,
+        storage: updateObjectSection<StorageSettings>(
+          settings.storage,
+          fieldKey,
+          value,
+        ),
+      };
     default:
       return settings;
   }
@@ -10,7 +10,12 @@ import {
 import { getStore } from "@/lib/fs/state";
 import type { MediaEntry } from "@/lib/types";
 import { fetchAlbumDuplicates, loadAlbumMedias } from "@/lib/types/album";
-import type { Album, AlbumId, AlbumNode } from "@/lib/types/album";
+import type {
+  Album,
+  AlbumId,
+  AlbumNode,
+  DuplicateGroup,
+} from "@/lib/types/album";
 import type { AlbumsSlice, CustomStateCreator } from "../types";
 import { toast } from "@/components/toaster";
 
@@ -27,7 +32,7 @@ const applyAggregateSizes = (
 };
 
 const mediaLoadingByPath = new Map<string, Promise<MediaEntry[]>>();
-const duplicatesLoadingByPath = new Map<string, Promise<string[][]>>();
+const duplicatesLoadingByPath = new Map<string, Promise<DuplicateGroup[]>>();
 
 export const albumsSlice: CustomStateCreator<AlbumsSlice> = (set, get) => ({
   rootDir: null,
@@ -42,6 +47,7 @@ export const albumsSlice: CustomStateCreator<AlbumsSlice> = (set, get) => ({
   loadingAlbumId: null,
   duplicatesAvailable: false,
   duplicatesLoading: false,
+  duplicatesScanId: null,
   setDuplicatesAvailable: (duplicatesAvailable) => set({ duplicatesAvailable }),
   setDuplicatesLoading: (duplicatesLoading) => set({ duplicatesLoading }),
   setRootDir: (rootDir) =>
@@ -202,14 +208,22 @@ export const albumsSlice: CustomStateCreator<AlbumsSlice> = (set, get) => ({
 
     const task = (async () => {
       await get().loadAlbumMedia(albumId, options);
-      const duplicates = await fetchAlbumDuplicates(album);
-      set((state) => ({
-        albumDuplicatesByPath: {
-          ...state.albumDuplicatesByPath,
-          [path]: duplicates,
-        },
-      }));
-      return duplicates;
+      const scanId = crypto.randomUUID();
+      set({ duplicatesScanId: scanId });
+      try {
+        const duplicates = await fetchAlbumDuplicates(album, scanId);
+        set((state) => ({
+          albumDuplicatesByPath: {
+            ...state.albumDuplicatesByPath,
+            [path]: duplicates,
+          },
+        }));
+        return duplicates;
+      } finally {
+        if (get().duplicatesScanId === scanId) {
+          set({ duplicatesScanId: null });
+        }
+      }
     })();
 
     duplicatesLoadingByPath.set(path, task);
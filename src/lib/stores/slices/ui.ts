@@ -1,5 +1,6 @@
 import { FAVORITES_ALBUM_ID } from "@/lib/consts";
 import {
+  assertWritable,
   moveMedia,
   setMediaFavorite,
   setMediaTimestamp,
@@ -281,6 +282,7 @@ export const uiSlice: CustomStateCreator<UISlice> = (set, get) => ({
       for (const [albumId, items] of Object.entries(grouped)) {
         const album = state.albumsById[albumId];
         if (!album || album.path === FAVORITES_ALBUM_ID) continue;
+        await assertWritable(album.path);
         for (const media of items) {
           await remove(media.path);
         }
@@ -1,6 +1,11 @@
 import type { StateCreator } from "zustand";
 import type { LayoutType, MediaEntry } from "@/lib/types";
-import type { Album, AlbumId, AlbumNode } from "@/lib/types/album";
+import type {
+  Album,
+  AlbumId,
+  AlbumNode,
+  DuplicateGroup,
+} from "@/lib/types/album";
 
 export type Language = "en" | "ru";
 
@@ -18,7 +23,7 @@ export type AlbumsSlice = {
   albumsReady: boolean;
   albumsById: Record<AlbumId, Album>;
   albumMediasByPath: Record<string, MediaEntry[]>;
-  albumDuplicatesByPath: Record<string, string[][]>;
+  albumDuplicatesByPath: Record<string, DuplicateGroup[]>;
   albumTree: AlbumNode[];
   expandedAlbumIds: Set<AlbumId>;
   manuallyExpandedAlbumIds: Set<AlbumId>;
@@ -26,6 +31,7 @@ export type AlbumsSlice = {
   loadingAlbumId: AlbumId | null;
   duplicatesAvailable: boolean;
   duplicatesLoading: boolean;
+  duplicatesScanId: string | null;
   setDuplicatesAvailable: (duplicatesAvailable: boolean) => void;
   setDuplicatesLoading: (loading: boolean) => void;
   setRootDir: (dir: string | null) => void;
@@ -49,7 +55,7 @@ export type AlbumsSlice = {
   loadAlbumDuplicates: (
     albumId: AlbumId,
     options?: { force?: boolean },
-  ) => Promise<string[][]>;
+  ) => Promise<DuplicateGroup[]>;
   triggerAlbumUpdate: (albumId: AlbumId) => void;
   setActive: (albumId: AlbumId) => Promise<void>;
   autoExpandAncestors: (albumId: AlbumId | null) => void;
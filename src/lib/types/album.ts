@@ -13,8 +13,44 @@ export interface DetachedAlbum {
   size: number;
   relative_path: string;
   parent?: string | null;
+  root_id: string;
 }
 
+export type DuplicateMember = {
+  file: string;
+  width: number | null;
+  height: number | null;
+  size: number;
+  format: string;
+  has_exif_date: boolean;
+  favorite: boolean;
+  sharpness: number | null;
+};
+
+export type SimilarMatch = {
+  path: string;
+  distance: number;
+};
+
+export type IgnoredDuplicateFile = {
+  name: string;
+  path: string;
+  thumb: string | null;
+  exists: boolean;
+};
+
+export type IgnoredDuplicateGroup = {
+  id: string;
+  files: IgnoredDuplicateFile[];
+};
+
+export type DuplicateGroup = {
+  kind: "exact" | "perceptual";
+  files: string[];
+  members: DuplicateMember[];
+  keeper: string | null;
+};
+
 export type AlbumNode = {
   id: AlbumId;
   name: string;
@@ -32,6 +68,7 @@ export class Album {
   thumb: string | null;
   size: number;
   totalSize: number;
+  rootId?: string;
   private sortedCache?: {
     signature: string;
     sourceRef?: MediaEntry[];
@@ -212,10 +249,14 @@ export async function loadAlbumMedias(album: Album): Promise<MediaEntry[]> {
   );
 }
 
-export async function fetchAlbumDuplicates(album: Album): Promise<string[][]> {
+export async function fetchAlbumDuplicates(
+  album: Album,
+  scanId?: string,
+): Promise<DuplicateGroup[]> {
   try {
     return await invoke("find_duplicates", {
       dir: album.path,
+      scanId: scanId ?? null,
     });
   } catch (error) {
     console.error("Failed to load duplicates", error);
@@ -239,6 +280,7 @@ export async function buildAlbum(detached: DetachedAlbum): Promise<Album> {
     detached.size,
     detached.parent ?? undefined,
   );
+  album.rootId = detached.root_id;
   await album.update(detached.size);
   albumsCache.set(detached.path, album);
   return album;
@@ -10,6 +10,7 @@ export type FileMeta = {
 export interface MediaEntry {
   url: string;
   thumb: string;
+  preview?: string;
   meta: FileMeta;
   path: string;
   name: string;
@@ -23,6 +24,9 @@ export interface DetachedMediaEntry {
   meta: string;
   name: string;
   favorite?: boolean;
+  thumb?: string;
+  view?: string;
+  preview?: string;
 }
 
 export interface FavoriteDetachedMediaEntry extends DetachedMediaEntry {
@@ -33,3 +37,67 @@ export interface FavoriteDetachedMediaEntry extends DetachedMediaEntry {
 
 export type LayoutType = "default" | "masonry" | "apple";
 export type OS = "macos" | "other";
+
+export type JobKind =
+  | "preload"
+  | "thumbnails"
+  | "metadata"
+  | "hashes"
+  | "rebuild_thumbnails"
+  | "rebuild_metadata"
+  | "duplicate_scan"
+  | "check_library";
+
+export type JobState =
+  | "queued"
+  | "running"
+  | "paused"
+  | "cancelled"
+  | "failed"
+  | "done";
+
+export interface JobInfo {
+  id: string;
+  kind: JobKind;
+  state: JobState;
+  target: string | null;
+  completed: number;
+  total: number;
+  errors: string[];
+  created_at: number;
+  updated_at: number;
+}
+
+export type FailureKind = "thumbnail" | "metadata" | "hash";
+
+export interface FailureEntry {
+  path: string;
+  album: string;
+  name: string;
+  kind: FailureKind;
+  reason: string | null;
+  at: number | null;
+  attempts: number;
+  retryDue: boolean;
+}
+
+export interface CheckCategory {
+  found: number;
+  fixed: number;
+  samples: string[];
+}
+
+export interface LibraryCheckReport {
+  albums: number;
+  orphanThumbs: CheckCategory;
+  missingEntries: CheckCategory;
+  staleLocks: CheckCategory;
+  legacyMeta: CheckCategory;
+  truncatedThumbs: CheckCategory;
+}
+
+export interface HeicSidecarReport {
+  sidecars: string[];
+  skipped: string[];
+  removed: number;
+}
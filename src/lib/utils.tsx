@@ -224,10 +224,12 @@ export function attachMediaEntry(
   albumId: string,
 ): MediaEntry {
   return {
-    url: convertFileSrc(path.join(albumPath, entry.name)),
+    url: convertFileSrc(entry.view ?? path.join(albumPath, entry.name)),
     thumb: convertFileSrc(
-      path.join(albumPath, ".room237-thumb", `${entry.name}.webp`),
+      entry.thumb ??
+        path.join(albumPath, ".room237-thumb", `${entry.name}.webp`),
     ),
+    preview: entry.preview ? convertFileSrc(entry.preview) : undefined,
     meta: unpackFileMeta(entry.meta),
     name: entry.name,
     path: path.join(albumPath, entry.name),